@@ -0,0 +1,418 @@
+//! A command line tool to inspect and maintain LMDB environments.
+//!
+//! It provides equivalents of the `mdb_stat`, `mdb_dump`, `mdb_load` and `mdb_copy`
+//! LMDB tools along with some commands to read and write raw entries.
+
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process;
+
+use clap::{Args, Parser, Subcommand};
+use heed::types::Bytes;
+use heed::{CompactionOption, Database, Env, EnvFlags, EnvOpenOptions, RoTxn};
+
+#[derive(Debug, Parser)]
+#[command(name = "heed-cli", version, about = "Inspect and maintain LMDB environments")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Show the environment information and the statistics of its databases.
+    Stat {
+        #[command(flatten)]
+        env: EnvArgs,
+    },
+    /// List the named databases of the environment.
+    List {
+        #[command(flatten)]
+        env: EnvArgs,
+    },
+    /// Write the databases in the `mdb_dump` text format.
+    Dump {
+        #[command(flatten)]
+        env: EnvArgs,
+        /// The named databases to dump, all of them are dumped if none is specified.
+        #[arg(short = 's', long = "database")]
+        databases: Vec<String>,
+        /// Dump the unnamed database.
+        #[arg(long)]
+        unnamed: bool,
+        /// The file to write the dump to, defaults to the standard output.
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+    },
+    /// Read databases in the `mdb_dump` text format and write them into the environment.
+    Load {
+        #[command(flatten)]
+        env: EnvArgs,
+        /// The file to read the dump from, defaults to the standard input.
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+    },
+    /// Copy the environment to another location.
+    Copy {
+        #[command(flatten)]
+        env: EnvArgs,
+        /// The directory to copy the environment into, or the file if `--no-sub-dir` is used.
+        destination: PathBuf,
+        /// Omit the free pages and renumber the pages sequentially while copying.
+        #[arg(long)]
+        compact: bool,
+    },
+    /// Clear the stale entries of the reader lock table.
+    CheckReaders {
+        #[command(flatten)]
+        env: EnvArgs,
+    },
+    /// Print the value associated with a key.
+    Get {
+        #[command(flatten)]
+        env: EnvArgs,
+        #[command(flatten)]
+        entry: EntryArgs,
+        key: String,
+    },
+    /// Write a value associated with a key.
+    Put {
+        #[command(flatten)]
+        env: EnvArgs,
+        #[command(flatten)]
+        entry: EntryArgs,
+        key: String,
+        value: String,
+    },
+}
+
+#[derive(Debug, Args)]
+struct EnvArgs {
+    /// The path to the environment.
+    path: PathBuf,
+    /// The environment is a single file and not a directory.
+    #[arg(long)]
+    no_sub_dir: bool,
+    /// The maximum number of named databases that can be opened.
+    #[arg(long, default_value_t = 1024)]
+    max_dbs: u32,
+    /// The size of the memory map in bytes, LMDB uses the size of the environment by default.
+    #[arg(long)]
+    map_size: Option<usize>,
+}
+
+impl EnvArgs {
+    fn open(&self, read_only: bool) -> heed::Result<Env> {
+        let mut options = EnvOpenOptions::new();
+        options.max_dbs(self.max_dbs);
+        if let Some(map_size) = self.map_size {
+            options.map_size(map_size);
+        }
+
+        let mut flags = EnvFlags::empty();
+        flags.set(EnvFlags::NO_SUB_DIR, self.no_sub_dir);
+        flags.set(EnvFlags::READ_ONLY, read_only);
+
+        // safety: The environment is only opened once by this process
+        //         and the flags we use are not unsafe ones.
+        unsafe {
+            options.flags(flags);
+            options.open(&self.path)
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+struct EntryArgs {
+    /// The named database to use, the unnamed database is used by default.
+    #[arg(short = 's', long = "database")]
+    database: Option<String>,
+    /// The keys and values are read and printed in hexadecimal.
+    #[arg(long)]
+    hex: bool,
+}
+
+impl EntryArgs {
+    fn open_database(&self, env: &Env, rtxn: &RoTxn) -> Result<Database<Bytes, Bytes>, String> {
+        match env.open_database(rtxn, self.database.as_deref()) {
+            Ok(Some(database)) => Ok(database),
+            Ok(None) => Err(format!("database {:?} not found", self.database.as_deref().unwrap())),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn parse(&self, input: &str) -> Result<Vec<u8>, String> {
+        if self.hex {
+            unhex(input.as_bytes())
+                .ok_or_else(|| format!("{:?} is not a valid hexadecimal string", input))
+        } else {
+            Ok(input.as_bytes().to_vec())
+        }
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if let Err(e) = run(cli.command) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+fn run(command: Command) -> Result<(), Box<dyn Error>> {
+    match command {
+        Command::Stat { env } => stat(&env.open(true)?),
+        Command::List { env } => {
+            let env = env.open(true)?;
+            let rtxn = env.read_txn()?;
+            let mut stdout = io::stdout().lock();
+            for name in env.database_names(&rtxn)? {
+                writeln!(stdout, "{}", name)?;
+            }
+            Ok(())
+        }
+        Command::Dump { env, databases, unnamed, file } => {
+            let env = env.open(true)?;
+            let rtxn = env.read_txn()?;
+            let writer: Box<dyn Write> = match file {
+                Some(path) => Box::new(File::create(path)?),
+                None => Box::new(io::stdout().lock()),
+            };
+
+            if databases.is_empty() && !unnamed {
+                env.export(&rtxn, writer)?;
+            } else {
+                let mut names: Vec<_> = databases.iter().map(|name| Some(name.as_str())).collect();
+                if unnamed {
+                    names.insert(0, None);
+                }
+                env.export_databases(&rtxn, &names, writer)?;
+            }
+            Ok(())
+        }
+        Command::Load { env, file } => {
+            if !env.no_sub_dir {
+                fs::create_dir_all(&env.path)?;
+            }
+            let env = env.open(false)?;
+            let mut wtxn = env.write_txn()?;
+            match file {
+                Some(path) => env.import(&mut wtxn, File::open(path)?)?,
+                None => env.import(&mut wtxn, io::stdin().lock())?,
+            }
+            wtxn.commit()?;
+            Ok(())
+        }
+        Command::Copy { env: env_args, destination, compact } => {
+            let env = env_args.open(true)?;
+            let option =
+                if compact { CompactionOption::Enabled } else { CompactionOption::Disabled };
+            let path = if env_args.no_sub_dir {
+                destination
+            } else {
+                fs::create_dir_all(&destination)?;
+                destination.join("data.mdb")
+            };
+            env.copy_to_file(path, option)?;
+            Ok(())
+        }
+        Command::CheckReaders { env } => {
+            let env = env.open(false)?;
+            let cleared = env.clear_stale_readers()?;
+            println!("{} stale readers cleared", cleared);
+            Ok(())
+        }
+        Command::Get { env, entry, key } => {
+            let env = env.open(true)?;
+            let rtxn = env.read_txn()?;
+            let database = entry.open_database(&env, &rtxn)?;
+            let key = entry.parse(&key)?;
+            let value = database.get(&rtxn, &key)?.ok_or("key not found")?;
+
+            let mut stdout = io::stdout().lock();
+            if entry.hex {
+                writeln!(stdout, "{}", hex(value))?;
+            } else {
+                stdout.write_all(value)?;
+                writeln!(stdout)?;
+            }
+            Ok(())
+        }
+        Command::Put { env, entry, key, value } => {
+            let env = env.open(false)?;
+            let mut wtxn = env.write_txn()?;
+            let database = entry.open_database(&env, &wtxn)?;
+            let key = entry.parse(&key)?;
+            let value = entry.parse(&value)?;
+            database.put(&mut wtxn, &key, &value)?;
+            wtxn.commit()?;
+            Ok(())
+        }
+    }
+}
+
+fn stat(env: &Env) -> Result<(), Box<dyn Error>> {
+    let info = env.info();
+    // Computing the non-free pages size opens a read transaction by itself.
+    let non_free_pages_size = env.non_free_pages_size()?;
+    let rtxn = env.read_txn()?;
+    let mut stdout = io::stdout().lock();
+
+    writeln!(stdout, "Environment Info")?;
+    writeln!(stdout, "  Path: {}", env.path().display())?;
+    writeln!(stdout, "  Map size: {}", info.map_size)?;
+    writeln!(stdout, "  Real disk size: {}", env.real_disk_size()?)?;
+    writeln!(stdout, "  Non-free pages size: {}", non_free_pages_size)?;
+    writeln!(stdout, "  Last page number: {}", info.last_page_number)?;
+    writeln!(stdout, "  Last transaction ID: {}", info.last_txn_id)?;
+    writeln!(stdout, "  Max readers: {}", info.maximum_number_of_readers)?;
+    writeln!(stdout, "  Number of readers used: {}", info.number_of_readers)?;
+
+    let names = env.database_names(&rtxn)?;
+    let databases = std::iter::once(None).chain(names.iter().map(|name| Some(name.as_str())));
+    for name in databases {
+        let database: Database<Bytes, Bytes> = match env.open_database(&rtxn, name)? {
+            Some(database) => database,
+            None => continue,
+        };
+        let stat = database.stat(&rtxn)?;
+
+        match name {
+            Some(name) => writeln!(stdout, "Status of {}", name)?,
+            None => writeln!(stdout, "Status of Main DB")?,
+        }
+        writeln!(stdout, "  Tree depth: {}", stat.depth)?;
+        writeln!(stdout, "  Branch pages: {}", stat.branch_pages)?;
+        writeln!(stdout, "  Leaf pages: {}", stat.leaf_pages)?;
+        writeln!(stdout, "  Overflow pages: {}", stat.overflow_pages)?;
+        writeln!(stdout, "  Entries: {}", stat.entries)?;
+    }
+
+    Ok(())
+}
+
+/// Formats the given bytes in lowercase hexadecimal.
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Parses bytes written in hexadecimal, returns `None` if the input is not
+/// made of pairs of hexadecimal digits.
+fn unhex(hex: &[u8]) -> Option<Vec<u8>> {
+    let pairs = hex.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return None;
+    }
+    pairs
+        .map(|pair| {
+            let high = char::from(pair[0]).to_digit(16)?;
+            let low = char::from(pair[1]).to_digit(16)?;
+            Some((high << 4 | low) as u8)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_args(args: &[&str]) -> Result<(), Box<dyn Error>> {
+        let cli = Cli::try_parse_from(std::iter::once("heed-cli").chain(args.iter().copied()))?;
+        run(cli.command)
+    }
+
+    fn open_env(path: &std::path::Path) -> Env {
+        let args =
+            EnvArgs { path: path.to_path_buf(), no_sub_dir: false, max_dbs: 1024, map_size: None };
+        args.open(false).unwrap()
+    }
+
+    #[test]
+    fn dump_and_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let env = open_env(dir.path());
+        let mut wtxn = env.write_txn().unwrap();
+        let colors: Database<Bytes, Bytes> =
+            env.create_database(&mut wtxn, Some("colors")).unwrap();
+        colors.put(&mut wtxn, b"red", b"#ff0000").unwrap();
+        colors.put(&mut wtxn, b"green", b"#00ff00").unwrap();
+        let numbers: Database<Bytes, Bytes> =
+            env.create_database(&mut wtxn, Some("numbers")).unwrap();
+        numbers.put(&mut wtxn, &[0, 1], &[255]).unwrap();
+        wtxn.commit().unwrap();
+        env.prepare_for_closing().wait();
+
+        let dump = dir.path().join("dump.txt");
+        let path = dir.path().to_str().unwrap();
+        run_args(&["dump", path, "--file", dump.to_str().unwrap()]).unwrap();
+
+        let other_dir = tempfile::tempdir().unwrap();
+        let other_path = other_dir.path().join("loaded");
+        let other_path = other_path.to_str().unwrap();
+        run_args(&["load", other_path, "--file", dump.to_str().unwrap()]).unwrap();
+
+        let env = open_env(other_path.as_ref());
+        let rtxn = env.read_txn().unwrap();
+        assert_eq!(env.database_names(&rtxn).unwrap(), ["colors", "numbers"]);
+        let colors: Database<Bytes, Bytes> =
+            env.open_database(&rtxn, Some("colors")).unwrap().unwrap();
+        assert_eq!(colors.get(&rtxn, b"red").unwrap(), Some(&b"#ff0000"[..]));
+        assert_eq!(colors.get(&rtxn, b"green").unwrap(), Some(&b"#00ff00"[..]));
+        let numbers: Database<Bytes, Bytes> =
+            env.open_database(&rtxn, Some("numbers")).unwrap().unwrap();
+        assert_eq!(numbers.get(&rtxn, &[0, 1]).unwrap(), Some(&[255][..]));
+    }
+
+    #[test]
+    fn put_and_get_arguments() {
+        let cli =
+            Cli::try_parse_from(["heed-cli", "put", "env", "-s", "db", "--hex", "00ff", "0a"])
+                .unwrap();
+        let Command::Put { env, entry, key, value } = cli.command else {
+            panic!("expected a put command");
+        };
+        assert_eq!(env.path, PathBuf::from("env"));
+        assert_eq!(entry.database.as_deref(), Some("db"));
+        assert_eq!(entry.parse(&key).unwrap(), [0x00, 0xff]);
+        assert_eq!(entry.parse(&value).unwrap(), [0x0a]);
+        assert!(entry.parse("0").is_err());
+        assert!(entry.parse("zz").is_err());
+
+        let cli = Cli::try_parse_from(["heed-cli", "get", "env", "hello"]).unwrap();
+        let Command::Get { entry, key, .. } = cli.command else {
+            panic!("expected a get command");
+        };
+        assert_eq!(entry.database, None);
+        assert_eq!(entry.parse(&key).unwrap(), b"hello");
+
+        assert!(Cli::try_parse_from(["heed-cli", "put", "env", "key"]).is_err());
+    }
+
+    #[test]
+    fn hex_roundtrip() {
+        assert_eq!(hex(&[0, 15, 255]), "000fff");
+        assert_eq!(unhex(b"000fFF"), Some(vec![0, 15, 255]));
+        assert_eq!(unhex(b""), Some(vec![]));
+        assert_eq!(unhex(b"0"), None);
+        assert_eq!(unhex(b"+f"), None);
+    }
+
+    #[test]
+    fn put_then_get() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+        run_args(&["put", path, "--hex", "6b6579", "76616c7565"]).unwrap();
+
+        let env = open_env(dir.path());
+        let rtxn = env.read_txn().unwrap();
+        let db: Database<Bytes, Bytes> = env.open_database(&rtxn, None).unwrap().unwrap();
+        assert_eq!(db.get(&rtxn, b"key").unwrap(), Some(&b"value"[..]));
+        drop(rtxn);
+        env.prepare_for_closing().wait();
+
+        run_args(&["get", path, "key"]).unwrap();
+        assert!(run_args(&["get", path, "missing"]).is_err());
+    }
+}
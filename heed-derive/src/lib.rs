@@ -0,0 +1,753 @@
+#![doc(
+    html_favicon_url = "https://raw.githubusercontent.com/meilisearch/heed/main/assets/heed-pigeon.ico?raw=true"
+)]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/meilisearch/heed/main/assets/heed-pigeon-logo.png?raw=true"
+)]
+
+//! Contains the derive macros of the codecs used with heed.
+//!
+//! The macros are reexported by heed when its `derive` feature is enabled.
+
+#![warn(missing_docs)]
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Expr, Fields, Ident, Type};
+
+/// Derives the [`BytesEncode`] and [`BytesDecode`] traits for a struct or an enum,
+/// the type can then directly be used as the key or data codec of a database.
+///
+/// The fields are encoded one after the other in the order they are declared.
+/// The variants of an enum are encoded with a leading byte that is the index of
+/// the variant, followed by the fields of the variant.
+///
+/// The supported field types and the attributes to choose their encoding are:
+///
+/// - The integers, from `u8` to `u128` and from `i8` to `i128`. They are encoded in an
+///   order-preserving way by default, meaning that the encoded bytes of the integers are
+///   ordered the same way as the integers themselves. The `big_endian`, `little_endian`
+///   and `native_endian` attributes encode them with the given endianness instead.
+/// - The `bool`s, encoded with a single byte.
+/// - The `String`s and `Vec<u8>`s. They are terminated by a nul byte by default, which keeps
+///   them ordered but forbids nul bytes in them. The `length_prefixed` attribute prefixes
+///   them with their length encoded as a big endian `u32` instead.
+/// - The fixed-size arrays of integers and `bool`s, encoded element by element.
+///   The integer attributes apply to the elements of the array.
+/// - Any other type that derives `HeedCodec`.
+///
+/// The `prefix` attribute can be put on the leading fields of a struct to generate a prefix
+/// codec named after the type followed by `Prefix`, e.g. `LogKeyPrefix` for a `LogKey` struct.
+/// It encodes a tuple of the prefix fields, or the field itself if there is only one, and
+/// decodes the whole type, which makes it usable with [`Database::remap_key_type`] and
+/// [`Database::prefix_iter`].
+///
+/// ```
+/// use heed::types::Str;
+/// use heed::{Database, EnvOpenOptions, HeedCodec};
+///
+/// #[derive(Debug, PartialEq, Eq, HeedCodec)]
+/// enum Level {
+///     Debug,
+///     Warn,
+///     Error,
+/// }
+///
+/// #[derive(Debug, PartialEq, Eq, HeedCodec)]
+/// struct LogKey {
+///     #[heed(prefix)]
+///     service: String,
+///     #[heed(prefix)]
+///     timestamp: i64,
+///     level: Level,
+/// }
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let dir = tempfile::tempdir()?;
+/// # let env = unsafe { EnvOpenOptions::new().open(dir.path())? };
+/// let mut wtxn = env.write_txn()?;
+/// let db: Database<LogKey, Str> = env.create_database(&mut wtxn, None)?;
+///
+/// let key = LogKey { service: "api".to_string(), timestamp: -12, level: Level::Warn };
+/// db.put(&mut wtxn, &key, "the clock is late")?;
+/// let key = LogKey { service: "api".to_string(), timestamp: 42, level: Level::Debug };
+/// db.put(&mut wtxn, &key, "fibonacci was executed in 21ms")?;
+/// let key = LogKey { service: "auth".to_string(), timestamp: 42, level: Level::Error };
+/// db.put(&mut wtxn, &key, "invalid token")?;
+///
+/// let mut iter = db.iter(&wtxn)?;
+/// assert_eq!(iter.next().transpose()?.map(|(key, _)| key.timestamp), Some(-12));
+/// drop(iter);
+///
+/// let prefix = ("api".to_string(), 42);
+/// let mut iter = db.remap_key_type::<LogKeyPrefix>().prefix_iter(&wtxn, &prefix)?;
+/// let (key, log) = iter.next().transpose()?.unwrap();
+/// assert_eq!(key.level, Level::Debug);
+/// assert_eq!(log, "fibonacci was executed in 21ms");
+/// assert!(iter.next().is_none());
+/// # drop(iter);
+///
+/// wtxn.commit()?;
+/// # Ok(()) }
+/// ```
+///
+/// [`BytesEncode`]: https://docs.rs/heed/latest/heed/trait.BytesEncode.html
+/// [`BytesDecode`]: https://docs.rs/heed/latest/heed/trait.BytesDecode.html
+/// [`Database::remap_key_type`]: https://docs.rs/heed/latest/heed/struct.Database.html#method.remap_key_type
+/// [`Database::prefix_iter`]: https://docs.rs/heed/latest/heed/struct.Database.html#method.prefix_iter
+#[proc_macro_derive(HeedCodec, attributes(heed))]
+pub fn derive_heed_codec(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "HeedCodec cannot be derived for generic types",
+        ));
+    }
+
+    let name = &input.ident;
+    let (encode_body, decode_body, prefix) = match &input.data {
+        Data::Struct(data) => {
+            let mut fields = parse_fields(&data.fields)?;
+            let prefix_len = prefix_len(&fields)?;
+            let pattern = pattern(quote!(#name), &data.fields, &fields);
+            let encode = fields.iter().map(|field| field.encoding.encode(&field.binding));
+            let decode = fields.iter().map(|field| field.encoding.decode(&field.binding));
+            let encode_body = quote! {
+                let #pattern = self;
+                #(#encode)*
+            };
+            let decode_body = quote! {
+                #(#decode)*
+                ::std::result::Result::Ok(#pattern)
+            };
+            fields.truncate(prefix_len);
+            (encode_body, decode_body, fields)
+        }
+        Data::Enum(data) => {
+            if data.variants.len() > 256 {
+                return Err(syn::Error::new(
+                    data.variants.span(),
+                    "HeedCodec supports at most 256 variants",
+                ));
+            }
+
+            let mut encode_arms = Vec::new();
+            let mut decode_arms = Vec::new();
+            for (index, variant) in data.variants.iter().enumerate() {
+                let tag = index as u8;
+                let variant_name = &variant.ident;
+                let fields = parse_fields(&variant.fields)?;
+                if let Some(field) = fields.iter().find(|field| field.prefix) {
+                    return Err(syn::Error::new(
+                        field.span,
+                        "the prefix attribute is only supported on the fields of a struct",
+                    ));
+                }
+                let pattern = pattern(quote!(#name::#variant_name), &variant.fields, &fields);
+                let encode = fields.iter().map(|field| field.encoding.encode(&field.binding));
+                let decode = fields.iter().map(|field| field.encoding.decode(&field.binding));
+                encode_arms.push(quote! {
+                    #pattern => {
+                        __writer.write_all(&[#tag])?;
+                        #(#encode)*
+                    }
+                });
+                decode_arms.push(quote! {
+                    #tag => {
+                        #(#decode)*
+                        ::std::result::Result::Ok(#pattern)
+                    }
+                });
+            }
+
+            let encode_body = quote! {
+                match self {
+                    #(#encode_arms)*
+                }
+            };
+            let decode_body = quote! {
+                match __take(__bytes, 1)?[0] {
+                    #(#decode_arms)*
+                    tag => ::std::result::Result::Err(::std::format!(
+                        "invalid {}: unknown variant tag {}",
+                        ::std::stringify!(#name),
+                        tag,
+                    ).into()),
+                }
+            };
+            (encode_body, decode_body, Vec::new())
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span,
+                "HeedCodec cannot be derived for unions",
+            ))
+        }
+    };
+
+    let (prefix_struct, prefix_impl) = match prefix.as_slice() {
+        [] => (TokenStream2::new(), TokenStream2::new()),
+        fields => {
+            let vis = &input.vis;
+            let prefix_name = format_ident!("{}Prefix", name);
+            let doc = format!(
+                "The prefix codec of [`{name}`], encodes its leading fields to iterate over the \
+                 entries that start with them.",
+            );
+            let types: Vec<_> = fields.iter().map(|field| &field.ty).collect();
+            let bindings: Vec<_> = fields.iter().map(|field| &field.binding).collect();
+            let (item, pattern) = match (types.as_slice(), bindings.as_slice()) {
+                ([ty], [binding]) => (quote!(#ty), quote!(#binding)),
+                _ => (quote!((#(#types,)*)), quote!((#(#bindings,)*))),
+            };
+            let encode = fields.iter().map(|field| field.encoding.encode(&field.binding));
+
+            let prefix_struct = quote! {
+                #[doc = #doc]
+                #vis enum #prefix_name {}
+            };
+            let prefix_impl = quote! {
+                #[automatically_derived]
+                impl<'a> ::heed::BytesEncode<'a> for #prefix_name {
+                    type EItem = #item;
+                    type Error = ::heed::BoxedError;
+
+                    fn bytes_encode(
+                        item: &'a Self::EItem,
+                    ) -> ::std::result::Result<::std::borrow::Cow<'a, [u8]>, ::heed::BoxedError> {
+                        let mut __output = ::std::vec::Vec::new();
+                        let __writer = &mut __output;
+                        let #pattern = item;
+                        #(#encode)*
+                        ::std::result::Result::Ok(::std::borrow::Cow::Owned(__output))
+                    }
+                }
+
+                #[automatically_derived]
+                impl<'a> ::heed::BytesDecode<'a> for #prefix_name {
+                    type DItem = #name;
+                    type Error = ::heed::BoxedError;
+
+                    fn bytes_decode(bytes: &'a [u8]) -> ::std::result::Result<#name, ::heed::BoxedError> {
+                        <#name as ::heed::BytesDecode<'a>>::bytes_decode(bytes)
+                    }
+                }
+            };
+            (prefix_struct, prefix_impl)
+        }
+    };
+
+    Ok(quote! {
+        #prefix_struct
+
+        const _: () = {
+            #[allow(unused_imports)]
+            use ::std::io::Write as _;
+
+            #[allow(dead_code)]
+            fn __take<'b>(
+                bytes: &mut &'b [u8],
+                len: usize,
+            ) -> ::std::result::Result<&'b [u8], ::heed::BoxedError> {
+                if bytes.len() < len {
+                    return ::std::result::Result::Err(::std::format!(
+                        "invalid {}: unexpected end of bytes",
+                        ::std::stringify!(#name),
+                    ).into());
+                }
+                let (head, tail) = bytes.split_at(len);
+                *bytes = tail;
+                ::std::result::Result::Ok(head)
+            }
+
+            #[allow(dead_code)]
+            fn __take_terminated<'b>(
+                bytes: &mut &'b [u8],
+            ) -> ::std::result::Result<&'b [u8], ::heed::BoxedError> {
+                match bytes.iter().position(|byte| *byte == 0) {
+                    ::std::option::Option::Some(len) => {
+                        let head = __take(bytes, len)?;
+                        __take(bytes, 1)?;
+                        ::std::result::Result::Ok(head)
+                    }
+                    ::std::option::Option::None => ::std::result::Result::Err(::std::format!(
+                        "invalid {}: missing the nul byte terminating a string",
+                        ::std::stringify!(#name),
+                    ).into()),
+                }
+            }
+
+            #[automatically_derived]
+            impl #name {
+                #[doc(hidden)]
+                pub fn __heed_encode<W: ::std::io::Write>(
+                    &self,
+                    __writer: &mut W,
+                ) -> ::std::result::Result<(), ::heed::BoxedError> {
+                    #encode_body
+                    ::std::result::Result::Ok(())
+                }
+
+                #[doc(hidden)]
+                pub fn __heed_decode(
+                    __bytes: &mut &[u8],
+                ) -> ::std::result::Result<Self, ::heed::BoxedError> {
+                    #decode_body
+                }
+            }
+
+            #[automatically_derived]
+            impl<'a> ::heed::BytesEncode<'a> for #name {
+                type EItem = Self;
+                type Error = ::heed::BoxedError;
+
+                fn bytes_encode(
+                    item: &'a #name,
+                ) -> ::std::result::Result<::std::borrow::Cow<'a, [u8]>, ::heed::BoxedError> {
+                    let mut output = ::std::vec::Vec::new();
+                    item.__heed_encode(&mut output)?;
+                    ::std::result::Result::Ok(::std::borrow::Cow::Owned(output))
+                }
+
+                fn encodes_into_writer(_item: &#name) -> bool {
+                    true
+                }
+
+                fn bytes_encode_into_writer<W: ::std::io::Write>(
+                    item: &'a #name,
+                    mut writer: W,
+                ) -> ::std::result::Result<(), ::heed::EncodeIntoWriterError<::heed::BoxedError>> {
+                    item.__heed_encode(&mut writer).map_err(::heed::EncodeIntoWriterError::Encoding)
+                }
+            }
+
+            #[automatically_derived]
+            impl<'a> ::heed::BytesDecode<'a> for #name {
+                type DItem = Self;
+                type Error = ::heed::BoxedError;
+
+                fn bytes_decode(mut bytes: &'a [u8]) -> ::std::result::Result<#name, ::heed::BoxedError> {
+                    let item = Self::__heed_decode(&mut bytes)?;
+                    if bytes.is_empty() {
+                        ::std::result::Result::Ok(item)
+                    } else {
+                        ::std::result::Result::Err(::std::format!(
+                            "invalid {}: {} trailing bytes",
+                            ::std::stringify!(#name),
+                            bytes.len(),
+                        ).into())
+                    }
+                }
+            }
+
+            #prefix_impl
+        };
+    })
+}
+
+/// A field of a struct or of an enum variant.
+struct Field {
+    /// The name of the field, `None` for the fields of a tuple struct.
+    ident: Option<Ident>,
+    /// The variable the field is bound to in the generated code.
+    binding: Ident,
+    ty: Type,
+    encoding: Encoding,
+    prefix: bool,
+    span: proc_macro2::Span,
+}
+
+fn parse_fields(fields: &Fields) -> syn::Result<Vec<Field>> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let attributes = Attributes::parse(field)?;
+            let encoding = Encoding::new(&field.ty, &attributes)?;
+            Ok(Field {
+                ident: field.ident.clone(),
+                binding: format_ident!("__field{}", index),
+                ty: field.ty.clone(),
+                encoding,
+                prefix: attributes.prefix,
+                span: field.span(),
+            })
+        })
+        .collect()
+}
+
+/// Returns the number of fields of the prefix codec, ensuring that they are the leading ones.
+fn prefix_len(fields: &[Field]) -> syn::Result<usize> {
+    let len = fields.iter().take_while(|field| field.prefix).count();
+    if let Some(field) = fields[len..].iter().find(|field| field.prefix) {
+        return Err(syn::Error::new(
+            field.span,
+            "the prefix attribute must only be put on the leading fields",
+        ));
+    }
+    Ok(len)
+}
+
+/// Generates a pattern that binds the fields to their variables, used to both
+/// destructure and construct the type.
+fn pattern(path: TokenStream2, kind: &Fields, fields: &[Field]) -> TokenStream2 {
+    let bindings = fields.iter().map(|field| &field.binding);
+    match kind {
+        Fields::Named(_) => {
+            let idents = fields.iter().map(|field| &field.ident);
+            quote!(#path { #(#idents: #bindings),* })
+        }
+        Fields::Unnamed(_) => quote!(#path(#(#bindings),*)),
+        Fields::Unit => path,
+    }
+}
+
+/// The `#[heed(...)]` attributes of a field.
+#[derive(Default)]
+struct Attributes {
+    integer: Option<(IntegerOrder, proc_macro2::Span)>,
+    string: Option<(StringDelimiter, proc_macro2::Span)>,
+    prefix: bool,
+}
+
+impl Attributes {
+    fn parse(field: &syn::Field) -> syn::Result<Attributes> {
+        let mut attributes = Attributes::default();
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("heed")) {
+            attr.parse_nested_meta(|meta| {
+                let integer = match meta.path.get_ident().map(Ident::to_string).as_deref() {
+                    Some("ordered") => Some(IntegerOrder::Ordered),
+                    Some("big_endian") => Some(IntegerOrder::BigEndian),
+                    Some("little_endian") => Some(IntegerOrder::LittleEndian),
+                    Some("native_endian") => Some(IntegerOrder::NativeEndian),
+                    _ => None,
+                };
+                let string = match meta.path.get_ident().map(Ident::to_string).as_deref() {
+                    Some("terminated") => Some(StringDelimiter::Terminated),
+                    Some("length_prefixed") => Some(StringDelimiter::LengthPrefixed),
+                    _ => None,
+                };
+
+                if let Some(order) = integer {
+                    if attributes.integer.replace((order, meta.path.span())).is_some() {
+                        return Err(meta.error("the integer encoding is specified twice"));
+                    }
+                } else if let Some(delimiter) = string {
+                    if attributes.string.replace((delimiter, meta.path.span())).is_some() {
+                        return Err(meta.error("the string encoding is specified twice"));
+                    }
+                } else if meta.path.is_ident("prefix") {
+                    attributes.prefix = true;
+                } else {
+                    return Err(meta.error("unsupported heed attribute"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(attributes)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum IntegerOrder {
+    Ordered,
+    BigEndian,
+    LittleEndian,
+    NativeEndian,
+}
+
+#[derive(Clone, Copy)]
+enum StringDelimiter {
+    Terminated,
+    LengthPrefixed,
+}
+
+/// How a field is encoded.
+enum Encoding {
+    Integer { ty: Ident, order: IntegerOrder },
+    Bool,
+    Bytes { string: bool, delimiter: StringDelimiter },
+    Array { elem: Box<Encoding>, elem_ty: Type, len: Expr },
+    Nested(Type),
+}
+
+impl Encoding {
+    fn new(ty: &Type, attributes: &Attributes) -> syn::Result<Encoding> {
+        let encoding = Encoding::from_type(ty, attributes)?;
+
+        let (integer, string) = match &encoding {
+            Encoding::Integer { .. } => (true, false),
+            Encoding::Array { elem, .. } => (matches!(**elem, Encoding::Integer { .. }), false),
+            Encoding::Bytes { .. } => (false, true),
+            Encoding::Bool | Encoding::Nested(_) => (false, false),
+        };
+        if let (Some((_, span)), false) = (attributes.integer, integer) {
+            return Err(syn::Error::new(span, "this attribute is only supported on integers"));
+        }
+        if let (Some((_, span)), false) = (attributes.string, string) {
+            return Err(syn::Error::new(
+                span,
+                "this attribute is only supported on strings and vectors of bytes",
+            ));
+        }
+
+        Ok(encoding)
+    }
+
+    fn from_type(ty: &Type, attributes: &Attributes) -> syn::Result<Encoding> {
+        match ty {
+            Type::Path(path) if path.qself.is_none() => {
+                let segment = path.path.segments.last().unwrap();
+                let ident = segment.ident.to_string();
+                match ident.as_str() {
+                    "u8" | "u16" | "u32" | "u64" | "u128" | "i8" | "i16" | "i32" | "i64"
+                    | "i128"
+                        if segment.arguments.is_none() =>
+                    {
+                        let order = attributes.integer.map_or(IntegerOrder::Ordered, |(o, _)| o);
+                        Ok(Encoding::Integer { ty: segment.ident.clone(), order })
+                    }
+                    "usize" | "isize" => Err(syn::Error::new(
+                        ty.span(),
+                        "the pointer-sized integers are not portable, use a fixed-size integer",
+                    )),
+                    "bool" if segment.arguments.is_none() => Ok(Encoding::Bool),
+                    "String" if segment.arguments.is_none() => Ok(Encoding::Bytes {
+                        string: true,
+                        delimiter: attributes
+                            .string
+                            .map_or(StringDelimiter::Terminated, |(delimiter, _)| delimiter),
+                    }),
+                    "Vec" if is_vec_of_bytes(&segment.arguments) => Ok(Encoding::Bytes {
+                        string: false,
+                        delimiter: attributes
+                            .string
+                            .map_or(StringDelimiter::Terminated, |(delimiter, _)| delimiter),
+                    }),
+                    _ => Ok(Encoding::Nested(ty.clone())),
+                }
+            }
+            Type::Array(array) => {
+                let elem = Encoding::from_type(&array.elem, attributes)?;
+                match elem {
+                    Encoding::Integer { .. } | Encoding::Bool => Ok(Encoding::Array {
+                        elem: Box::new(elem),
+                        elem_ty: (*array.elem).clone(),
+                        len: array.len.clone(),
+                    }),
+                    _ => Err(syn::Error::new(
+                        array.elem.span(),
+                        "only the arrays of integers and booleans are supported",
+                    )),
+                }
+            }
+            Type::Group(group) => Encoding::from_type(&group.elem, attributes),
+            _ => Err(syn::Error::new(ty.span(), "unsupported field type")),
+        }
+    }
+
+    /// Generates the code that writes the field, bound to a reference, into `__writer`.
+    fn encode(&self, value: &Ident) -> TokenStream2 {
+        match self {
+            Encoding::Integer { ty, order } => {
+                let bytes = match order {
+                    IntegerOrder::Ordered => match unsigned_of(ty) {
+                        Some(unsigned) => quote! {
+                            (*#value as #unsigned ^ !(#unsigned::MAX >> 1)).to_be_bytes()
+                        },
+                        None => quote!(#value.to_be_bytes()),
+                    },
+                    IntegerOrder::BigEndian => quote!(#value.to_be_bytes()),
+                    IntegerOrder::LittleEndian => quote!(#value.to_le_bytes()),
+                    IntegerOrder::NativeEndian => quote!(#value.to_ne_bytes()),
+                };
+                quote!(__writer.write_all(&#bytes)?;)
+            }
+            Encoding::Bool => quote!(__writer.write_all(&[*#value as u8])?;),
+            Encoding::Bytes { string: _, delimiter: StringDelimiter::Terminated } => quote! {
+                let __value: &[u8] = ::std::convert::AsRef::as_ref(#value);
+                if __value.contains(&0) {
+                    return ::std::result::Result::Err(
+                        "a terminated string cannot contain a nul byte".into(),
+                    );
+                }
+                __writer.write_all(__value)?;
+                __writer.write_all(&[0])?;
+            },
+            Encoding::Bytes { string: _, delimiter: StringDelimiter::LengthPrefixed } => quote! {
+                let __value: &[u8] = ::std::convert::AsRef::as_ref(#value);
+                __writer.write_all(&u32::try_from(__value.len())?.to_be_bytes())?;
+                __writer.write_all(__value)?;
+            },
+            Encoding::Array { elem, .. } => {
+                let element = format_ident!("__element");
+                let encode = elem.encode(&element);
+                quote! {
+                    for #element in #value.iter() {
+                        #encode
+                    }
+                }
+            }
+            Encoding::Nested(ty) => quote!(<#ty>::__heed_encode(#value, __writer)?;),
+        }
+    }
+
+    /// Generates the code that reads the field from `__bytes` into a variable.
+    fn decode(&self, binding: &Ident) -> TokenStream2 {
+        let value = match self {
+            Encoding::Integer { ty, order } => {
+                let size = quote!(::std::mem::size_of::<#ty>());
+                let array = quote!(__take(__bytes, #size)?.try_into().unwrap());
+                match order {
+                    IntegerOrder::Ordered => match unsigned_of(ty) {
+                        Some(unsigned) => quote! {
+                            (#unsigned::from_be_bytes(#array) ^ !(#unsigned::MAX >> 1)) as #ty
+                        },
+                        None => quote!(#ty::from_be_bytes(#array)),
+                    },
+                    IntegerOrder::BigEndian => quote!(#ty::from_be_bytes(#array)),
+                    IntegerOrder::LittleEndian => quote!(#ty::from_le_bytes(#array)),
+                    IntegerOrder::NativeEndian => quote!(#ty::from_ne_bytes(#array)),
+                }
+            }
+            Encoding::Bool => quote! {
+                match __take(__bytes, 1)?[0] {
+                    0 => false,
+                    1 => true,
+                    byte => {
+                        return ::std::result::Result::Err(
+                            ::std::format!("invalid boolean byte {}", byte).into(),
+                        )
+                    }
+                }
+            },
+            Encoding::Bytes { string, delimiter } => {
+                let bytes = match delimiter {
+                    StringDelimiter::Terminated => quote!(__take_terminated(__bytes)?),
+                    StringDelimiter::LengthPrefixed => quote! {{
+                        let __len = u32::from_be_bytes(__take(__bytes, 4)?.try_into().unwrap());
+                        __take(__bytes, usize::try_from(__len)?)?
+                    }},
+                };
+                if *string {
+                    quote!(::std::str::from_utf8(#bytes)?.to_owned())
+                } else {
+                    quote!(#bytes.to_vec())
+                }
+            }
+            Encoding::Array { elem, elem_ty, len } => {
+                let element = format_ident!("__element");
+                let decode = elem.decode(&element);
+                quote! {{
+                    let mut __array = [<#elem_ty as ::std::default::Default>::default(); #len];
+                    for __slot in __array.iter_mut() {
+                        #decode
+                        *__slot = #element;
+                    }
+                    __array
+                }}
+            }
+            Encoding::Nested(ty) => quote!(<#ty>::__heed_decode(__bytes)?),
+        };
+        quote!(let #binding = #value;)
+    }
+}
+
+fn is_vec_of_bytes(arguments: &syn::PathArguments) -> bool {
+    match arguments {
+        syn::PathArguments::AngleBracketed(arguments) => match arguments.args.first() {
+            Some(syn::GenericArgument::Type(Type::Path(path))) if arguments.args.len() == 1 => {
+                path.path.is_ident("u8")
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Returns the unsigned counterpart of a signed integer, `None` if it is already unsigned.
+fn unsigned_of(ty: &Ident) -> Option<Ident> {
+    let unsigned = match ty.to_string().as_str() {
+        "i8" => "u8",
+        "i16" => "u16",
+        "i32" => "u32",
+        "i64" => "u64",
+        "i128" => "u128",
+        _ => return None,
+    };
+    Some(Ident::new(unsigned, ty.span()))
+}
+
+/// The invalid uses of the derive macro, they must fail to compile.
+///
+/// The `prefix` attribute must only be put on the leading fields of a struct:
+///
+/// ```compile_fail
+/// #[derive(heed::HeedCodec)]
+/// struct Key {
+///     id: u32,
+///     #[heed(prefix)]
+///     name: String,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// #[derive(heed::HeedCodec)]
+/// enum Key {
+///     Id(#[heed(prefix)] u32),
+/// }
+/// ```
+///
+/// The encoding attributes must match the type of the field and be given once:
+///
+/// ```compile_fail
+/// #[derive(heed::HeedCodec)]
+/// struct Key(#[heed(big_endian)] String);
+/// ```
+///
+/// ```compile_fail
+/// #[derive(heed::HeedCodec)]
+/// struct Key(#[heed(length_prefixed)] u32);
+/// ```
+///
+/// ```compile_fail
+/// #[derive(heed::HeedCodec)]
+/// struct Key(#[heed(big_endian, little_endian)] u32);
+/// ```
+///
+/// ```compile_fail
+/// #[derive(heed::HeedCodec)]
+/// struct Key(#[heed(reversed)] u32);
+/// ```
+///
+/// The field types must have a portable encoding:
+///
+/// ```compile_fail
+/// #[derive(heed::HeedCodec)]
+/// struct Key(usize);
+/// ```
+///
+/// ```compile_fail
+/// #[derive(heed::HeedCodec)]
+/// struct Key([String; 2]);
+/// ```
+///
+/// ```compile_fail
+/// #[derive(heed::HeedCodec)]
+/// struct Key((u32, u32));
+/// ```
+///
+/// ```compile_fail
+/// #[derive(heed::HeedCodec)]
+/// struct Key<T>(T);
+/// ```
+#[cfg(doctest)]
+#[allow(dead_code)]
+struct CompileFailTests;
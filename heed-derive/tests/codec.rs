@@ -0,0 +1,196 @@
+use std::fmt::Debug;
+
+use heed::{BoxedError, BytesDecode, BytesEncode, HeedCodec};
+
+fn encode<T>(item: &T) -> Vec<u8>
+where
+    T: for<'a> BytesEncode<'a, EItem = T, Error = BoxedError>,
+{
+    let bytes = T::bytes_encode(item).unwrap().into_owned();
+    let mut written = Vec::new();
+    T::bytes_encode_into_writer(item, &mut written).unwrap();
+    assert_eq!(bytes, written);
+    bytes
+}
+
+fn roundtrip<T>(item: &T) -> Vec<u8>
+where
+    T: for<'a> BytesEncode<'a, EItem = T, Error = BoxedError>
+        + for<'a> BytesDecode<'a, DItem = T, Error = BoxedError>
+        + Debug
+        + PartialEq,
+{
+    let bytes = encode(item);
+    assert_eq!(&T::bytes_decode(&bytes).unwrap(), item);
+    bytes
+}
+
+fn decoding_error<T>(bytes: &[u8]) -> String
+where
+    T: for<'a> BytesDecode<'a, DItem = T, Error = BoxedError> + Debug,
+{
+    T::bytes_decode(bytes).unwrap_err().to_string()
+}
+
+#[derive(Debug, PartialEq, HeedCodec)]
+struct Integers {
+    ordered: i16,
+    #[heed(ordered)]
+    explicit: u16,
+    #[heed(big_endian)]
+    big: i16,
+    #[heed(little_endian)]
+    little: u32,
+    #[heed(native_endian)]
+    native: i64,
+}
+
+#[test]
+fn integer_attributes() {
+    let item = Integers { ordered: -2, explicit: 2, big: -2, little: 2, native: -2 };
+    let bytes = roundtrip(&item);
+    let mut expected = vec![0x7F, 0xFE, 0x00, 0x02, 0xFF, 0xFE, 0x02, 0x00, 0x00, 0x00];
+    expected.extend_from_slice(&(-2i64).to_ne_bytes());
+    assert_eq!(bytes, expected);
+}
+
+#[derive(Debug, PartialEq, HeedCodec)]
+struct Strings {
+    name: String,
+    #[heed(terminated)]
+    explicit: Vec<u8>,
+    #[heed(length_prefixed)]
+    raw: Vec<u8>,
+    #[heed(length_prefixed)]
+    text: String,
+}
+
+#[test]
+fn string_attributes() {
+    let item =
+        Strings { name: "ab".into(), explicit: vec![1], raw: vec![0, 1], text: "a\0".into() };
+    let bytes = roundtrip(&item);
+    assert_eq!(bytes, [b'a', b'b', 0, 1, 0, 0, 0, 0, 2, 0, 1, 0, 0, 0, 2, b'a', 0]);
+
+    // The terminated strings cannot contain a nul byte.
+    let item = Strings { name: "a\0".into(), explicit: vec![], raw: vec![], text: String::new() };
+    let error = Strings::bytes_encode(&item).unwrap_err();
+    assert_eq!(error.to_string(), "a terminated string cannot contain a nul byte");
+}
+
+#[derive(Debug, PartialEq, HeedCodec)]
+struct Arrays {
+    flags: [bool; 2],
+    ordered: [i8; 2],
+    #[heed(little_endian)]
+    little: [u16; 2],
+}
+
+#[test]
+fn arrays_and_booleans() {
+    let item = Arrays { flags: [true, false], ordered: [-1, 1], little: [1, 256] };
+    let bytes = roundtrip(&item);
+    assert_eq!(bytes, [1, 0, 0x7F, 0x81, 1, 0, 0, 1]);
+
+    assert_eq!(decoding_error::<Arrays>(&[2, 0, 0, 0, 0, 0, 0, 0]), "invalid boolean byte 2");
+}
+
+#[derive(Debug, Clone, PartialEq, PartialOrd, HeedCodec)]
+enum Kind {
+    Login,
+    Purchase { amount: u64, refunded: bool },
+    Tagged(#[heed(length_prefixed)] Vec<u8>, [i16; 2]),
+}
+
+#[derive(Debug, Clone, PartialEq, PartialOrd, HeedCodec)]
+struct Event {
+    #[heed(prefix)]
+    tenant: i32,
+    #[heed(prefix)]
+    name: String,
+    kind: Kind,
+    #[heed(little_endian)]
+    sequence: u32,
+}
+
+#[derive(Debug, PartialEq, HeedCodec)]
+struct Tenant(#[heed(prefix)] u8, String);
+
+#[derive(Debug, PartialEq, HeedCodec)]
+struct Marker;
+
+#[test]
+fn enums_are_tagged_by_variant_index() {
+    assert_eq!(roundtrip(&Kind::Login), [0]);
+    assert_eq!(
+        roundtrip(&Kind::Purchase { amount: 3, refunded: true }),
+        [1, 0, 0, 0, 0, 0, 0, 0, 3, 1]
+    );
+    assert_eq!(roundtrip(&Kind::Tagged(vec![7], [-1, 1])), [2, 0, 0, 0, 1, 7, 0x7F, 0xFF, 0x80, 1]);
+    assert!(roundtrip(&Marker).is_empty());
+
+    assert_eq!(decoding_error::<Kind>(&[3]), "invalid Kind: unknown variant tag 3");
+}
+
+#[test]
+fn byte_order_follows_derived_order() {
+    let event = |tenant, name: &str, kind| Event { tenant, name: name.into(), kind, sequence: 7 };
+    let mut events = vec![
+        event(1, "b", Kind::Purchase { amount: 12, refunded: false }),
+        event(-4, "z", Kind::Login),
+        event(1, "a", Kind::Purchase { amount: 3, refunded: true }),
+        event(1, "a", Kind::Purchase { amount: 3, refunded: false }),
+        event(1, "a", Kind::Tagged(vec![0, 1], [-2, 5])),
+        event(1, "a", Kind::Tagged(vec![0, 1], [-3, 5])),
+        event(1, "ab", Kind::Login),
+        event(1, "", Kind::Login),
+        event(i32::MIN, "a", Kind::Login),
+        event(i32::MAX, "a", Kind::Login),
+    ];
+    events.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let encoded: Vec<_> = events.iter().map(roundtrip).collect();
+    assert!(encoded.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn prefix_codecs_encode_the_leading_fields() {
+    let event = Event { tenant: 1, name: "a".into(), kind: Kind::Login, sequence: 7 };
+    let bytes = encode(&event);
+    let prefix = (1, "a".to_owned());
+    assert_eq!(EventPrefix::bytes_encode(&prefix).unwrap(), &bytes[..6]);
+    assert_eq!(EventPrefix::bytes_decode(&bytes).unwrap(), event);
+
+    // A single prefix field is encoded by itself instead of a tuple.
+    let tenant = Tenant(3, "name".into());
+    let bytes = roundtrip(&tenant);
+    assert_eq!(TenantPrefix::bytes_encode(&3).unwrap(), &bytes[..1]);
+}
+
+#[test]
+fn invalid_bytes() {
+    let bytes = encode(&Tenant(3, "name".into()));
+    assert_eq!(decoding_error::<Tenant>(&bytes[..0]), "invalid Tenant: unexpected end of bytes");
+    assert_eq!(
+        decoding_error::<Tenant>(&bytes[..bytes.len() - 1]),
+        "invalid Tenant: missing the nul byte terminating a string"
+    );
+
+    let mut trailing = bytes.clone();
+    trailing.extend_from_slice(&[1, 2]);
+    assert_eq!(decoding_error::<Tenant>(&trailing), "invalid Tenant: 2 trailing bytes");
+
+    let bytes = [3, 0xC0, 0];
+    assert!(Tenant::bytes_decode(&bytes).unwrap_err().is::<std::str::Utf8Error>());
+
+    let bytes = encode(&Strings {
+        name: String::new(),
+        explicit: vec![],
+        raw: vec![1, 2, 3],
+        text: String::new(),
+    });
+    assert_eq!(
+        decoding_error::<Strings>(&bytes[..bytes.len() - 5]),
+        "invalid Strings: unexpected end of bytes"
+    );
+}
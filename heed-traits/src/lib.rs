@@ -12,6 +12,7 @@
 use std::borrow::Cow;
 use std::cmp::{Ord, Ordering};
 use std::error::Error as StdError;
+use std::{fmt, io};
 
 /// A boxed `Send + Sync + 'static` error.
 pub type BoxedError = Box<dyn StdError + Send + Sync + 'static>;
@@ -21,8 +22,106 @@ pub trait BytesEncode<'a> {
     /// The type to encode.
     type EItem: ?Sized + 'a;
 
+    /// The error returned when the item cannot be encoded.
+    ///
+    /// Codecs that do not have a dedicated error type can use [`BoxedError`],
+    /// the ones that cannot fail use [`Infallible`](std::convert::Infallible).
+    type Error: Into<BoxedError> + 'static;
+
     /// Encode the given item as bytes.
-    fn bytes_encode(item: &'a Self::EItem) -> Result<Cow<'a, [u8]>, BoxedError>;
+    fn bytes_encode(item: &'a Self::EItem) -> Result<Cow<'a, [u8]>, Self::Error>;
+
+    /// Returns `true` if [`BytesEncode::bytes_encode_into_writer`] writes the bytes
+    /// of the given item without allocating them.
+    ///
+    /// The items are encoded with [`BytesEncode::bytes_encode`] by default and the borrowed
+    /// bytes are used as is. The items for which it returns `true` are encoded with
+    /// [`BytesEncode::bytes_encode_into_writer`] into a buffer reused by the transaction.
+    fn encodes_into_writer(item: &Self::EItem) -> bool {
+        let _ = item;
+        false
+    }
+
+    /// Returns the exact number of bytes [`BytesEncode::bytes_encode_into_writer`]
+    /// writes for the given item, if it can be known without encoding it.
+    ///
+    /// The values are written directly into the space reserved by LMDB when their size
+    /// is known, avoiding an intermediate copy. The space replaces the previous value
+    /// before the item is encoded, the previous value is put back if the encoding fails.
+    ///
+    /// Returning a size that differs from the number of written bytes is a bug,
+    /// the write returns an [`io::ErrorKind::InvalidData`] error.
+    fn encoded_size(item: &Self::EItem) -> Option<usize> {
+        let _ = item;
+        None
+    }
+
+    /// Encode the given item as bytes and write them into the given writer.
+    ///
+    /// The default implementation calls [`BytesEncode::bytes_encode`] and writes the result,
+    /// codecs can override it to write the bytes directly without allocating and then return
+    /// `true` from [`BytesEncode::encodes_into_writer`].
+    fn bytes_encode_into_writer<W: io::Write>(
+        item: &'a Self::EItem,
+        mut writer: W,
+    ) -> Result<(), EncodeIntoWriterError<Self::Error>> {
+        let bytes = Self::bytes_encode(item).map_err(EncodeIntoWriterError::Encoding)?;
+        writer.write_all(&bytes)?;
+        Ok(())
+    }
+}
+
+/// The error returned by [`BytesEncode::bytes_encode_into_writer`].
+#[derive(Debug)]
+pub enum EncodeIntoWriterError<E> {
+    /// The codec could not encode the item.
+    Encoding(E),
+    /// The writer could not write the bytes.
+    Io(io::Error),
+}
+
+impl<E> EncodeIntoWriterError<E> {
+    /// Converts the error of the codec with the given function.
+    pub fn map_encoding<F>(self, op: impl FnOnce(E) -> F) -> EncodeIntoWriterError<F> {
+        match self {
+            EncodeIntoWriterError::Encoding(error) => EncodeIntoWriterError::Encoding(op(error)),
+            EncodeIntoWriterError::Io(error) => EncodeIntoWriterError::Io(error),
+        }
+    }
+}
+
+impl<E: Into<BoxedError>> EncodeIntoWriterError<E> {
+    /// Boxes the error, whether it comes from the codec or the writer.
+    pub fn into_boxed(self) -> BoxedError {
+        match self {
+            EncodeIntoWriterError::Encoding(error) => error.into(),
+            EncodeIntoWriterError::Io(error) => error.into(),
+        }
+    }
+}
+
+impl<E> From<io::Error> for EncodeIntoWriterError<E> {
+    fn from(error: io::Error) -> Self {
+        EncodeIntoWriterError::Io(error)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for EncodeIntoWriterError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EncodeIntoWriterError::Encoding(error) => write!(f, "{}", error),
+            EncodeIntoWriterError::Io(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl<E: StdError + 'static> StdError for EncodeIntoWriterError<E> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            EncodeIntoWriterError::Encoding(error) => Some(error),
+            EncodeIntoWriterError::Io(error) => Some(error),
+        }
+    }
 }
 
 /// A trait that represents a decoding structure.
@@ -30,10 +129,109 @@ pub trait BytesDecode<'a> {
     /// The type to decode.
     type DItem: 'a;
 
+    /// The error returned when the bytes cannot be decoded.
+    ///
+    /// Codecs that do not have a dedicated error type can use [`BoxedError`].
+    type Error: Into<BoxedError>;
+
+    /// Decode the given bytes as `DItem`.
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, Self::Error>;
+}
+
+/// An encoding structure returning a [`BoxedError`], the signature of
+/// [`BytesEncode`] before it had an associated [`BytesEncode::Error`] type.
+///
+/// It is implemented by the codecs that do not need a typed error and is bridged to
+/// [`BytesEncode`], with [`BoxedError`] as its error type. The codecs written for the
+/// previous versions only have to implement this trait instead of [`BytesEncode`].
+///
+/// ```
+/// use std::borrow::Cow;
+///
+/// use heed_traits::{BoxedBytesEncode, BoxedError, BytesEncode};
+///
+/// enum Upper {}
+///
+/// impl<'a> BoxedBytesEncode<'a> for Upper {
+///     type EItem = str;
+///
+///     fn bytes_encode(item: &'a str) -> Result<Cow<'a, [u8]>, BoxedError> {
+///         Ok(Cow::Owned(item.to_uppercase().into_bytes()))
+///     }
+/// }
+///
+/// let bytes = <Upper as BytesEncode>::bytes_encode("hello").unwrap();
+/// assert_eq!(bytes, &b"HELLO"[..]);
+/// ```
+pub trait BoxedBytesEncode<'a> {
+    /// The type to encode.
+    type EItem: ?Sized + 'a;
+
+    /// Encode the given item as bytes.
+    fn bytes_encode(item: &'a Self::EItem) -> Result<Cow<'a, [u8]>, BoxedError>;
+}
+
+impl<'a, C: BoxedBytesEncode<'a>> BytesEncode<'a> for C {
+    type EItem = C::EItem;
+    type Error = BoxedError;
+
+    fn bytes_encode(item: &'a Self::EItem) -> Result<Cow<'a, [u8]>, Self::Error> {
+        <C as BoxedBytesEncode>::bytes_encode(item)
+    }
+}
+
+/// A decoding structure returning a [`BoxedError`], the signature of
+/// [`BytesDecode`] before it had an associated [`BytesDecode::Error`] type.
+///
+/// It is implemented by the codecs that do not need a typed error and is bridged to
+/// [`BytesDecode`], with [`BoxedError`] as its error type. The codecs written for the
+/// previous versions only have to implement this trait instead of [`BytesDecode`].
+pub trait BoxedBytesDecode<'a> {
+    /// The type to decode.
+    type DItem: 'a;
+
     /// Decode the given bytes as `DItem`.
     fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, BoxedError>;
 }
 
+impl<'a, C: BoxedBytesDecode<'a>> BytesDecode<'a> for C {
+    type DItem = C::DItem;
+    type Error = BoxedError;
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, Self::Error> {
+        <C as BoxedBytesDecode>::bytes_decode(bytes)
+    }
+}
+
+/// A decoding structure that does not borrow from the bytes it decodes.
+///
+/// It is implemented for every [`BytesDecode`] codec that decodes the same owned type
+/// whatever the lifetime of the bytes is, and is useful to decode bytes that only live
+/// temporarily, like decompressed or decrypted ones.
+pub trait BytesDecodeOwned {
+    /// The type to decode.
+    type DItem;
+
+    /// The error returned when the bytes cannot be decoded.
+    type Error: Into<BoxedError>;
+
+    /// Decode the given bytes as `DItem`.
+    fn bytes_decode_owned(bytes: &[u8]) -> Result<Self::DItem, Self::Error>;
+}
+
+impl<C, T, E> BytesDecodeOwned for C
+where
+    C: for<'a> BytesDecode<'a, DItem = T, Error = E>,
+    E: Into<BoxedError>,
+{
+    type DItem = T;
+    type Error = E;
+
+    fn bytes_decode_owned(bytes: &[u8]) -> Result<Self::DItem, Self::Error> {
+        C::bytes_decode(bytes)
+    }
+}
+
 /// Define a custom key comparison function for a database.
 ///
 /// The comparison function is called whenever it is necessary to compare a key specified
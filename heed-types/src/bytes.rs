@@ -1,6 +1,7 @@
 use std::borrow::Cow;
+use std::convert::Infallible;
 
-use heed_traits::{BoxedError, BytesDecode, BytesEncode};
+use heed_traits::{BytesDecode, BytesEncode};
 
 /// Describes a byte slice `[u8]` that is totally borrowed and doesn't depend on
 /// any [memory alignment].
@@ -10,16 +11,18 @@ pub enum Bytes {}
 
 impl<'a> BytesEncode<'a> for Bytes {
     type EItem = [u8];
+    type Error = Infallible;
 
-    fn bytes_encode(item: &'a Self::EItem) -> Result<Cow<[u8]>, BoxedError> {
+    fn bytes_encode(item: &'a Self::EItem) -> Result<Cow<'a, [u8]>, Self::Error> {
         Ok(Cow::Borrowed(item))
     }
 }
 
 impl<'a> BytesDecode<'a> for Bytes {
     type DItem = &'a [u8];
+    type Error = Infallible;
 
-    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, BoxedError> {
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, Self::Error> {
         Ok(bytes)
     }
 }
@@ -0,0 +1,227 @@
+use std::borrow::Cow;
+use std::convert::Infallible;
+use std::marker::PhantomData;
+use std::{error, fmt};
+
+use heed_traits::{BoxedError, BytesDecode, BytesEncode};
+
+/// Describes a codec that appends a checksum of the bytes of the inner `C` codec and
+/// verifies it before decoding them, to detect the values that were corrupted on disk.
+///
+/// The checksum is computed by the `H` algorithm and stored in big endian after the
+/// bytes. As it changes the ordering of the bytes, it is meant to be used for values,
+/// not for keys.
+///
+/// A corrupted value is reported with a [`ChecksumError::Mismatch`] instead of the
+/// error of the inner codec, it can be distinguished by downcasting the decoding error.
+pub struct Checksummed<C, H>(PhantomData<(C, H)>);
+
+impl<'a, C, H> BytesEncode<'a> for Checksummed<C, H>
+where
+    C: BytesEncode<'a>,
+    H: Checksum,
+{
+    type EItem = C::EItem;
+    type Error = C::Error;
+
+    fn bytes_encode(item: &'a Self::EItem) -> Result<Cow<'a, [u8]>, Self::Error> {
+        let bytes = C::bytes_encode(item)?;
+        let checksum = H::checksum(&bytes).to_be_bytes();
+        let mut output = Vec::with_capacity(bytes.len() + H::SIZE);
+        output.extend_from_slice(&bytes);
+        output.extend_from_slice(&checksum[checksum.len() - H::SIZE..]);
+        Ok(Cow::Owned(output))
+    }
+
+    fn encoded_size(item: &Self::EItem) -> Option<usize> {
+        C::encoded_size(item).map(|size| size + H::SIZE)
+    }
+}
+
+impl<'a, C, H> BytesDecode<'a> for Checksummed<C, H>
+where
+    C: BytesDecode<'a>,
+    H: Checksum,
+{
+    type DItem = C::DItem;
+    type Error = ChecksumError;
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, Self::Error> {
+        let Some(split) = bytes.len().checked_sub(H::SIZE) else {
+            return Err(ChecksumError::MissingChecksum);
+        };
+
+        let (bytes, checksum) = bytes.split_at(split);
+        let mut stored = [0; 8];
+        stored[8 - H::SIZE..].copy_from_slice(checksum);
+        let stored = u64::from_be_bytes(stored);
+        let computed = H::checksum(bytes);
+        if stored != computed {
+            return Err(ChecksumError::Mismatch { stored, computed });
+        }
+
+        C::bytes_decode(bytes).map_err(|e| ChecksumError::Codec(e.into()))
+    }
+}
+
+unsafe impl<C, H> Send for Checksummed<C, H> {}
+
+unsafe impl<C, H> Sync for Checksummed<C, H> {}
+
+/// A checksum algorithm that can be used with the [`Checksummed`] codec.
+pub trait Checksum {
+    /// The number of bytes of the checksum, at most 8.
+    const SIZE: usize;
+
+    /// Computes the checksum of the given bytes.
+    fn checksum(bytes: &[u8]) -> u64;
+}
+
+/// The CRC-32C checksum, hardware accelerated on most platforms.
+#[cfg(feature = "crc32c")]
+pub enum Crc32c {}
+
+#[cfg(feature = "crc32c")]
+impl Checksum for Crc32c {
+    const SIZE: usize = 4;
+
+    fn checksum(bytes: &[u8]) -> u64 {
+        crc32c::crc32c(bytes).into()
+    }
+}
+
+/// The 64 bits XXH3 checksum, a very fast non-cryptographic hash.
+#[cfg(feature = "xxhash")]
+pub enum Xxh3 {}
+
+#[cfg(feature = "xxhash")]
+impl Checksum for Xxh3 {
+    const SIZE: usize = 8;
+
+    fn checksum(bytes: &[u8]) -> u64 {
+        xxhash_rust::xxh3::xxh3_64(bytes)
+    }
+}
+
+/// An error that happens when checksummed bytes cannot be decoded.
+#[derive(Debug)]
+pub enum ChecksumError {
+    /// The bytes are shorter than the checksum.
+    MissingChecksum,
+    /// The stored checksum does not match the bytes, they are corrupted.
+    Mismatch {
+        /// The checksum stored with the bytes.
+        stored: u64,
+        /// The checksum computed from the bytes.
+        computed: u64,
+    },
+    /// The checksum is valid but the inner codec failed to decode the bytes.
+    Codec(BoxedError),
+}
+
+impl ChecksumError {
+    /// Returns `true` if the bytes are corrupted, `false` if the inner codec failed.
+    pub fn is_corruption(&self) -> bool {
+        !matches!(self, ChecksumError::Codec(_))
+    }
+}
+
+impl fmt::Display for ChecksumError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChecksumError::MissingChecksum => {
+                f.write_str("the bytes are too short to be checksummed")
+            }
+            ChecksumError::Mismatch { stored, computed } => write!(
+                f,
+                "checksum mismatch, the bytes are corrupted (stored {:#x}, computed {:#x})",
+                stored, computed
+            ),
+            ChecksumError::Codec(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl error::Error for ChecksumError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ChecksumError::Codec(error) => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<Infallible> for ChecksumError {
+    fn from(never: Infallible) -> ChecksumError {
+        match never {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Bytes, Str, U8};
+
+    /// A two bytes checksum that sums the bytes.
+    enum Sum {}
+
+    impl Checksum for Sum {
+        const SIZE: usize = 2;
+
+        fn checksum(bytes: &[u8]) -> u64 {
+            bytes.iter().map(|&byte| u64::from(byte)).sum::<u64>() & 0xFFFF
+        }
+    }
+
+    #[test]
+    fn checksum_is_appended_in_big_endian() {
+        let bytes = Checksummed::<Bytes, Sum>::bytes_encode(&[0xFF, 0xFF, 3]).unwrap();
+        assert_eq!(bytes, &[0xFF, 0xFF, 3, 0x02, 0x01][..]);
+        assert_eq!(Checksummed::<Bytes, Sum>::encoded_size(&[0xFF, 0xFF, 3]), None);
+        assert_eq!(Checksummed::<U8, Sum>::encoded_size(&3), Some(3));
+        assert_eq!(Checksummed::<Bytes, Sum>::bytes_decode(&bytes).unwrap(), &[0xFF, 0xFF, 3]);
+
+        let bytes = Checksummed::<Str, Sum>::bytes_encode("").unwrap();
+        assert_eq!(Checksummed::<Str, Sum>::bytes_decode(&bytes).unwrap(), "");
+    }
+
+    #[test]
+    #[cfg(feature = "crc32c")]
+    fn crc32c() {
+        let bytes = Checksummed::<Str, Crc32c>::bytes_encode("123456789").unwrap();
+        assert_eq!(bytes[9..], 0xE306_9283u32.to_be_bytes());
+        assert_eq!(Checksummed::<Str, Crc32c>::bytes_decode(&bytes).unwrap(), "123456789");
+    }
+
+    #[test]
+    #[cfg(feature = "xxhash")]
+    fn xxh3() {
+        let bytes = Checksummed::<Str, Xxh3>::bytes_encode("value").unwrap();
+        assert_eq!(bytes.len(), 5 + 8);
+        assert_eq!(Checksummed::<Str, Xxh3>::bytes_decode(&bytes).unwrap(), "value");
+    }
+
+    #[test]
+    fn corruption_is_distinguished_from_codec_errors() {
+        let mut bytes = Checksummed::<Str, Sum>::bytes_encode("world").unwrap().into_owned();
+        bytes[0] ^= 1;
+        match Checksummed::<Str, Sum>::bytes_decode(&bytes) {
+            Err(e @ ChecksumError::Mismatch { stored, computed }) => {
+                assert!(e.is_corruption());
+                assert_eq!(stored, computed + 1);
+            }
+            otherwise => panic!("expected a checksum mismatch, got {:?}", otherwise),
+        }
+
+        let error = Checksummed::<Str, Sum>::bytes_decode(&[1]).unwrap_err();
+        assert!(matches!(error, ChecksumError::MissingChecksum));
+        assert!(error.is_corruption());
+
+        // The checksum is valid but the bytes are not valid UTF-8.
+        let bytes = Checksummed::<Bytes, Sum>::bytes_encode(&[0xFF]).unwrap();
+        let error = Checksummed::<Str, Sum>::bytes_decode(&bytes).unwrap_err();
+        assert!(matches!(error, ChecksumError::Codec(_)));
+        assert!(!error.is_corruption());
+        assert!(error::Error::source(&error).is_some());
+    }
+}
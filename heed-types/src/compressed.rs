@@ -0,0 +1,469 @@
+use std::borrow::Cow;
+use std::marker::PhantomData;
+use std::{error, fmt, io};
+
+use heed_traits::{BoxedError, BytesDecode, BytesDecodeOwned, BytesEncode};
+
+/// The identifier written in the header of the values that are stored uncompressed.
+const UNCOMPRESSED: u8 = 0;
+/// The identifier written in the header of the values compressed with LZ4.
+#[cfg(feature = "lz4")]
+const LZ4: u8 = 1;
+/// The identifier written in the header of the values compressed with zstd.
+#[cfg(feature = "zstd")]
+const ZSTD: u8 = 2;
+/// The identifier written in the header of the values compressed with zstd and a dictionary,
+/// it is followed by the little-endian `u32` identifier of the dictionary.
+#[cfg(feature = "zstd")]
+const ZSTD_DICT: u8 = 3;
+
+/// The maximum size of the decompressed values by default, 64 MiB.
+pub const DEFAULT_MAX_DECOMPRESSED_SIZE: usize = 64 * 1024 * 1024;
+
+/// Describes a codec that compresses the bytes of the inner `C` codec with the `A` algorithm.
+///
+/// The compressed bytes are prefixed by a one byte header that identifies the algorithm.
+/// The values are decompressed according to their header, not to `A`, it is therefore
+/// possible to change the algorithm of a database over time: the values that were
+/// written with the previous algorithm can still be read.
+///
+/// The bytes are decompressed into an owned buffer before being decoded by `C`,
+/// it means that the inner codec must decode owned values, e.g. a `SerdeJson<String>`
+/// can be decompressed but a `Str` cannot.
+///
+/// The stored bytes are not trusted: a value is never decompressed into more than
+/// `MAX_SIZE` bytes, [`DEFAULT_MAX_DECOMPRESSED_SIZE`] by default. The size announced
+/// by an LZ4 value is checked before allocating and zstd values are decompressed
+/// through a bounded reader. Values larger than `MAX_SIZE` cannot be encoded either,
+/// so that everything written by the codec can be read back.
+pub struct Compressed<C, A, const MAX_SIZE: usize = DEFAULT_MAX_DECOMPRESSED_SIZE>(
+    PhantomData<(C, A)>,
+);
+
+impl<'a, C, A, const MAX_SIZE: usize> BytesEncode<'a> for Compressed<C, A, MAX_SIZE>
+where
+    C: BytesEncode<'a>,
+    A: Algorithm,
+{
+    type EItem = C::EItem;
+    type Error = BoxedError;
+
+    fn bytes_encode(item: &'a Self::EItem) -> Result<Cow<'a, [u8]>, Self::Error> {
+        let bytes = C::bytes_encode(item).map_err(Into::into)?;
+        if bytes.len() > MAX_SIZE {
+            return Err(CompressedError::TooLarge { max_size: MAX_SIZE }.into());
+        }
+        let mut output = vec![A::ID];
+        A::compress(&bytes, &mut output)?;
+        Ok(Cow::Owned(output))
+    }
+}
+
+impl<'a, C, A, const MAX_SIZE: usize> BytesDecode<'a> for Compressed<C, A, MAX_SIZE>
+where
+    C: BytesDecodeOwned,
+    C::DItem: 'a,
+    A: Algorithm,
+{
+    type DItem = C::DItem;
+    type Error = BoxedError;
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, Self::Error> {
+        let bytes = match bytes.split_first() {
+            Some((&UNCOMPRESSED, bytes)) => Cow::Borrowed(bytes),
+            #[cfg(feature = "lz4")]
+            Some((&LZ4, bytes)) => Cow::Owned(decompress_lz4(bytes, MAX_SIZE)?),
+            #[cfg(feature = "zstd")]
+            Some((&ZSTD, bytes)) => {
+                Cow::Owned(read_bounded(zstd::stream::Decoder::with_buffer(bytes)?, MAX_SIZE)?)
+            }
+            #[cfg(feature = "zstd")]
+            Some((&ZSTD_DICT, bytes)) => {
+                if bytes.len() < 4 {
+                    return Err(CompressedError::MissingHeader.into());
+                }
+                let (id, bytes) = bytes.split_at(4);
+                let id = u32::from_le_bytes(id.try_into().unwrap());
+                let dictionary = match A::dictionary() {
+                    Some((expected, dictionary)) if expected == id => dictionary,
+                    Some((expected, _)) => {
+                        return Err(
+                            CompressedError::DictionaryMismatch { expected, found: id }.into()
+                        )
+                    }
+                    None => return Err(CompressedError::MissingDictionary.into()),
+                };
+                let decoder = zstd::stream::Decoder::with_dictionary(bytes, dictionary)?;
+                Cow::Owned(read_bounded(decoder, MAX_SIZE)?)
+            }
+            Some((&id, _)) => return Err(CompressedError::UnsupportedAlgorithm(id).into()),
+            None => return Err(CompressedError::MissingHeader.into()),
+        };
+        C::bytes_decode_owned(&bytes).map_err(Into::into)
+    }
+}
+
+unsafe impl<C, A, const MAX_SIZE: usize> Send for Compressed<C, A, MAX_SIZE> {}
+
+unsafe impl<C, A, const MAX_SIZE: usize> Sync for Compressed<C, A, MAX_SIZE> {}
+
+/// Decompresses an LZ4 block prefixed by its size, after checking the size against the limit.
+#[cfg(feature = "lz4")]
+fn decompress_lz4(bytes: &[u8], max_size: usize) -> Result<Vec<u8>, BoxedError> {
+    let (size, bytes) = lz4_flex::block::uncompressed_size(bytes)?;
+    if size > max_size {
+        return Err(CompressedError::TooLarge { max_size }.into());
+    }
+    Ok(lz4_flex::decompress(bytes, size)?)
+}
+
+/// Reads the whole decompressed output, without reading more than `max_size` bytes.
+#[cfg(feature = "zstd")]
+fn read_bounded<R: io::Read>(reader: R, max_size: usize) -> Result<Vec<u8>, BoxedError> {
+    let mut output = Vec::new();
+    let limit = u64::try_from(max_size).unwrap_or(u64::MAX).saturating_add(1);
+    io::copy(&mut reader.take(limit), &mut output)?;
+    if output.len() > max_size {
+        return Err(CompressedError::TooLarge { max_size }.into());
+    }
+    Ok(output)
+}
+
+/// A compression algorithm that can be used with the [`Compressed`] codec.
+///
+/// This trait is sealed, the algorithms are identified by the header of the values
+/// and only the algorithms of this crate can be decompressed.
+pub trait Algorithm: private::Sealed {
+    /// The identifier of the algorithm written in the header of the values.
+    #[doc(hidden)]
+    const ID: u8;
+
+    /// Compresses the bytes at the end of the output.
+    #[doc(hidden)]
+    fn compress(bytes: &[u8], output: &mut Vec<u8>) -> io::Result<()>;
+
+    /// The identifier and the dictionary used to decompress the values compressed
+    /// with a dictionary.
+    #[doc(hidden)]
+    fn dictionary() -> Option<(u32, &'static [u8])> {
+        None
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Stores the bytes as is, without compressing them.
+///
+/// It is useful to start with uncompressed values and enable compression later on.
+pub enum Uncompressed {}
+
+impl private::Sealed for Uncompressed {}
+
+impl Algorithm for Uncompressed {
+    const ID: u8 = UNCOMPRESSED;
+
+    fn compress(bytes: &[u8], output: &mut Vec<u8>) -> io::Result<()> {
+        output.extend_from_slice(bytes);
+        Ok(())
+    }
+}
+
+/// Compresses the bytes with LZ4, a fast algorithm with a moderate compression ratio.
+#[cfg(feature = "lz4")]
+pub enum Lz4 {}
+
+#[cfg(feature = "lz4")]
+impl private::Sealed for Lz4 {}
+
+#[cfg(feature = "lz4")]
+impl Algorithm for Lz4 {
+    const ID: u8 = LZ4;
+
+    fn compress(bytes: &[u8], output: &mut Vec<u8>) -> io::Result<()> {
+        output.extend_from_slice(&lz4_flex::compress_prepend_size(bytes));
+        Ok(())
+    }
+}
+
+/// Compresses the bytes with zstd at the given compression level, 3 by default.
+#[cfg(feature = "zstd")]
+pub enum Zstd<const LEVEL: i32 = 3> {}
+
+#[cfg(feature = "zstd")]
+impl<const LEVEL: i32> private::Sealed for Zstd<LEVEL> {}
+
+#[cfg(feature = "zstd")]
+impl<const LEVEL: i32> Algorithm for Zstd<LEVEL> {
+    const ID: u8 = ZSTD;
+
+    fn compress(bytes: &[u8], output: &mut Vec<u8>) -> io::Result<()> {
+        zstd::stream::copy_encode(bytes, output, LEVEL)
+    }
+}
+
+/// Compresses the bytes with zstd and the dictionary provided by `D`.
+///
+/// A dictionary drastically improves the compression ratio of small values that look alike.
+#[cfg(feature = "zstd")]
+pub struct ZstdDict<D>(PhantomData<D>);
+
+#[cfg(feature = "zstd")]
+impl<D> private::Sealed for ZstdDict<D> {}
+
+#[cfg(feature = "zstd")]
+impl<D: ZstdDictionary> Algorithm for ZstdDict<D> {
+    const ID: u8 = ZSTD_DICT;
+
+    fn compress(bytes: &[u8], output: &mut Vec<u8>) -> io::Result<()> {
+        output.extend_from_slice(&D::id().to_le_bytes());
+        let mut encoder =
+            zstd::stream::Encoder::with_dictionary(output, D::LEVEL, D::dictionary())?;
+        io::copy(&mut &bytes[..], &mut encoder)?;
+        encoder.finish().map(drop)
+    }
+
+    fn dictionary() -> Option<(u32, &'static [u8])> {
+        Some((D::id(), D::dictionary()))
+    }
+}
+
+/// Provides the dictionary used by the [`ZstdDict`] algorithm.
+///
+/// A dictionary is trained once, with `zstd::dict::from_samples`, and must be kept as long as
+/// values compressed with it exist. Its identifier is written in the header of the values,
+/// the values compressed with another dictionary are rejected instead of being decompressed
+/// into garbage. It is usually stored in a dedicated database of the
+/// environment, e.g. a `Database<Str, Bytes>` named `zstd-dictionaries`, and loaded into
+/// a static when the environment is opened.
+///
+/// ```
+/// use std::sync::OnceLock;
+///
+/// use heed_types::ZstdDictionary;
+///
+/// static LOGS_DICTIONARY: OnceLock<Vec<u8>> = OnceLock::new();
+///
+/// enum LogsDictionary {}
+///
+/// impl ZstdDictionary for LogsDictionary {
+///     fn dictionary() -> &'static [u8] {
+///         LOGS_DICTIONARY.get().expect("the dictionary must be loaded from the environment")
+///     }
+/// }
+/// ```
+#[cfg(feature = "zstd")]
+pub trait ZstdDictionary {
+    /// The compression level, 3 by default.
+    const LEVEL: i32 = 3;
+
+    /// Returns the dictionary used to compress and decompress the values.
+    fn dictionary() -> &'static [u8];
+
+    /// Returns the identifier of the dictionary.
+    ///
+    /// It is read from the trained dictionaries, the raw content dictionaries
+    /// do not have one and should give a unique identifier here.
+    fn id() -> u32 {
+        zstd::zstd_safe::get_dict_id_from_dict(Self::dictionary()).map_or(0, |id| id.get())
+    }
+}
+
+/// An error that happens when compressed bytes cannot be decompressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressedError {
+    /// The bytes are too short to contain the header identifying the algorithm.
+    MissingHeader,
+    /// The algorithm identified by the header is unknown or its feature is not enabled.
+    UnsupportedAlgorithm(u8),
+    /// The bytes were compressed with a dictionary but the codec does not provide one.
+    MissingDictionary,
+    /// The bytes were compressed with another dictionary than the one of the codec.
+    DictionaryMismatch {
+        /// The identifier of the dictionary of the codec.
+        expected: u32,
+        /// The identifier of the dictionary written in the header.
+        found: u32,
+    },
+    /// The decompressed value would be larger than the maximum size of the codec.
+    TooLarge {
+        /// The maximum size of the decompressed values.
+        max_size: usize,
+    },
+}
+
+impl fmt::Display for CompressedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CompressedError::MissingHeader => f.write_str("missing compression header"),
+            CompressedError::UnsupportedAlgorithm(id) => {
+                write!(f, "unsupported compression algorithm {}", id)
+            }
+            CompressedError::MissingDictionary => {
+                f.write_str("the bytes were compressed with a dictionary that is not provided")
+            }
+            CompressedError::DictionaryMismatch { expected, found } => write!(
+                f,
+                "the bytes were compressed with the dictionary {} instead of {}",
+                found, expected
+            ),
+            CompressedError::TooLarge { max_size } => {
+                write!(f, "the decompressed value is larger than {} bytes", max_size)
+            }
+        }
+    }
+}
+
+impl error::Error for CompressedError {}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use super::*;
+
+    /// A codec that decodes the bytes into an owned vector.
+    enum Raw {}
+
+    impl<'a> BytesEncode<'a> for Raw {
+        type EItem = [u8];
+        type Error = Infallible;
+
+        fn bytes_encode(item: &'a Self::EItem) -> Result<Cow<'a, [u8]>, Self::Error> {
+            Ok(Cow::Borrowed(item))
+        }
+    }
+
+    impl<'a> BytesDecode<'a> for Raw {
+        type DItem = Vec<u8>;
+        type Error = Infallible;
+
+        fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, Self::Error> {
+            Ok(bytes.to_vec())
+        }
+    }
+
+    #[cfg(feature = "zstd")]
+    enum Dictionary {}
+
+    #[cfg(feature = "zstd")]
+    impl ZstdDictionary for Dictionary {
+        fn dictionary() -> &'static [u8] {
+            b"a log line that looks like all the other log lines"
+        }
+    }
+
+    #[cfg(feature = "zstd")]
+    enum OtherDictionary {}
+
+    #[cfg(feature = "zstd")]
+    impl ZstdDictionary for OtherDictionary {
+        fn dictionary() -> &'static [u8] {
+            b"another dictionary"
+        }
+
+        fn id() -> u32 {
+            42
+        }
+    }
+
+    fn logs() -> Vec<u8> {
+        b"a log line that looks like all the other log lines".repeat(20)
+    }
+
+    fn encode<A: Algorithm, const MAX_SIZE: usize>(bytes: &[u8]) -> Result<Vec<u8>, BoxedError> {
+        Ok(Compressed::<Raw, A, MAX_SIZE>::bytes_encode(bytes)?.into_owned())
+    }
+
+    fn decode<A: Algorithm, const MAX_SIZE: usize>(bytes: &[u8]) -> CompressedError {
+        let error = Compressed::<Raw, A, MAX_SIZE>::bytes_decode(bytes).unwrap_err();
+        *error.downcast_ref::<CompressedError>().unwrap()
+    }
+
+    const MAX: usize = DEFAULT_MAX_DECOMPRESSED_SIZE;
+
+    #[test]
+    fn values_are_decompressed_according_to_their_header() {
+        let logs = logs();
+        let mut values = vec![encode::<Uncompressed, MAX>(&logs).unwrap()];
+        #[cfg(feature = "lz4")]
+        values.push(encode::<Lz4, MAX>(&logs).unwrap());
+        #[cfg(feature = "zstd")]
+        {
+            values.push(encode::<Zstd, MAX>(&logs).unwrap());
+            values.push(encode::<Zstd<19>, MAX>(&logs).unwrap());
+            values.push(encode::<ZstdDict<Dictionary>, MAX>(&logs).unwrap());
+        }
+
+        assert_eq!(values[0][0], UNCOMPRESSED);
+        assert_eq!(&values[0][1..], &logs[..]);
+        for value in &values[1..] {
+            assert!(value.len() < logs.len() / 4, "{:?} is too large", value[0]);
+        }
+
+        for value in &values {
+            #[cfg(feature = "zstd")]
+            let decoded = Compressed::<Raw, ZstdDict<Dictionary>>::bytes_decode(value).unwrap();
+            #[cfg(not(feature = "zstd"))]
+            let decoded = Compressed::<Raw, Uncompressed>::bytes_decode(value).unwrap();
+            assert_eq!(decoded, logs);
+        }
+    }
+
+    #[test]
+    fn invalid_headers() {
+        assert_eq!(decode::<Uncompressed, MAX>(&[]), CompressedError::MissingHeader);
+        assert_eq!(decode::<Uncompressed, MAX>(&[9, 1]), CompressedError::UnsupportedAlgorithm(9));
+    }
+
+    #[test]
+    #[cfg(feature = "zstd")]
+    fn dictionaries_are_checked() {
+        let value = encode::<ZstdDict<Dictionary>, MAX>(&logs()).unwrap();
+        assert_eq!(value[0], ZSTD_DICT);
+        assert_eq!(value[1..5], Dictionary::id().to_le_bytes());
+
+        assert_eq!(
+            decode::<ZstdDict<OtherDictionary>, MAX>(&value),
+            CompressedError::DictionaryMismatch { expected: 42, found: Dictionary::id() }
+        );
+        assert_eq!(decode::<Zstd, MAX>(&value), CompressedError::MissingDictionary);
+        assert_eq!(
+            decode::<ZstdDict<Dictionary>, MAX>(&value[..3]),
+            CompressedError::MissingHeader
+        );
+    }
+
+    #[test]
+    fn sizes_are_bounded() {
+        let logs = logs();
+        let too_large = CompressedError::TooLarge { max_size: 64 };
+
+        let error = encode::<Uncompressed, 64>(&logs).unwrap_err();
+        assert_eq!(error.downcast_ref::<CompressedError>(), Some(&too_large));
+
+        let mut values = Vec::new();
+        #[cfg(feature = "lz4")]
+        values.push(encode::<Lz4, MAX>(&logs).unwrap());
+        #[cfg(feature = "zstd")]
+        {
+            values.push(encode::<Zstd, MAX>(&logs).unwrap());
+            values.push(encode::<ZstdDict<Dictionary>, MAX>(&logs).unwrap());
+        }
+        for value in &values {
+            #[cfg(feature = "zstd")]
+            assert_eq!(decode::<ZstdDict<Dictionary>, 64>(value), too_large);
+            #[cfg(not(feature = "zstd"))]
+            assert_eq!(decode::<Uncompressed, 64>(value), too_large);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "lz4")]
+    fn lz4_size_is_checked_before_allocating() {
+        let mut lying = vec![LZ4];
+        lying.extend_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(decode::<Lz4, MAX>(&lying), CompressedError::TooLarge { max_size: MAX });
+    }
+}
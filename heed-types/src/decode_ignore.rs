@@ -1,4 +1,4 @@
-use heed_traits::BoxedError;
+use std::convert::Infallible;
 
 /// A convenient struct made to ignore the type when decoding it.
 ///
@@ -8,8 +8,9 @@ pub enum DecodeIgnore {}
 
 impl heed_traits::BytesDecode<'_> for DecodeIgnore {
     type DItem = ();
+    type Error = Infallible;
 
-    fn bytes_decode(_bytes: &[u8]) -> Result<Self::DItem, BoxedError> {
+    fn bytes_decode(_bytes: &[u8]) -> Result<Self::DItem, Self::Error> {
         Ok(())
     }
 }
@@ -0,0 +1,335 @@
+use std::borrow::Cow;
+use std::marker::PhantomData;
+use std::{error, fmt};
+
+#[cfg(feature = "aes-gcm")]
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+#[cfg(all(feature = "chacha20poly1305", not(feature = "aes-gcm")))]
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use heed_traits::{BoxedError, BytesDecode, BytesDecodeOwned, BytesEncode};
+
+/// The identifier written in the header of the values encrypted with AES-256-GCM.
+#[cfg(feature = "aes-gcm")]
+const AES_256_GCM: u8 = 1;
+/// The identifier written in the header of the values encrypted with ChaCha20-Poly1305.
+#[cfg(feature = "chacha20poly1305")]
+const CHACHA20_POLY1305: u8 = 2;
+
+/// The length of the header: the cipher identifier followed by the key id in big endian.
+const HEADER_LEN: usize = 1 + 4;
+/// The length of the random nonce, both ciphers use 96 bits nonces.
+const NONCE_LEN: usize = 12;
+
+/// Describes a codec that encrypts the bytes of the inner `C` codec with the keys of `K`.
+///
+/// The values are encrypted with the current key of `K` and prefixed by a header that
+/// identifies the cipher and the key, followed by a random nonce. The header is
+/// authenticated along with the bytes. As the values are decrypted with the key
+/// identified by their header, it is possible to rotate the keys: the values written with
+/// a previous key can still be read as long as `K` provides it.
+///
+/// The bytes are decrypted into an owned buffer before being decoded by `C`,
+/// it means that the inner codec must decode owned values, e.g. a `SerdeJson<String>`
+/// can be decrypted but a `Str` cannot. Encrypted bytes are random, it is therefore
+/// meant to be used for values, not for keys.
+pub struct Encrypted<C, K>(PhantomData<(C, K)>);
+
+impl<'a, C, K> BytesEncode<'a> for Encrypted<C, K>
+where
+    C: BytesEncode<'a>,
+    K: KeyProvider,
+{
+    type EItem = C::EItem;
+    type Error = BoxedError;
+
+    fn bytes_encode(item: &'a Self::EItem) -> Result<Cow<'a, [u8]>, Self::Error> {
+        let bytes = C::bytes_encode(item).map_err(Into::into)?;
+        let (id, key) = K::current_key();
+        let mut output = Vec::with_capacity(HEADER_LEN + NONCE_LEN + bytes.len() + 16);
+        output.push(key.cipher_id());
+        output.extend_from_slice(&id.to_be_bytes());
+        key.encrypt(&bytes, &mut output)?;
+        Ok(Cow::Owned(output))
+    }
+}
+
+impl<'a, C, K> BytesDecode<'a> for Encrypted<C, K>
+where
+    C: BytesDecodeOwned,
+    C::DItem: 'a,
+    K: KeyProvider,
+{
+    type DItem = C::DItem;
+    type Error = BoxedError;
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, Self::Error> {
+        if bytes.len() < HEADER_LEN + NONCE_LEN {
+            return Err(EncryptedError::MissingHeader.into());
+        }
+
+        let (header, bytes) = bytes.split_at(HEADER_LEN);
+        let id = u32::from_be_bytes(header[1..].try_into().unwrap());
+        let key = K::key(id).ok_or(EncryptedError::UnknownKey(id))?;
+        if key.cipher_id() != header[0] {
+            return Err(EncryptedError::UnsupportedCipher(header[0]).into());
+        }
+
+        let bytes = key.decrypt(header, bytes)?;
+        C::bytes_decode_owned(&bytes).map_err(Into::into)
+    }
+}
+
+unsafe impl<C, K> Send for Encrypted<C, K> {}
+
+unsafe impl<C, K> Sync for Encrypted<C, K> {}
+
+/// A 256 bits key and the cipher it must be used with.
+#[derive(Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EncryptionKey {
+    /// A key used with the AES-256-GCM cipher, hardware accelerated on most platforms.
+    #[cfg(feature = "aes-gcm")]
+    Aes256Gcm([u8; 32]),
+    /// A key used with the ChaCha20-Poly1305 cipher, fast on platforms without AES instructions.
+    #[cfg(feature = "chacha20poly1305")]
+    ChaCha20Poly1305([u8; 32]),
+}
+
+impl EncryptionKey {
+    fn cipher_id(&self) -> u8 {
+        match self {
+            #[cfg(feature = "aes-gcm")]
+            EncryptionKey::Aes256Gcm(_) => AES_256_GCM,
+            #[cfg(feature = "chacha20poly1305")]
+            EncryptionKey::ChaCha20Poly1305(_) => CHACHA20_POLY1305,
+        }
+    }
+
+    /// Encrypts the bytes and writes the nonce followed by the ciphertext at the end of the
+    /// output, the header already written in the output is authenticated with the bytes.
+    fn encrypt(&self, bytes: &[u8], output: &mut Vec<u8>) -> Result<(), EncryptedError> {
+        let payload = Payload { msg: bytes, aad: &output[..] };
+        let (nonce, ciphertext) = match self {
+            #[cfg(feature = "aes-gcm")]
+            EncryptionKey::Aes256Gcm(key) => {
+                let nonce = aes_gcm::Aes256Gcm::generate_nonce(&mut OsRng);
+                let cipher = aes_gcm::Aes256Gcm::new(key.into());
+                (nonce, cipher.encrypt(&nonce, payload))
+            }
+            #[cfg(feature = "chacha20poly1305")]
+            EncryptionKey::ChaCha20Poly1305(key) => {
+                let nonce = chacha20poly1305::ChaCha20Poly1305::generate_nonce(&mut OsRng);
+                let cipher = chacha20poly1305::ChaCha20Poly1305::new(key.into());
+                (nonce, cipher.encrypt(&nonce, payload))
+            }
+        };
+        let ciphertext = ciphertext.map_err(|_| EncryptedError::Encryption)?;
+        output.extend_from_slice(&nonce);
+        output.extend_from_slice(&ciphertext);
+        Ok(())
+    }
+
+    /// Authenticates the header and decrypts the bytes that start with the nonce.
+    fn decrypt(&self, header: &[u8], bytes: &[u8]) -> Result<Vec<u8>, EncryptedError> {
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let payload = Payload { msg: ciphertext, aad: header };
+        let plaintext = match self {
+            #[cfg(feature = "aes-gcm")]
+            EncryptionKey::Aes256Gcm(key) => {
+                aes_gcm::Aes256Gcm::new(key.into()).decrypt(nonce.into(), payload)
+            }
+            #[cfg(feature = "chacha20poly1305")]
+            EncryptionKey::ChaCha20Poly1305(key) => {
+                chacha20poly1305::ChaCha20Poly1305::new(key.into()).decrypt(nonce.into(), payload)
+            }
+        };
+        plaintext.map_err(|_| EncryptedError::Decryption)
+    }
+}
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Never print the key material.
+        match self {
+            #[cfg(feature = "aes-gcm")]
+            EncryptionKey::Aes256Gcm(_) => f.write_str("Aes256Gcm(..)"),
+            #[cfg(feature = "chacha20poly1305")]
+            EncryptionKey::ChaCha20Poly1305(_) => f.write_str("ChaCha20Poly1305(..)"),
+        }
+    }
+}
+
+/// Provides the keys used by the [`Encrypted`] codec.
+///
+/// The keys are identified by an id written in the header of the values. To rotate the
+/// keys, make [`KeyProvider::current_key`] return a new key with a new id and keep
+/// returning the previous keys from [`KeyProvider::key`] until all the values have been
+/// rewritten. The keys are usually loaded from a secret manager into a static when the
+/// environment is opened.
+///
+/// ```
+/// use std::sync::OnceLock;
+///
+/// use heed_types::{EncryptionKey, KeyProvider};
+///
+/// static KEYS: OnceLock<Vec<[u8; 32]>> = OnceLock::new();
+///
+/// enum PiiKeys {}
+///
+/// impl KeyProvider for PiiKeys {
+///     fn current_key() -> (u32, EncryptionKey) {
+///         let keys = KEYS.get().expect("the keys must be loaded");
+///         let id = keys.len() as u32 - 1;
+///         (id, EncryptionKey::Aes256Gcm(keys[id as usize]))
+///     }
+///
+///     fn key(id: u32) -> Option<EncryptionKey> {
+///         let keys = KEYS.get()?;
+///         keys.get(id as usize).copied().map(EncryptionKey::Aes256Gcm)
+///     }
+/// }
+/// ```
+pub trait KeyProvider {
+    /// Returns the key used to encrypt the new values and its id.
+    fn current_key() -> (u32, EncryptionKey);
+
+    /// Returns the key with the given id, used to decrypt the values.
+    fn key(id: u32) -> Option<EncryptionKey>;
+}
+
+/// An error that happens when bytes cannot be encrypted or decrypted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncryptedError {
+    /// The bytes are too short to contain the header and the nonce.
+    MissingHeader,
+    /// The key provider does not know the key identified by the header.
+    UnknownKey(u32),
+    /// The cipher identified by the header is unknown, its feature is not enabled,
+    /// or it is not the cipher of the key.
+    UnsupportedCipher(u8),
+    /// The bytes cannot be encrypted.
+    Encryption,
+    /// The bytes cannot be authenticated, they are corrupted or were encrypted with another key.
+    Decryption,
+}
+
+impl fmt::Display for EncryptedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EncryptedError::MissingHeader => f.write_str("missing encryption header"),
+            EncryptedError::UnknownKey(id) => write!(f, "unknown encryption key {}", id),
+            EncryptedError::UnsupportedCipher(id) => write!(f, "unsupported cipher {}", id),
+            EncryptedError::Encryption => f.write_str("the bytes cannot be encrypted"),
+            EncryptedError::Decryption => f.write_str("the bytes cannot be decrypted"),
+        }
+    }
+}
+
+impl error::Error for EncryptedError {}
+
+#[cfg(test)]
+mod tests {
+    use byteorder::BigEndian;
+
+    use super::*;
+    use crate::U64;
+
+    const VALUE: u64 = 0x0123_4567_89AB_CDEF;
+
+    fn key(id: u32) -> Option<EncryptionKey> {
+        match id {
+            #[cfg(feature = "aes-gcm")]
+            0 => Some(EncryptionKey::Aes256Gcm([0; 32])),
+            #[cfg(feature = "chacha20poly1305")]
+            1 => Some(EncryptionKey::ChaCha20Poly1305([1; 32])),
+            _ => None,
+        }
+    }
+
+    /// Encrypts with the first key of the enabled ciphers.
+    enum First {}
+
+    impl KeyProvider for First {
+        fn current_key() -> (u32, EncryptionKey) {
+            let id = if cfg!(feature = "aes-gcm") { 0 } else { 1 };
+            (id, key(id).unwrap())
+        }
+
+        fn key(id: u32) -> Option<EncryptionKey> {
+            key(id)
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> EncryptedError {
+        let error = Encrypted::<U64<BigEndian>, First>::bytes_decode(bytes).unwrap_err();
+        *error.downcast_ref::<EncryptedError>().unwrap()
+    }
+
+    #[test]
+    fn values_are_encrypted_with_a_random_nonce() {
+        let first = Encrypted::<U64<BigEndian>, First>::bytes_encode(&VALUE).unwrap();
+        let second = Encrypted::<U64<BigEndian>, First>::bytes_encode(&VALUE).unwrap();
+        assert_eq!(first.len(), HEADER_LEN + NONCE_LEN + 8 + 16);
+        assert_eq!(first[..HEADER_LEN], second[..HEADER_LEN]);
+        assert_ne!(first[HEADER_LEN..], second[HEADER_LEN..]);
+        assert!(!first.windows(8).any(|w| w == VALUE.to_be_bytes()));
+
+        for bytes in [first, second] {
+            assert_eq!(Encrypted::<U64<BigEndian>, First>::bytes_decode(&bytes).unwrap(), VALUE);
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "aes-gcm", feature = "chacha20poly1305"))]
+    fn values_are_decrypted_with_the_key_of_their_header() {
+        enum Second {}
+
+        impl KeyProvider for Second {
+            fn current_key() -> (u32, EncryptionKey) {
+                (1, key(1).unwrap())
+            }
+
+            fn key(id: u32) -> Option<EncryptionKey> {
+                key(id)
+            }
+        }
+
+        let first = Encrypted::<U64<BigEndian>, First>::bytes_encode(&VALUE).unwrap();
+        let second = Encrypted::<U64<BigEndian>, Second>::bytes_encode(&VALUE).unwrap();
+        assert_eq!(first[..HEADER_LEN], [AES_256_GCM, 0, 0, 0, 0]);
+        assert_eq!(second[..HEADER_LEN], [CHACHA20_POLY1305, 0, 0, 0, 1]);
+
+        for bytes in [&first, &second] {
+            assert_eq!(Encrypted::<U64<BigEndian>, First>::bytes_decode(bytes).unwrap(), VALUE);
+            assert_eq!(Encrypted::<U64<BigEndian>, Second>::bytes_decode(bytes).unwrap(), VALUE);
+        }
+
+        // The cipher of the header must be the one of the key.
+        let mut mismatch = first.into_owned();
+        mismatch[4] = 1;
+        assert_eq!(decode(&mismatch), EncryptedError::UnsupportedCipher(AES_256_GCM));
+    }
+
+    #[test]
+    fn tampering_is_detected() {
+        let bytes = Encrypted::<U64<BigEndian>, First>::bytes_encode(&VALUE).unwrap();
+
+        let mut tampered = bytes.to_vec();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert_eq!(decode(&tampered), EncryptedError::Decryption);
+
+        let mut tampered = bytes.to_vec();
+        tampered[HEADER_LEN] ^= 1;
+        assert_eq!(decode(&tampered), EncryptedError::Decryption);
+
+        let mut unknown = bytes.to_vec();
+        unknown[4] = 2;
+        assert_eq!(decode(&unknown), EncryptedError::UnknownKey(2));
+
+        let mut unsupported = bytes.to_vec();
+        unsupported[0] = 9;
+        assert_eq!(decode(&unsupported), EncryptedError::UnsupportedCipher(9));
+
+        assert_eq!(decode(&bytes[..HEADER_LEN + NONCE_LEN - 1]), EncryptedError::MissingHeader);
+    }
+}
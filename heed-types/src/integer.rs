@@ -1,26 +1,45 @@
 use std::borrow::Cow;
+use std::convert::Infallible;
+use std::io;
 use std::marker::PhantomData;
 use std::mem::size_of;
 
-use byteorder::{ByteOrder, ReadBytesExt};
-use heed_traits::{BoxedError, BytesDecode, BytesEncode};
+use byteorder::{ByteOrder, ReadBytesExt, WriteBytesExt};
+use heed_traits::{BytesDecode, BytesEncode, EncodeIntoWriterError};
 
 /// Encodable version of [`u8`].
 pub struct U8;
 
 impl BytesEncode<'_> for U8 {
     type EItem = u8;
+    type Error = Infallible;
 
-    fn bytes_encode(item: &Self::EItem) -> Result<Cow<[u8]>, BoxedError> {
+    fn bytes_encode(item: &Self::EItem) -> Result<Cow<'_, [u8]>, Self::Error> {
         Ok(Cow::from([*item].to_vec()))
     }
+
+    fn encoded_size(_item: &Self::EItem) -> Option<usize> {
+        Some(size_of::<Self::EItem>())
+    }
+
+    fn encodes_into_writer(_item: &Self::EItem) -> bool {
+        true
+    }
+
+    fn bytes_encode_into_writer<W: io::Write>(
+        item: &Self::EItem,
+        mut writer: W,
+    ) -> Result<(), EncodeIntoWriterError<Self::Error>> {
+        writer.write_u8(*item).map_err(Into::into)
+    }
 }
 
 impl BytesDecode<'_> for U8 {
     type DItem = u8;
+    type Error = io::Error;
 
-    fn bytes_decode(mut bytes: &'_ [u8]) -> Result<Self::DItem, BoxedError> {
-        bytes.read_u8().map_err(Into::into)
+    fn bytes_decode(mut bytes: &'_ [u8]) -> Result<Self::DItem, Self::Error> {
+        bytes.read_u8()
     }
 }
 
@@ -29,17 +48,34 @@ pub struct I8;
 
 impl BytesEncode<'_> for I8 {
     type EItem = i8;
+    type Error = Infallible;
 
-    fn bytes_encode(item: &Self::EItem) -> Result<Cow<[u8]>, BoxedError> {
+    fn bytes_encode(item: &Self::EItem) -> Result<Cow<'_, [u8]>, Self::Error> {
         Ok(Cow::from([*item as u8].to_vec()))
     }
+
+    fn encoded_size(_item: &Self::EItem) -> Option<usize> {
+        Some(size_of::<Self::EItem>())
+    }
+
+    fn encodes_into_writer(_item: &Self::EItem) -> bool {
+        true
+    }
+
+    fn bytes_encode_into_writer<W: io::Write>(
+        item: &Self::EItem,
+        mut writer: W,
+    ) -> Result<(), EncodeIntoWriterError<Self::Error>> {
+        writer.write_i8(*item).map_err(Into::into)
+    }
 }
 
 impl BytesDecode<'_> for I8 {
     type DItem = i8;
+    type Error = io::Error;
 
-    fn bytes_decode(mut bytes: &'_ [u8]) -> Result<Self::DItem, BoxedError> {
-        bytes.read_i8().map_err(Into::into)
+    fn bytes_decode(mut bytes: &'_ [u8]) -> Result<Self::DItem, Self::Error> {
+        bytes.read_i8()
     }
 }
 
@@ -53,19 +89,36 @@ macro_rules! define_type {
 
         impl<O: ByteOrder> BytesEncode<'_> for $name<O> {
             type EItem = $native;
+            type Error = Infallible;
 
-            fn bytes_encode(item: &Self::EItem) -> Result<Cow<[u8]>, BoxedError> {
+            fn bytes_encode(item: &Self::EItem) -> Result<Cow<'_, [u8]>, Self::Error> {
                 let mut buf = vec![0; size_of::<Self::EItem>()];
                 O::$write_method(&mut buf, *item);
                 Ok(Cow::from(buf))
             }
+
+            fn encoded_size(_item: &Self::EItem) -> Option<usize> {
+                Some(size_of::<Self::EItem>())
+            }
+
+            fn encodes_into_writer(_item: &Self::EItem) -> bool {
+                true
+            }
+
+            fn bytes_encode_into_writer<W: io::Write>(
+                item: &Self::EItem,
+                mut writer: W,
+            ) -> Result<(), EncodeIntoWriterError<Self::Error>> {
+                writer.$write_method::<O>(*item).map_err(Into::into)
+            }
         }
 
         impl<O: ByteOrder> BytesDecode<'_> for $name<O> {
             type DItem = $native;
+            type Error = io::Error;
 
-            fn bytes_decode(mut bytes: &'_ [u8]) -> Result<Self::DItem, BoxedError> {
-                bytes.$read_method::<O>().map_err(Into::into)
+            fn bytes_decode(mut bytes: &'_ [u8]) -> Result<Self::DItem, Self::Error> {
+                bytes.$read_method::<O>()
             }
         }
     };
@@ -79,3 +132,58 @@ define_type!(I16, i16, read_i16, write_i16);
 define_type!(I32, i32, read_i32, write_i32);
 define_type!(I64, i64, read_i64, write_i64);
 define_type!(I128, i128, read_i128, write_i128);
+
+macro_rules! define_native_key {
+    ($name:ident, $native:ident) => {
+        #[doc = "Encodable version of [`"]
+        #[doc = stringify!($native)]
+        #[doc = "`] in native endianness, the integer layout expected by LMDB's `INTEGER_KEY`"]
+        #[doc = "and `INTEGER_DUP` flags."]
+        ///
+        /// Its bytes are only ordered numerically by LMDB when one of these flags is set,
+        /// they must not be moved between platforms of different endianness.
+        pub struct $name;
+
+        impl BytesEncode<'_> for $name {
+            type EItem = $native;
+            type Error = Infallible;
+
+            fn bytes_encode(item: &Self::EItem) -> Result<Cow<'_, [u8]>, Self::Error> {
+                Ok(Cow::from(item.to_ne_bytes().to_vec()))
+            }
+
+            fn encoded_size(_item: &Self::EItem) -> Option<usize> {
+                Some(size_of::<Self::EItem>())
+            }
+
+            fn encodes_into_writer(_item: &Self::EItem) -> bool {
+                true
+            }
+
+            fn bytes_encode_into_writer<W: io::Write>(
+                item: &Self::EItem,
+                mut writer: W,
+            ) -> Result<(), EncodeIntoWriterError<Self::Error>> {
+                writer.write_all(&item.to_ne_bytes()).map_err(Into::into)
+            }
+        }
+
+        impl BytesDecode<'_> for $name {
+            type DItem = $native;
+            type Error = io::Error;
+
+            fn bytes_decode(bytes: &'_ [u8]) -> Result<Self::DItem, Self::Error> {
+                match bytes.try_into() {
+                    Ok(bytes) => Ok($native::from_ne_bytes(bytes)),
+                    Err(_) => Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        concat!("the bytes are not a native ", stringify!($native)),
+                    )),
+                }
+            }
+        }
+    };
+}
+
+define_native_key!(NativeU32Key, u32);
+define_native_key!(NativeUsizeKey, usize);
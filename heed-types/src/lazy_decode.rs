@@ -1,7 +1,6 @@
+use std::convert::Infallible;
 use std::marker;
 
-use heed_traits::BoxedError;
-
 /// Lazily decodes the data bytes.
 ///
 /// It can be used to avoid CPU-intensive decoding before making sure that it
@@ -11,8 +10,9 @@ pub struct LazyDecode<C>(marker::PhantomData<C>);
 
 impl<'a, C: 'static> heed_traits::BytesDecode<'a> for LazyDecode<C> {
     type DItem = Lazy<'a, C>;
+    type Error = Infallible;
 
-    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, BoxedError> {
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, Self::Error> {
         Ok(Lazy { data: bytes, _phantom: marker::PhantomData })
     }
 }
@@ -31,7 +31,7 @@ impl<'a, C: heed_traits::BytesDecode<'a>> Lazy<'a, C> {
     }
 
     /// Decode the given bytes as `DItem`.
-    pub fn decode(&self) -> Result<C::DItem, BoxedError> {
+    pub fn decode(&self) -> Result<C::DItem, C::Error> {
         C::bytes_decode(self.data)
     }
 }
@@ -13,27 +13,88 @@ mod bytes;
 mod decode_ignore;
 mod integer;
 mod lazy_decode;
+mod ordered;
 mod str;
+mod tuple;
 mod unit;
+mod versioned;
+
+#[cfg(any(feature = "crc32c", feature = "xxhash"))]
+mod checksummed;
+
+#[cfg(any(feature = "lz4", feature = "zstd"))]
+mod compressed;
+
+#[cfg(any(feature = "aes-gcm", feature = "chacha20poly1305"))]
+mod encrypted;
+
+#[cfg(feature = "bytemuck")]
+mod pod;
+
+#[cfg(feature = "rkyv")]
+mod rkyv;
 
 #[cfg(feature = "serde-bincode")]
 mod serde_bincode;
 
+#[cfg(feature = "serde-bitcode")]
+mod serde_bitcode;
+
+#[cfg(feature = "serde-cbor")]
+mod serde_cbor;
+
 #[cfg(feature = "serde-json")]
 mod serde_json;
 
+#[cfg(feature = "serde-ordered")]
+mod serde_ordered;
+
+#[cfg(feature = "serde-postcard")]
+mod serde_postcard;
+
 #[cfg(feature = "serde-rmp")]
 mod serde_rmp;
 
 pub use self::bytes::Bytes;
+#[cfg(feature = "crc32c")]
+pub use self::checksummed::Crc32c;
+#[cfg(feature = "xxhash")]
+pub use self::checksummed::Xxh3;
+#[cfg(any(feature = "crc32c", feature = "xxhash"))]
+pub use self::checksummed::{Checksum, ChecksumError, Checksummed};
+#[cfg(feature = "lz4")]
+pub use self::compressed::Lz4;
+#[cfg(any(feature = "lz4", feature = "zstd"))]
+pub use self::compressed::{
+    Algorithm, Compressed, CompressedError, Uncompressed, DEFAULT_MAX_DECOMPRESSED_SIZE,
+};
+#[cfg(feature = "zstd")]
+pub use self::compressed::{Zstd, ZstdDict, ZstdDictionary};
 pub use self::decode_ignore::DecodeIgnore;
+#[cfg(any(feature = "aes-gcm", feature = "chacha20poly1305"))]
+pub use self::encrypted::{Encrypted, EncryptedError, EncryptionKey, KeyProvider};
 pub use self::integer::*;
 pub use self::lazy_decode::{Lazy, LazyDecode};
+pub use self::ordered::*;
+#[cfg(feature = "bytemuck")]
+pub use self::pod::{Pod, PodSlice};
+#[cfg(feature = "rkyv")]
+pub use self::rkyv::{ArchivedRef, Rkyv};
 #[cfg(feature = "serde-bincode")]
-pub use self::serde_bincode::SerdeBincode;
+pub use self::serde_bincode::{BincodeOptions, SerdeBincode, SerdeBincodeWith};
+#[cfg(feature = "serde-bitcode")]
+pub use self::serde_bitcode::Bitcode;
+#[cfg(feature = "serde-cbor")]
+pub use self::serde_cbor::SerdeCbor;
 #[cfg(feature = "serde-json")]
 pub use self::serde_json::SerdeJson;
+#[cfg(feature = "serde-ordered")]
+pub use self::serde_ordered::{SerdeOrdered, SerdeOrderedError};
+#[cfg(feature = "serde-postcard")]
+pub use self::serde_postcard::SerdePostcard;
 #[cfg(feature = "serde-rmp")]
 pub use self::serde_rmp::SerdeRmp;
 pub use self::str::Str;
+pub use self::tuple::{KeyPart, Tuple, TupleError};
 pub use self::unit::Unit;
+pub use self::versioned::{NoPrevious, PreviousVersion, Version, Versioned, VersionedError};
@@ -0,0 +1,250 @@
+use std::borrow::Cow;
+use std::convert::Infallible;
+use std::io;
+
+use byteorder::{BigEndian, ByteOrder, ReadBytesExt, WriteBytesExt};
+use heed_traits::{BytesDecode, BytesEncode, EncodeIntoWriterError};
+
+/// Encodable version of [`i8`] whose byte order matches the numeric order.
+///
+/// The number is stored with its sign bit flipped, negative numbers are
+/// therefore sorted before positive ones by the default lexicographic comparator.
+pub enum OrderedI8 {}
+
+impl BytesEncode<'_> for OrderedI8 {
+    type EItem = i8;
+    type Error = Infallible;
+
+    fn bytes_encode(item: &Self::EItem) -> Result<Cow<'_, [u8]>, Self::Error> {
+        Ok(Cow::from([*item as u8 ^ 0x80].to_vec()))
+    }
+
+    fn encoded_size(_item: &Self::EItem) -> Option<usize> {
+        Some(std::mem::size_of::<Self::EItem>())
+    }
+
+    fn encodes_into_writer(_item: &Self::EItem) -> bool {
+        true
+    }
+
+    fn bytes_encode_into_writer<W: io::Write>(
+        item: &Self::EItem,
+        mut writer: W,
+    ) -> Result<(), EncodeIntoWriterError<Self::Error>> {
+        writer.write_u8(*item as u8 ^ 0x80).map_err(Into::into)
+    }
+}
+
+impl BytesDecode<'_> for OrderedI8 {
+    type DItem = i8;
+    type Error = io::Error;
+
+    fn bytes_decode(mut bytes: &'_ [u8]) -> Result<Self::DItem, Self::Error> {
+        Ok((bytes.read_u8()? ^ 0x80) as i8)
+    }
+}
+
+macro_rules! define_signed_type {
+    ($name:ident, $native:ident, $unsigned:ident, $read_method:ident, $write_method:ident) => {
+        #[doc = "Encodable version of [`"]
+        #[doc = stringify!($native)]
+        #[doc = "`] whose byte order matches the numeric order."]
+        ///
+        /// The number is stored in big endian with its sign bit flipped,
+        /// negative numbers are therefore sorted before positive ones
+        /// by the default lexicographic comparator.
+        pub enum $name {}
+
+        impl BytesEncode<'_> for $name {
+            type EItem = $native;
+            type Error = Infallible;
+
+            fn bytes_encode(item: &Self::EItem) -> Result<Cow<'_, [u8]>, Self::Error> {
+                let mut buf = vec![0; std::mem::size_of::<Self::EItem>()];
+                let flipped = (*item as $unsigned) ^ (1 << ($unsigned::BITS - 1));
+                BigEndian::$write_method(&mut buf, flipped);
+                Ok(Cow::from(buf))
+            }
+
+            fn encoded_size(_item: &Self::EItem) -> Option<usize> {
+                Some(std::mem::size_of::<Self::EItem>())
+            }
+
+            fn encodes_into_writer(_item: &Self::EItem) -> bool {
+                true
+            }
+
+            fn bytes_encode_into_writer<W: io::Write>(
+                item: &Self::EItem,
+                mut writer: W,
+            ) -> Result<(), EncodeIntoWriterError<Self::Error>> {
+                let flipped = (*item as $unsigned) ^ (1 << ($unsigned::BITS - 1));
+                writer.$write_method::<BigEndian>(flipped).map_err(Into::into)
+            }
+        }
+
+        impl BytesDecode<'_> for $name {
+            type DItem = $native;
+            type Error = io::Error;
+
+            fn bytes_decode(mut bytes: &'_ [u8]) -> Result<Self::DItem, Self::Error> {
+                let flipped = bytes.$read_method::<BigEndian>()?;
+                Ok((flipped ^ (1 << ($unsigned::BITS - 1))) as $native)
+            }
+        }
+    };
+}
+
+define_signed_type!(OrderedI16, i16, u16, read_u16, write_u16);
+define_signed_type!(OrderedI32, i32, u32, read_u32, write_u32);
+define_signed_type!(OrderedI64, i64, u64, read_u64, write_u64);
+define_signed_type!(OrderedI128, i128, u128, read_u128, write_u128);
+
+macro_rules! define_float_type {
+    ($name:ident, $native:ident, $unsigned:ident, $read_method:ident, $write_method:ident) => {
+        #[doc = "Encodable version of [`"]
+        #[doc = stringify!($native)]
+        #[doc = "`] whose byte order matches the numeric order."]
+        ///
+        /// The number is stored in big endian following the IEEE 754 `totalOrder` predicate,
+        #[doc = concat!("the order of [`", stringify!($native), "::total_cmp`]: the negative NaNs come first,")]
+        /// followed by the negative numbers, `-0.0`, `+0.0`, the positive numbers
+        /// and finally the positive NaNs.
+        pub enum $name {}
+
+        impl BytesEncode<'_> for $name {
+            type EItem = $native;
+            type Error = Infallible;
+
+            fn bytes_encode(item: &Self::EItem) -> Result<Cow<'_, [u8]>, Self::Error> {
+                const SIGN: $unsigned = 1 << ($unsigned::BITS - 1);
+
+                let bits = item.to_bits();
+                // Negative numbers have all their bits flipped to reverse their order,
+                // positive numbers only have their sign bit flipped.
+                let ordered = if bits & SIGN == SIGN { !bits } else { bits ^ SIGN };
+                let mut buf = vec![0; std::mem::size_of::<Self::EItem>()];
+                BigEndian::$write_method(&mut buf, ordered);
+                Ok(Cow::from(buf))
+            }
+
+            fn encoded_size(_item: &Self::EItem) -> Option<usize> {
+                Some(std::mem::size_of::<Self::EItem>())
+            }
+
+            fn encodes_into_writer(_item: &Self::EItem) -> bool {
+                true
+            }
+
+            fn bytes_encode_into_writer<W: io::Write>(
+                item: &Self::EItem,
+                mut writer: W,
+            ) -> Result<(), EncodeIntoWriterError<Self::Error>> {
+                const SIGN: $unsigned = 1 << ($unsigned::BITS - 1);
+
+                let bits = item.to_bits();
+                let ordered = if bits & SIGN == SIGN { !bits } else { bits ^ SIGN };
+                writer.$write_method::<BigEndian>(ordered).map_err(Into::into)
+            }
+        }
+
+        impl BytesDecode<'_> for $name {
+            type DItem = $native;
+            type Error = io::Error;
+
+            fn bytes_decode(mut bytes: &'_ [u8]) -> Result<Self::DItem, Self::Error> {
+                const SIGN: $unsigned = 1 << ($unsigned::BITS - 1);
+
+                let ordered = bytes.$read_method::<BigEndian>()?;
+                let bits = if ordered & SIGN == SIGN { ordered ^ SIGN } else { !ordered };
+                Ok($native::from_bits(bits))
+            }
+        }
+    };
+}
+
+define_float_type!(OrderedF32, f32, u32, read_u32, write_u32);
+define_float_type!(OrderedF64, f64, u64, read_u64, write_u64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encodes the items with both encoding methods and checks they give the same bytes.
+    fn encode_all<C, T>(items: &[T]) -> Vec<Vec<u8>>
+    where
+        C: for<'a> BytesEncode<'a, EItem = T, Error = Infallible>,
+    {
+        items
+            .iter()
+            .map(|item| {
+                let bytes = C::bytes_encode(item).unwrap().into_owned();
+                let mut written = Vec::new();
+                C::bytes_encode_into_writer(item, &mut written).unwrap();
+                assert_eq!(bytes, written);
+                assert_eq!(C::encoded_size(item), Some(bytes.len()));
+                bytes
+            })
+            .collect()
+    }
+
+    #[test]
+    fn signed_integers_byte_order() {
+        let numbers = [i64::MIN, -300, -1, 0, 1, 42, i64::MAX];
+        let encoded = encode_all::<OrderedI64, _>(&numbers);
+        assert!(encoded.windows(2).all(|w| w[0] < w[1]));
+        let decoded: Vec<_> =
+            encoded.iter().map(|b| OrderedI64::bytes_decode(b).unwrap()).collect();
+        assert_eq!(decoded, numbers);
+
+        let numbers = [i8::MIN, -1, 0, 1, i8::MAX];
+        let encoded = encode_all::<OrderedI8, _>(&numbers);
+        assert!(encoded.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(encoded[2], [0x80]);
+
+        let numbers = [i128::MIN, -1, 0, i128::MAX];
+        let encoded = encode_all::<OrderedI128, _>(&numbers);
+        assert!(encoded.windows(2).all(|w| w[0] < w[1]));
+        let decoded: Vec<_> =
+            encoded.iter().map(|b| OrderedI128::bytes_decode(b).unwrap()).collect();
+        assert_eq!(decoded, numbers);
+    }
+
+    #[test]
+    fn floats_byte_order() {
+        let numbers = [
+            -f64::NAN,
+            f64::NEG_INFINITY,
+            -1e10,
+            -2.5,
+            -f64::MIN_POSITIVE,
+            -0.0,
+            0.0,
+            f64::MIN_POSITIVE / 2.0,
+            1e-10,
+            2.5,
+            f64::INFINITY,
+            f64::NAN,
+        ];
+        assert!(numbers.windows(2).all(|w| w[0].total_cmp(&w[1]).is_lt()));
+        let encoded = encode_all::<OrderedF64, _>(&numbers);
+        assert!(encoded.windows(2).all(|w| w[0] < w[1]));
+        let decoded: Vec<_> =
+            encoded.iter().map(|b| OrderedF64::bytes_decode(b).unwrap()).collect();
+        let bits = |n: &[f64]| n.iter().map(|n| n.to_bits()).collect::<Vec<_>>();
+        assert_eq!(bits(&decoded), bits(&numbers));
+
+        let numbers = [f32::NEG_INFINITY, -1.5, -0.0, 0.0, 1.5, f32::INFINITY];
+        let encoded = encode_all::<OrderedF32, _>(&numbers);
+        assert!(encoded.windows(2).all(|w| w[0] < w[1]));
+        assert!(OrderedF32::bytes_decode(&encoded[2]).unwrap().is_sign_negative());
+    }
+
+    #[test]
+    fn truncated_bytes() {
+        let error = OrderedI64::bytes_decode(&[0; 7]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        assert!(OrderedF32::bytes_decode(&[]).is_err());
+        assert!(OrderedI8::bytes_decode(&[]).is_err());
+    }
+}
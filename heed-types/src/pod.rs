@@ -0,0 +1,153 @@
+use std::borrow::Cow;
+use std::convert::Infallible;
+use std::marker::PhantomData;
+use std::mem::size_of;
+
+use bytemuck::{Pod as BytemuckPod, PodCastError};
+use heed_traits::{BytesDecode, BytesEncode};
+
+/// Describes a type that is [`Pod`](bytemuck::Pod) and that is stored as its raw bytes.
+///
+/// Encoding borrows the bytes of the value without allocating and decoding
+/// reinterprets the bytes as a reference to the value without copying them.
+/// If the bytes returned by LMDB are not aligned for `T`, the value is copied
+/// instead and an owned value is returned.
+///
+/// The bytes are stored in the native endianness and layout of the machine,
+/// they are not portable between architectures.
+pub struct Pod<T>(PhantomData<T>);
+
+impl<'a, T: BytemuckPod> BytesEncode<'a> for Pod<T> {
+    type EItem = T;
+    type Error = Infallible;
+
+    fn bytes_encode(item: &'a Self::EItem) -> Result<Cow<'a, [u8]>, Self::Error> {
+        Ok(Cow::Borrowed(bytemuck::bytes_of(item)))
+    }
+}
+
+impl<'a, T: BytemuckPod> BytesDecode<'a> for Pod<T> {
+    type DItem = Cow<'a, T>;
+    type Error = PodCastError;
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, Self::Error> {
+        match bytemuck::try_from_bytes(bytes) {
+            Ok(item) => Ok(Cow::Borrowed(item)),
+            Err(PodCastError::TargetAlignmentGreaterAndInputNotAligned) => {
+                Ok(Cow::Owned(bytemuck::pod_read_unaligned(bytes)))
+            }
+            Err(error) => Err(error),
+        }
+    }
+}
+
+unsafe impl<T> Send for Pod<T> {}
+
+unsafe impl<T> Sync for Pod<T> {}
+
+/// Describes a slice of a type that is [`Pod`](bytemuck::Pod) and that is stored as its raw bytes.
+///
+/// Encoding borrows the bytes of the slice without allocating and decoding
+/// reinterprets the bytes as a slice without copying them. If the bytes returned
+/// by LMDB are not aligned for `T`, the slice is copied into an aligned vector instead.
+///
+/// The bytes are stored in the native endianness and layout of the machine,
+/// they are not portable between architectures.
+pub struct PodSlice<T>(PhantomData<T>);
+
+impl<'a, T: BytemuckPod> BytesEncode<'a> for PodSlice<T> {
+    type EItem = [T];
+    type Error = Infallible;
+
+    fn bytes_encode(item: &'a Self::EItem) -> Result<Cow<'a, [u8]>, Self::Error> {
+        Ok(Cow::Borrowed(bytemuck::cast_slice(item)))
+    }
+}
+
+impl<'a, T: BytemuckPod> BytesDecode<'a> for PodSlice<T> {
+    type DItem = Cow<'a, [T]>;
+    type Error = PodCastError;
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, Self::Error> {
+        match bytemuck::try_cast_slice(bytes) {
+            Ok(items) => Ok(Cow::Borrowed(items)),
+            Err(PodCastError::TargetAlignmentGreaterAndInputNotAligned) => {
+                let chunks = bytes.chunks_exact(size_of::<T>());
+                if !chunks.remainder().is_empty() {
+                    return Err(PodCastError::OutputSliceWouldHaveSlop);
+                }
+                let mut items = vec![T::zeroed(); chunks.len()];
+                bytemuck::cast_slice_mut(&mut items).copy_from_slice(bytes);
+                Ok(Cow::Owned(items))
+            }
+            Err(error) => Err(error),
+        }
+    }
+}
+
+unsafe impl<T> Send for PodSlice<T> {}
+
+unsafe impl<T> Sync for PodSlice<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a copy of the bytes that is not aligned for a `u64`.
+    fn unaligned(bytes: &[u8], storage: &mut Vec<u8>) -> std::ops::Range<usize> {
+        *storage = vec![0; bytes.len() + 2];
+        let offset = if (storage.as_ptr() as usize + 1) % 8 == 0 { 2 } else { 1 };
+        storage[offset..][..bytes.len()].copy_from_slice(bytes);
+        offset..offset + bytes.len()
+    }
+
+    #[test]
+    fn aligned_bytes_are_borrowed() {
+        let words = [42u64, 43, 44];
+        let bytes = PodSlice::<u64>::bytes_encode(&words).unwrap();
+        assert!(matches!(bytes, Cow::Borrowed(_)));
+        assert_eq!(bytes.len(), 24);
+
+        let decoded = PodSlice::<u64>::bytes_decode(&bytes[..16]).unwrap();
+        assert!(matches!(decoded, Cow::Borrowed(&[42, 43])));
+        let decoded = Pod::<u64>::bytes_decode(&bytes[..8]).unwrap();
+        assert!(matches!(decoded, Cow::Borrowed(&42)));
+
+        let point = [1.5f64, -2.0, 0.0];
+        let bytes = Pod::<[f64; 3]>::bytes_encode(&point).unwrap();
+        assert_eq!(Pod::<[f64; 3]>::bytes_decode(&bytes).unwrap().as_ref(), &point);
+    }
+
+    #[test]
+    fn unaligned_bytes_are_copied() {
+        let words = [42u64, 43, 44];
+        let bytes = PodSlice::<u64>::bytes_encode(&words).unwrap();
+        let mut storage = Vec::new();
+        let range = unaligned(&bytes, &mut storage);
+        let bytes = &storage[range];
+
+        let decoded = PodSlice::<u64>::bytes_decode(&bytes[..16]).unwrap();
+        assert!(matches!(&decoded, Cow::Owned(words) if words == &[42, 43]));
+        let decoded = Pod::<u64>::bytes_decode(&bytes[..8]).unwrap();
+        assert!(matches!(decoded, Cow::Owned(42)));
+        let decoded = PodSlice::<u64>::bytes_decode(&[]).unwrap();
+        assert!(decoded.is_empty());
+    }
+
+    #[test]
+    fn wrong_sizes() {
+        let words = [42u64, 43];
+        let bytes = PodSlice::<u64>::bytes_encode(&words).unwrap();
+        let mut storage = Vec::new();
+        let range = unaligned(&bytes, &mut storage);
+
+        for bytes in [&bytes[..], &storage[range]] {
+            assert_eq!(
+                PodSlice::<u64>::bytes_decode(&bytes[..12]).unwrap_err(),
+                PodCastError::OutputSliceWouldHaveSlop
+            );
+            assert!(Pod::<u64>::bytes_decode(&bytes[..4]).is_err());
+            assert!(Pod::<u64>::bytes_decode(bytes).is_err());
+        }
+    }
+}
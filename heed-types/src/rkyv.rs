@@ -0,0 +1,168 @@
+use std::borrow::Cow;
+use std::marker::PhantomData;
+use std::{fmt, ops};
+
+use heed_traits::{BytesDecode, BytesEncode};
+use rkyv::api::high::{HighSerializer, HighValidator};
+use rkyv::bytecheck::CheckBytes;
+use rkyv::ser::allocator::ArenaHandle;
+use rkyv::util::AlignedVec;
+use rkyv::{rancor, Archive, Serialize};
+
+/// Describes a type that is [`Archive`]d by `rkyv` and that can be accessed without
+/// being deserialized.
+///
+/// The decoded item is an [`ArchivedRef`] that dereferences to the validated
+/// `Archived<T>`. The archive is accessed directly from the memory map when the bytes
+/// returned by LMDB are aligned on 16 bytes, and from an aligned copy otherwise.
+/// LMDB only aligns the values on 2 bytes, the values are therefore copied unless the
+/// keys and values sizes are chosen to keep them aligned, e.g. with a database where
+/// the keys are 8 bytes long and the value size is a multiple of 16.
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use heed_traits::{BytesDecode, BytesEncode};
+/// use heed_types::Rkyv;
+///
+/// #[derive(rkyv::Archive, rkyv::Serialize)]
+/// struct Record {
+///     id: u64,
+///     tags: Vec<String>,
+/// }
+///
+/// let record = Record { id: 42, tags: vec!["heed".into(), "lmdb".into()] };
+/// let bytes = Rkyv::<Record>::bytes_encode(&record)?;
+/// let archived = Rkyv::<Record>::bytes_decode(&bytes)?;
+/// assert_eq!(archived.id, 42);
+/// assert_eq!(archived.tags[1], "lmdb");
+/// # Ok(()) }
+/// ```
+pub struct Rkyv<T>(PhantomData<T>);
+
+impl<'a, T: 'a> BytesEncode<'a> for Rkyv<T>
+where
+    T: for<'b> Serialize<HighSerializer<AlignedVec, ArenaHandle<'b>, rancor::Error>>,
+{
+    type EItem = T;
+    type Error = rancor::Error;
+
+    fn bytes_encode(item: &'a Self::EItem) -> Result<Cow<'a, [u8]>, Self::Error> {
+        rkyv::to_bytes(item).map(|bytes| Cow::Owned(bytes.into_vec()))
+    }
+}
+
+impl<'a, T: 'a> BytesDecode<'a> for Rkyv<T>
+where
+    T: Archive,
+    T::Archived: for<'b> CheckBytes<HighValidator<'b, rancor::Error>>,
+{
+    type DItem = ArchivedRef<'a, T>;
+    type Error = rancor::Error;
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, Self::Error> {
+        if bytes.as_ptr().align_offset(<AlignedVec>::ALIGNMENT) == 0 {
+            rkyv::access::<T::Archived, _>(bytes)
+                .map(|archived| ArchivedRef(Inner::Borrowed(archived)))
+        } else {
+            let mut aligned = AlignedVec::with_capacity(bytes.len());
+            aligned.extend_from_slice(bytes);
+            rkyv::access::<T::Archived, rancor::Error>(&aligned)?;
+            Ok(ArchivedRef(Inner::Owned(aligned, PhantomData)))
+        }
+    }
+}
+
+unsafe impl<T> Send for Rkyv<T> {}
+
+unsafe impl<T> Sync for Rkyv<T> {}
+
+/// A validated `Archived<T>` decoded by the [`Rkyv`] codec.
+///
+/// It either borrows the archive from the database or owns an aligned copy of it,
+/// and dereferences to the archive in both cases.
+pub struct ArchivedRef<'a, T: Archive>(Inner<'a, T>);
+
+enum Inner<'a, T: Archive> {
+    /// The archive is accessed directly from the bytes returned by LMDB.
+    Borrowed(&'a T::Archived),
+    /// The bytes were not aligned and have been copied into a validated aligned buffer.
+    Owned(AlignedVec, PhantomData<T>),
+}
+
+impl<T: Archive> ArchivedRef<'_, T> {
+    /// Returns `true` if the archive is borrowed from the database, `false` if it was copied.
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self.0, Inner::Borrowed(_))
+    }
+}
+
+impl<T: Archive> ops::Deref for ArchivedRef<'_, T> {
+    type Target = T::Archived;
+
+    fn deref(&self) -> &Self::Target {
+        match &self.0 {
+            Inner::Borrowed(archived) => archived,
+            // SAFETY: the bytes have been validated when the value was decoded.
+            Inner::Owned(bytes, _) => unsafe { rkyv::access_unchecked(bytes) },
+        }
+    }
+}
+
+impl<T: Archive> fmt::Debug for ArchivedRef<'_, T>
+where
+    T::Archived: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Archive, Serialize)]
+    struct Profile {
+        name: String,
+        friends: Vec<u64>,
+    }
+
+    fn profile() -> AlignedVec {
+        let profile = Profile { name: "Kerollmops".into(), friends: vec![1, 2] };
+        let mut aligned = AlignedVec::new();
+        aligned.extend_from_slice(&Rkyv::<Profile>::bytes_encode(&profile).unwrap());
+        aligned
+    }
+
+    #[test]
+    fn aligned_bytes_are_borrowed() {
+        let bytes = profile();
+        let profile = Rkyv::<Profile>::bytes_decode(&bytes).unwrap();
+        assert!(profile.is_borrowed());
+        assert_eq!(profile.name, "Kerollmops");
+        assert_eq!(profile.friends.as_slice(), &[1, 2]);
+    }
+
+    #[test]
+    fn unaligned_bytes_are_copied() {
+        let bytes = profile();
+        let mut unaligned = AlignedVec::<16>::with_capacity(bytes.len() + 1);
+        unaligned.push(0);
+        unaligned.extend_from_slice(&bytes);
+
+        let profile = Rkyv::<Profile>::bytes_decode(&unaligned[1..]).unwrap();
+        assert!(!profile.is_borrowed());
+        assert_eq!(profile.name, "Kerollmops");
+        assert_eq!(profile.friends.as_slice(), &[1, 2]);
+    }
+
+    #[test]
+    fn invalid_archives_are_rejected() {
+        let mut bytes = AlignedVec::<16>::new();
+        bytes.extend_from_slice(&[0xff; 17]);
+        assert!(Rkyv::<Profile>::bytes_decode(&bytes[..16]).is_err());
+        assert!(Rkyv::<Profile>::bytes_decode(&bytes[1..]).is_err());
+        assert!(Rkyv::<Profile>::bytes_decode(&[]).is_err());
+    }
+}
@@ -1,6 +1,8 @@
 use std::borrow::Cow;
+use std::io;
 
-use heed_traits::{BoxedError, BytesDecode, BytesEncode};
+use bincode::Options;
+use heed_traits::{BytesDecode, BytesEncode, EncodeIntoWriterError};
 use serde::{Deserialize, Serialize};
 
 /// Describes a type that is [`Serialize`]/[`Deserialize`] and uses `bincode` to do so.
@@ -13,9 +15,25 @@ where
     T: Serialize,
 {
     type EItem = T;
+    type Error = bincode::Error;
 
-    fn bytes_encode(item: &'a Self::EItem) -> Result<Cow<[u8]>, BoxedError> {
-        bincode::serialize(item).map(Cow::Owned).map_err(Into::into)
+    fn bytes_encode(item: &'a Self::EItem) -> Result<Cow<'a, [u8]>, Self::Error> {
+        bincode::serialize(item).map(Cow::Owned)
+    }
+
+    fn encoded_size(item: &Self::EItem) -> Option<usize> {
+        bincode::serialized_size(item).ok().and_then(|size| size.try_into().ok())
+    }
+
+    fn encodes_into_writer(_item: &Self::EItem) -> bool {
+        true
+    }
+
+    fn bytes_encode_into_writer<W: io::Write>(
+        item: &'a Self::EItem,
+        writer: W,
+    ) -> Result<(), EncodeIntoWriterError<Self::Error>> {
+        bincode::serialize_into(writer, item).map_err(EncodeIntoWriterError::Encoding)
     }
 }
 
@@ -24,12 +42,177 @@ where
     T: Deserialize<'a>,
 {
     type DItem = T;
+    type Error = bincode::Error;
 
-    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, BoxedError> {
-        bincode::deserialize(bytes).map_err(Into::into)
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, Self::Error> {
+        bincode::deserialize(bytes)
     }
 }
 
 unsafe impl<T> Send for SerdeBincode<T> {}
 
 unsafe impl<T> Sync for SerdeBincode<T> {}
+
+/// Describes a type that is [`Serialize`]/[`Deserialize`] and uses `bincode` with the
+/// options provided by `O` to do so.
+///
+/// Contrary to [`SerdeBincode`] which uses the fixed-size little-endian integers of
+/// `bincode::serialize`, it allows to use varint integers to shrink the values, big-endian
+/// integers, or to bound the size of the encoded values.
+///
+/// The values are decoded from the slice stored in the database: `bincode` checks the
+/// lengths against the remaining bytes before allocating, the allocations are therefore
+/// bounded by the size of the value even when the bytes are not trusted.
+///
+/// It can borrow bytes from the original slice.
+pub struct SerdeBincodeWith<T, O>(std::marker::PhantomData<(T, O)>);
+
+impl<'a, T: 'a, O> BytesEncode<'a> for SerdeBincodeWith<T, O>
+where
+    T: Serialize,
+    O: BincodeOptions,
+{
+    type EItem = T;
+    type Error = bincode::Error;
+
+    fn bytes_encode(item: &'a Self::EItem) -> Result<Cow<'a, [u8]>, Self::Error> {
+        O::options().serialize(item).map(Cow::Owned)
+    }
+
+    fn encoded_size(item: &Self::EItem) -> Option<usize> {
+        O::options().serialized_size(item).ok().and_then(|size| size.try_into().ok())
+    }
+
+    fn encodes_into_writer(_item: &Self::EItem) -> bool {
+        true
+    }
+
+    fn bytes_encode_into_writer<W: io::Write>(
+        item: &'a Self::EItem,
+        writer: W,
+    ) -> Result<(), EncodeIntoWriterError<Self::Error>> {
+        O::options().serialize_into(writer, item).map_err(EncodeIntoWriterError::Encoding)
+    }
+}
+
+impl<'a, T: 'a, O> BytesDecode<'a> for SerdeBincodeWith<T, O>
+where
+    T: Deserialize<'a>,
+    O: BincodeOptions,
+{
+    type DItem = T;
+    type Error = bincode::Error;
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, Self::Error> {
+        O::options().deserialize(bytes)
+    }
+}
+
+unsafe impl<T, O> Send for SerdeBincodeWith<T, O> {}
+
+unsafe impl<T, O> Sync for SerdeBincodeWith<T, O> {}
+
+/// Provides the `bincode` options used by the [`SerdeBincodeWith`] codec.
+///
+/// The options must not change once values have been written with them.
+///
+/// ```
+/// use bincode::config::{BigEndian, Bounded, WithOtherEndian, WithOtherLimit};
+/// use bincode::{DefaultOptions, Options};
+/// use heed_types::BincodeOptions;
+///
+/// /// Varint and big-endian integers, values larger than 64 KiB cannot be written.
+/// enum Compact {}
+///
+/// impl BincodeOptions for Compact {
+///     type Options = WithOtherLimit<WithOtherEndian<DefaultOptions, BigEndian>, Bounded>;
+///
+///     fn options() -> Self::Options {
+///         DefaultOptions::new().with_big_endian().with_limit(64 * 1024)
+///     }
+/// }
+/// ```
+pub trait BincodeOptions {
+    /// The type of the options.
+    type Options: bincode::Options;
+
+    /// Returns the options used to encode and decode the values.
+    fn options() -> Self::Options;
+}
+
+/// The default `bincode` options: varint little-endian integers, no size limit,
+/// and trailing bytes are rejected.
+impl BincodeOptions for bincode::DefaultOptions {
+    type Options = bincode::DefaultOptions;
+
+    fn options() -> Self::Options {
+        bincode::DefaultOptions::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bincode::config::{BigEndian, Bounded, WithOtherEndian, WithOtherLimit};
+    use bincode::{DefaultOptions, ErrorKind};
+
+    use super::*;
+
+    enum Bounded16 {}
+
+    impl BincodeOptions for Bounded16 {
+        type Options = WithOtherLimit<WithOtherEndian<DefaultOptions, BigEndian>, Bounded>;
+
+        fn options() -> Self::Options {
+            DefaultOptions::new().with_big_endian().with_limit(16)
+        }
+    }
+
+    #[test]
+    fn fixint_and_varint_integers() {
+        let small = vec![1u64, 2, 3];
+        let fixint = SerdeBincode::<Vec<u64>>::bytes_encode(&small).unwrap();
+        assert_eq!(fixint.len(), 32);
+        assert_eq!(SerdeBincode::<Vec<u64>>::encoded_size(&small), Some(32));
+
+        let varint = SerdeBincodeWith::<Vec<u64>, DefaultOptions>::bytes_encode(&small).unwrap();
+        assert_eq!(varint, &[3, 1, 2, 3][..]);
+        assert_eq!(SerdeBincodeWith::<Vec<u64>, DefaultOptions>::encoded_size(&small), Some(4));
+        let mut written = Vec::new();
+        SerdeBincodeWith::<Vec<u64>, DefaultOptions>::bytes_encode_into_writer(
+            &small,
+            &mut written,
+        )
+        .unwrap();
+        assert_eq!(varint, written);
+        assert_eq!(
+            SerdeBincodeWith::<Vec<u64>, DefaultOptions>::bytes_decode(&varint).unwrap(),
+            small
+        );
+
+        let big_endian = SerdeBincodeWith::<u16, Bounded16>::bytes_encode(&300).unwrap();
+        assert_eq!(big_endian, &[251, 0x01, 0x2C][..]);
+    }
+
+    #[test]
+    fn size_limit() {
+        let small = vec![1u64, 2, 3];
+        let bytes = SerdeBincodeWith::<Vec<u64>, Bounded16>::bytes_encode(&small).unwrap();
+        assert_eq!(SerdeBincodeWith::<Vec<u64>, Bounded16>::bytes_decode(&bytes).unwrap(), small);
+
+        let large = vec![u64::MAX; 4];
+        let error = SerdeBincodeWith::<Vec<u64>, Bounded16>::bytes_encode(&large).unwrap_err();
+        assert!(matches!(*error, ErrorKind::SizeLimit));
+        assert_eq!(SerdeBincodeWith::<Vec<u64>, Bounded16>::encoded_size(&large), None);
+    }
+
+    #[test]
+    fn forged_lengths_are_rejected() {
+        // A length of u64::MAX followed by a single byte.
+        let forged = [0xfd, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, b'a'];
+        assert!(SerdeBincodeWith::<String, Bounded16>::bytes_decode(&forged).is_err());
+        assert!(SerdeBincodeWith::<Vec<u8>, DefaultOptions>::bytes_decode(&forged).is_err());
+
+        let error = SerdeBincodeWith::<u8, DefaultOptions>::bytes_decode(&[1, 2]).unwrap_err();
+        assert!(matches!(*error, ErrorKind::Custom(_)));
+    }
+}
@@ -0,0 +1,67 @@
+use std::borrow::Cow;
+
+use heed_traits::{BytesDecode, BytesEncode};
+use serde::{Deserialize, Serialize};
+
+/// Describes a type that is [`Serialize`]/[`Deserialize`] and uses `bitcode` to do so.
+///
+/// It produces very compact bytes and can borrow bytes from the original slice.
+pub struct Bitcode<T>(std::marker::PhantomData<T>);
+
+impl<'a, T: 'a> BytesEncode<'a> for Bitcode<T>
+where
+    T: Serialize,
+{
+    type EItem = T;
+    type Error = bitcode::Error;
+
+    fn bytes_encode(item: &Self::EItem) -> Result<Cow<'_, [u8]>, Self::Error> {
+        bitcode::serialize(item).map(Cow::Owned)
+    }
+}
+
+impl<'a, T: 'a> BytesDecode<'a> for Bitcode<T>
+where
+    T: Deserialize<'a>,
+{
+    type DItem = T;
+    type Error = bitcode::Error;
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, Self::Error> {
+        bitcode::deserialize(bytes)
+    }
+}
+
+unsafe impl<T> Send for Bitcode<T> {}
+
+unsafe impl<T> Sync for Bitcode<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct User<'a> {
+        id: u64,
+        name: &'a str,
+        #[serde(borrow)]
+        avatar: Cow<'a, [u8]>,
+    }
+
+    #[test]
+    fn borrows_from_the_bytes() {
+        let user = User { id: 42, name: "kero", avatar: Cow::Borrowed(&[1, 2, 3]) };
+        let bytes = Bitcode::<User>::bytes_encode(&user).unwrap();
+        let decoded = Bitcode::<User>::bytes_decode(&bytes).unwrap();
+        assert_eq!(decoded, user);
+        assert!(bytes.as_ptr_range().contains(&decoded.name.as_ptr()));
+    }
+
+    #[test]
+    fn invalid_bytes() {
+        let user = User { id: 42, name: "kero", avatar: Cow::Borrowed(&[1, 2, 3]) };
+        let bytes = Bitcode::<User>::bytes_encode(&user).unwrap();
+        assert!(Bitcode::<User>::bytes_decode(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Bitcode::<User>::bytes_decode(&[]).is_err());
+    }
+}
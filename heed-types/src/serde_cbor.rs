@@ -0,0 +1,88 @@
+use std::borrow::Cow;
+use std::io;
+
+use heed_traits::{BytesDecode, BytesEncode, EncodeIntoWriterError};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Describes a type that is [`Serialize`]/[`DeserializeOwned`] and uses `ciborium`
+/// to encode it as CBOR.
+///
+/// It cannot borrow bytes from the original slice, `ciborium` only decodes owned values.
+pub struct SerdeCbor<T>(std::marker::PhantomData<T>);
+
+impl<'a, T: 'a> BytesEncode<'a> for SerdeCbor<T>
+where
+    T: Serialize,
+{
+    type EItem = T;
+    type Error = ciborium::ser::Error<io::Error>;
+
+    fn bytes_encode(item: &Self::EItem) -> Result<Cow<'_, [u8]>, Self::Error> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(item, &mut bytes)?;
+        Ok(Cow::Owned(bytes))
+    }
+
+    fn encodes_into_writer(_item: &Self::EItem) -> bool {
+        true
+    }
+
+    fn bytes_encode_into_writer<W: io::Write>(
+        item: &'a Self::EItem,
+        writer: W,
+    ) -> Result<(), EncodeIntoWriterError<Self::Error>> {
+        ciborium::into_writer(item, writer).map_err(EncodeIntoWriterError::Encoding)
+    }
+}
+
+impl<'a, T: 'a> BytesDecode<'a> for SerdeCbor<T>
+where
+    T: DeserializeOwned,
+{
+    type DItem = T;
+    type Error = ciborium::de::Error<io::Error>;
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, Self::Error> {
+        ciborium::from_reader(bytes)
+    }
+}
+
+unsafe impl<T> Send for SerdeCbor<T> {}
+
+unsafe impl<T> Sync for SerdeCbor<T> {}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct User {
+        id: u64,
+        name: String,
+        avatar: Vec<u8>,
+    }
+
+    #[test]
+    fn roundtrip() {
+        let user = User { id: 42, name: "kero".into(), avatar: vec![1, 2, 3] };
+        let bytes = SerdeCbor::<User>::bytes_encode(&user).unwrap();
+        let mut written = Vec::new();
+        SerdeCbor::<User>::bytes_encode_into_writer(&user, &mut written).unwrap();
+        assert_eq!(bytes, written);
+        assert_eq!(SerdeCbor::<User>::bytes_decode(&bytes).unwrap(), user);
+
+        // The field names are written, CBOR is self-describing.
+        assert!(bytes.windows(4).any(|w| w == b"name"));
+    }
+
+    #[test]
+    fn invalid_bytes() {
+        let user = User { id: 42, name: "kero".into(), avatar: vec![1, 2, 3] };
+        let bytes = SerdeCbor::<User>::bytes_encode(&user).unwrap();
+        assert!(SerdeCbor::<User>::bytes_decode(&bytes[..bytes.len() - 1]).is_err());
+        assert!(SerdeCbor::<(String, u64)>::bytes_decode(&bytes).is_err());
+    }
+}
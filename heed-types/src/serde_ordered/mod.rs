@@ -0,0 +1,235 @@
+use std::borrow::Cow;
+use std::{error, fmt};
+
+use heed_traits::{BytesDecode, BytesEncode};
+use serde::{Deserialize, Serialize};
+
+mod de;
+mod ser;
+
+/// Describes a type that is [`Serialize`]/[`Deserialize`] and uses
+/// an order-preserving binary format to do so.
+///
+/// The lexicographic order of the encoded bytes matches the order of the values, as defined
+/// by `#[derive(PartialOrd, Ord)]`, making it suitable for keys that are range-scanned
+/// with the default comparator:
+///
+/// - integers are stored in big endian, with the sign bit of the signed ones flipped,
+/// - floats follow the IEEE 754 `totalOrder` predicate,
+/// - strings and bytes are escaped and terminated, shorter ones sort first,
+/// - options and the elements of sequences and maps are prefixed by a marker byte,
+/// - enum variants are prefixed by their index, in declaration order,
+/// - structs and tuples are the concatenation of their fields.
+///
+/// The format is not self-describing: the deserialized type must be the serialized one
+/// and it must not use `#[serde(flatten)]`, `#[serde(untagged)]` or skip fields conditionally.
+/// The maps are encoded in their iteration order, prefer a `BTreeMap` to a `HashMap`.
+/// It can borrow strings and bytes from the original slice when they do not
+/// contain any `0x00` byte.
+///
+/// ```
+/// use heed_traits::BytesEncode;
+/// use heed_types::SerdeOrdered;
+///
+/// // The (tenant, timestamp, name) tuples are sorted by tenant, then timestamp and name.
+/// type Event<'a> = (u32, i64, &'a str);
+///
+/// let first = SerdeOrdered::<Event>::bytes_encode(&(1, -10, "logout")).unwrap();
+/// let second = SerdeOrdered::<Event>::bytes_encode(&(1, 3, "login")).unwrap();
+/// assert!(first < second);
+/// ```
+pub struct SerdeOrdered<T>(std::marker::PhantomData<T>);
+
+impl<'a, T: 'a> BytesEncode<'a> for SerdeOrdered<T>
+where
+    T: Serialize,
+{
+    type EItem = T;
+    type Error = SerdeOrderedError;
+
+    fn bytes_encode(item: &Self::EItem) -> Result<Cow<'_, [u8]>, Self::Error> {
+        ser::to_vec(item).map(Cow::Owned)
+    }
+}
+
+impl<'a, T: 'a> BytesDecode<'a> for SerdeOrdered<T>
+where
+    T: Deserialize<'a>,
+{
+    type DItem = T;
+    type Error = SerdeOrderedError;
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, Self::Error> {
+        de::from_slice(bytes)
+    }
+}
+
+unsafe impl<T> Send for SerdeOrdered<T> {}
+
+unsafe impl<T> Sync for SerdeOrdered<T> {}
+
+/// The error returned by the [`SerdeOrdered`] codec.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SerdeOrderedError {
+    /// A custom error message from the [`Serialize`] or [`Deserialize`] implementations.
+    Message(String),
+    /// The bytes ended before the value was entirely decoded.
+    UnexpectedEnd,
+    /// There are bytes left after the value.
+    TrailingBytes,
+    /// The bytes are not a valid encoding of the given kind of value.
+    InvalidEncoding(&'static str),
+    /// The format does not support this kind of value.
+    Unsupported(&'static str),
+}
+
+impl fmt::Display for SerdeOrderedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SerdeOrderedError::Message(message) => f.write_str(message),
+            SerdeOrderedError::UnexpectedEnd => f.write_str("unexpected end of the bytes"),
+            SerdeOrderedError::TrailingBytes => f.write_str("trailing bytes after the value"),
+            SerdeOrderedError::InvalidEncoding(kind) => write!(f, "invalid {} encoding", kind),
+            SerdeOrderedError::Unsupported(kind) => {
+                write!(f, "{} is not supported by the ordered format", kind)
+            }
+        }
+    }
+}
+
+impl error::Error for SerdeOrderedError {}
+
+impl serde::ser::Error for SerdeOrderedError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        SerdeOrderedError::Message(msg.to_string())
+    }
+}
+
+impl serde::de::Error for SerdeOrderedError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        SerdeOrderedError::Message(msg.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+    enum Kind {
+        Login,
+        Purchase { amount: f64 },
+        Tagged(Option<String>, Vec<u16>),
+    }
+
+    #[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+    struct Event {
+        tenant: i32,
+        kind: Kind,
+        name: String,
+    }
+
+    fn encode<T: Serialize>(item: &T) -> Vec<u8> {
+        SerdeOrdered::<T>::bytes_encode(item).unwrap().into_owned()
+    }
+
+    #[test]
+    fn byte_order_follows_value_order() {
+        let event = |tenant, kind, name: &str| Event { tenant, kind, name: name.to_owned() };
+        let mut events = vec![
+            event(1, Kind::Purchase { amount: 12.5 }, "b"),
+            event(-4, Kind::Login, "z"),
+            event(1, Kind::Purchase { amount: -3.0 }, "a"),
+            event(1, Kind::Tagged(None, vec![3]), "a"),
+            event(1, Kind::Tagged(Some("x\0".into()), vec![]), "a"),
+            event(1, Kind::Tagged(Some("x".into()), vec![1, 2]), "a"),
+            event(1, Kind::Tagged(Some("x".into()), vec![1]), "a\0"),
+            event(1, Kind::Tagged(Some("x".into()), vec![1]), "a"),
+            event(0, Kind::Login, ""),
+        ];
+        events.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let encoded: Vec<_> = events.iter().map(encode).collect();
+        assert!(encoded.windows(2).all(|w| w[0] < w[1]));
+
+        for (bytes, event) in encoded.iter().zip(&events) {
+            assert_eq!(&SerdeOrdered::<Event>::bytes_decode(bytes).unwrap(), event);
+        }
+    }
+
+    #[test]
+    fn maps_and_primitives_roundtrip() {
+        let map: BTreeMap<String, (bool, char, Option<u128>)> = [
+            ("b".to_owned(), (true, 'é', None)),
+            ("a\0".to_owned(), (false, '\0', Some(u128::MAX))),
+        ]
+        .into_iter()
+        .collect();
+        let bytes = encode(&map);
+        assert_eq!(SerdeOrdered::<BTreeMap<_, _>>::bytes_decode(&bytes).unwrap(), map);
+
+        let floats = [f64::NEG_INFINITY, -1.5, -0.0, 0.0, 1e-310, f64::INFINITY];
+        let encoded: Vec<_> = floats.iter().map(encode).collect();
+        assert!(encoded.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn borrows_unescaped_strings() {
+        let bytes = encode(&(7u32, "name", "a\0b"));
+        let (id, name, escaped) =
+            SerdeOrdered::<(u32, &str, Cow<str>)>::bytes_decode(&bytes).unwrap();
+        assert_eq!((id, name, escaped.as_ref()), (7, "name", "a\0b"));
+        assert!(matches!(escaped, Cow::Owned(_)));
+
+        let error =
+            SerdeOrdered::<(u32, &str)>::bytes_decode(&encode(&(7u32, "a\0b"))).unwrap_err();
+        assert!(matches!(error, SerdeOrderedError::Message(_)));
+    }
+
+    #[test]
+    fn invalid_bytes() {
+        let bytes = encode(&(1u16, "a"));
+        let error = SerdeOrdered::<(u16, String)>::bytes_decode(&bytes[..3]).unwrap_err();
+        assert_eq!(error, SerdeOrderedError::UnexpectedEnd);
+
+        let error = SerdeOrdered::<u16>::bytes_decode(&bytes).unwrap_err();
+        assert_eq!(error, SerdeOrderedError::TrailingBytes);
+
+        let error = SerdeOrdered::<bool>::bytes_decode(&[2]).unwrap_err();
+        assert_eq!(error, SerdeOrderedError::InvalidEncoding("bool"));
+
+        let error = SerdeOrdered::<Option<u8>>::bytes_decode(&[3, 1]).unwrap_err();
+        assert_eq!(error, SerdeOrderedError::InvalidEncoding("option"));
+
+        let error = SerdeOrdered::<Vec<u8>>::bytes_decode(&[0x02]).unwrap_err();
+        assert_eq!(error, SerdeOrderedError::InvalidEncoding("sequence"));
+
+        let error = SerdeOrdered::<String>::bytes_decode(&[b'a', 0, 2]).unwrap_err();
+        assert_eq!(error, SerdeOrderedError::InvalidEncoding("escaped bytes"));
+    }
+
+    #[test]
+    fn unsupported_values() {
+        #[derive(Serialize)]
+        struct Sparse {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            value: Option<u8>,
+        }
+
+        let error = SerdeOrdered::<Sparse>::bytes_encode(&Sparse { value: None }).unwrap_err();
+        assert_eq!(error, SerdeOrderedError::Unsupported("skipping a struct field"));
+
+        #[derive(Debug, Deserialize)]
+        #[serde(untagged)]
+        enum Untagged {
+            Number(#[allow(dead_code)] u8),
+        }
+
+        let error = SerdeOrdered::<Untagged>::bytes_decode(&[1]).unwrap_err();
+        assert!(matches!(error, SerdeOrderedError::Unsupported(_)));
+    }
+}
@@ -0,0 +1,86 @@
+use std::borrow::Cow;
+use std::io;
+
+use heed_traits::{BytesDecode, BytesEncode, EncodeIntoWriterError};
+use serde::{Deserialize, Serialize};
+
+/// Describes a type that is [`Serialize`]/[`Deserialize`] and uses `postcard` to do so.
+///
+/// It can borrow bytes from the original slice.
+pub struct SerdePostcard<T>(std::marker::PhantomData<T>);
+
+impl<'a, T: 'a> BytesEncode<'a> for SerdePostcard<T>
+where
+    T: Serialize,
+{
+    type EItem = T;
+    type Error = postcard::Error;
+
+    fn bytes_encode(item: &Self::EItem) -> Result<Cow<'_, [u8]>, Self::Error> {
+        postcard::to_stdvec(item).map(Cow::Owned)
+    }
+
+    fn encodes_into_writer(_item: &Self::EItem) -> bool {
+        true
+    }
+
+    fn bytes_encode_into_writer<W: io::Write>(
+        item: &'a Self::EItem,
+        writer: W,
+    ) -> Result<(), EncodeIntoWriterError<Self::Error>> {
+        postcard::to_io(item, writer).map(drop).map_err(EncodeIntoWriterError::Encoding)
+    }
+}
+
+impl<'a, T: 'a> BytesDecode<'a> for SerdePostcard<T>
+where
+    T: Deserialize<'a>,
+{
+    type DItem = T;
+    type Error = postcard::Error;
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, Self::Error> {
+        postcard::from_bytes(bytes)
+    }
+}
+
+unsafe impl<T> Send for SerdePostcard<T> {}
+
+unsafe impl<T> Sync for SerdePostcard<T> {}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct User<'a> {
+        id: u64,
+        name: &'a str,
+        #[serde(borrow)]
+        avatar: Cow<'a, [u8]>,
+    }
+
+    #[test]
+    fn borrows_from_the_bytes() {
+        let user = User { id: 42, name: "kero", avatar: Cow::Borrowed(&[1, 2, 3]) };
+        let bytes = SerdePostcard::<User>::bytes_encode(&user).unwrap();
+        let mut written = Vec::new();
+        SerdePostcard::<User>::bytes_encode_into_writer(&user, &mut written).unwrap();
+        assert_eq!(bytes, written);
+
+        let decoded = SerdePostcard::<User>::bytes_decode(&bytes).unwrap();
+        assert_eq!(decoded, user);
+        assert!(bytes.as_ptr_range().contains(&decoded.name.as_ptr()));
+        assert!(matches!(decoded.avatar, Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn invalid_bytes() {
+        let user = User { id: 42, name: "kero", avatar: Cow::Borrowed(&[1, 2, 3]) };
+        let bytes = SerdePostcard::<User>::bytes_encode(&user).unwrap();
+        let error = SerdePostcard::<User>::bytes_decode(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert_eq!(error, postcard::Error::DeserializeUnexpectedEnd);
+    }
+}
@@ -1,6 +1,7 @@
 use std::borrow::Cow;
+use std::io;
 
-use heed_traits::{BoxedError, BytesDecode, BytesEncode};
+use heed_traits::{BytesDecode, BytesEncode, EncodeIntoWriterError};
 use serde::{Deserialize, Serialize};
 
 /// Describes a type that is [`Serialize`]/[`Deserialize`] and uses `rmp_serde` to do so.
@@ -13,9 +14,21 @@ where
     T: Serialize,
 {
     type EItem = T;
+    type Error = rmp_serde::encode::Error;
 
-    fn bytes_encode(item: &Self::EItem) -> Result<Cow<[u8]>, BoxedError> {
-        rmp_serde::to_vec(item).map(Cow::Owned).map_err(Into::into)
+    fn bytes_encode(item: &Self::EItem) -> Result<Cow<'_, [u8]>, Self::Error> {
+        rmp_serde::to_vec(item).map(Cow::Owned)
+    }
+
+    fn encodes_into_writer(_item: &Self::EItem) -> bool {
+        true
+    }
+
+    fn bytes_encode_into_writer<W: io::Write>(
+        item: &'a Self::EItem,
+        mut writer: W,
+    ) -> Result<(), EncodeIntoWriterError<Self::Error>> {
+        rmp_serde::encode::write(&mut writer, item).map_err(EncodeIntoWriterError::Encoding)
     }
 }
 
@@ -24,9 +37,10 @@ where
     T: Deserialize<'a>,
 {
     type DItem = T;
+    type Error = rmp_serde::decode::Error;
 
-    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, BoxedError> {
-        rmp_serde::from_slice(bytes).map_err(Into::into)
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, Self::Error> {
+        rmp_serde::from_slice(bytes)
     }
 }
 
@@ -1,23 +1,26 @@
 use std::borrow::Cow;
-use std::str;
+use std::convert::Infallible;
+use std::str::{self, Utf8Error};
 
-use heed_traits::{BoxedError, BytesDecode, BytesEncode};
+use heed_traits::{BytesDecode, BytesEncode};
 
 /// Describes a [`prim@str`].
 pub enum Str {}
 
 impl BytesEncode<'_> for Str {
     type EItem = str;
+    type Error = Infallible;
 
-    fn bytes_encode(item: &Self::EItem) -> Result<Cow<[u8]>, BoxedError> {
+    fn bytes_encode(item: &Self::EItem) -> Result<Cow<'_, [u8]>, Self::Error> {
         Ok(Cow::Borrowed(item.as_bytes()))
     }
 }
 
 impl<'a> BytesDecode<'a> for Str {
     type DItem = &'a str;
+    type Error = Utf8Error;
 
-    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, BoxedError> {
-        str::from_utf8(bytes).map_err(Into::into)
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, Self::Error> {
+        str::from_utf8(bytes)
     }
 }
@@ -0,0 +1,323 @@
+use std::borrow::Cow;
+use std::marker::PhantomData;
+use std::mem::size_of;
+use std::{error, fmt, str};
+
+use byteorder::BigEndian;
+use heed_traits::{BytesDecode, BytesEncode};
+
+use crate::*;
+
+/// Describes a composite key made of a tuple of up to eight [`KeyPart`] codecs.
+///
+/// The parts are encoded one after the other in an order-preserving way: the keys are
+/// sorted by their first part, then by their second part, and so on. The fixed-size parts
+/// are written as is and the variable-size parts, like [`Str`] and [`Bytes`], are escaped
+/// and terminated. The encoding of a tuple is therefore a prefix of the encoding of any longer
+/// tuple starting with the same parts, a shorter tuple codec can be used to encode
+/// a prefix or range bound by remapping the key type of the database.
+///
+/// The items to encode are tuples of references to the items of the parts,
+/// the decoded items are tuples of the decoded parts.
+///
+/// ```
+/// use heed_traits::{BytesDecode, BytesEncode};
+/// use heed_types::*;
+///
+/// type Key = Tuple<(U32<byteorder::BigEndian>, OrderedI64, Str)>;
+/// type Prefix = Tuple<(U32<byteorder::BigEndian>, OrderedI64)>;
+///
+/// let key = Key::bytes_encode(&(&1, &-5, "logs")).unwrap();
+/// let prefix = Prefix::bytes_encode(&(&1, &-5)).unwrap();
+/// assert!(key.starts_with(&prefix));
+///
+/// let (tenant, timestamp, name) = Key::bytes_decode(&key).unwrap();
+/// assert_eq!((tenant, timestamp, name.as_ref()), (1, -5, "logs"));
+/// ```
+pub struct Tuple<T>(PhantomData<T>);
+
+/// A codec that can be used as a part of a [`Tuple`] key.
+///
+/// The encoding of a part must be self-delimiting and must preserve the order of the items.
+pub trait KeyPart<'a> {
+    /// The type to encode.
+    type EItem: ?Sized + 'a;
+
+    /// The type to decode.
+    type DItem: 'a;
+
+    /// Appends the encoding of the given item to the output.
+    fn encode_part(item: &'a Self::EItem, output: &mut Vec<u8>) -> Result<(), TupleError>;
+
+    /// Decodes a part at the start of the given bytes and returns it along with the remaining bytes.
+    fn decode_part(bytes: &'a [u8]) -> Result<(Self::DItem, &'a [u8]), TupleError>;
+}
+
+macro_rules! impl_fixed_key_part {
+    ($($codec:ty),+) => {
+        $(
+            impl<'a> KeyPart<'a> for $codec {
+                type EItem = <$codec as BytesEncode<'a>>::EItem;
+                type DItem = <$codec as BytesDecode<'a>>::DItem;
+
+                fn encode_part(item: &'a Self::EItem, output: &mut Vec<u8>) -> Result<(), TupleError> {
+                    match <$codec as BytesEncode>::bytes_encode(item) {
+                        Ok(bytes) => output.extend_from_slice(&bytes),
+                        Err(never) => match never {},
+                    }
+                    Ok(())
+                }
+
+                fn decode_part(bytes: &'a [u8]) -> Result<(Self::DItem, &'a [u8]), TupleError> {
+                    let size = size_of::<Self::DItem>();
+                    if bytes.len() < size {
+                        return Err(TupleError::Truncated);
+                    }
+                    let (part, rest) = bytes.split_at(size);
+                    // The codecs only fail when the bytes do not have the size of the item.
+                    let part = <$codec as BytesDecode>::bytes_decode(part)
+                        .map_err(|_| TupleError::Truncated)?;
+                    Ok((part, rest))
+                }
+            }
+        )+
+    };
+}
+
+impl_fixed_key_part!(
+    U8,
+    U16<BigEndian>,
+    U32<BigEndian>,
+    U64<BigEndian>,
+    U128<BigEndian>,
+    OrderedI8,
+    OrderedI16,
+    OrderedI32,
+    OrderedI64,
+    OrderedI128,
+    OrderedF32,
+    OrderedF64
+);
+
+impl<'a> KeyPart<'a> for Str {
+    type EItem = str;
+    type DItem = Cow<'a, str>;
+
+    fn encode_part(item: &'a Self::EItem, output: &mut Vec<u8>) -> Result<(), TupleError> {
+        encode_escaped(item.as_bytes(), output);
+        Ok(())
+    }
+
+    fn decode_part(bytes: &'a [u8]) -> Result<(Self::DItem, &'a [u8]), TupleError> {
+        let (part, rest) = decode_escaped(bytes)?;
+        let part = match part {
+            Cow::Borrowed(bytes) => {
+                Cow::Borrowed(str::from_utf8(bytes).map_err(|_| TupleError::InvalidUtf8)?)
+            }
+            Cow::Owned(bytes) => {
+                Cow::Owned(String::from_utf8(bytes).map_err(|_| TupleError::InvalidUtf8)?)
+            }
+        };
+        Ok((part, rest))
+    }
+}
+
+impl<'a> KeyPart<'a> for Bytes {
+    type EItem = [u8];
+    type DItem = Cow<'a, [u8]>;
+
+    fn encode_part(item: &'a Self::EItem, output: &mut Vec<u8>) -> Result<(), TupleError> {
+        encode_escaped(item, output);
+        Ok(())
+    }
+
+    fn decode_part(bytes: &'a [u8]) -> Result<(Self::DItem, &'a [u8]), TupleError> {
+        decode_escaped(bytes)
+    }
+}
+
+/// Appends the bytes with the `0x00` bytes escaped as `0x00 0xFF` followed by a `0x00 0x01` terminator.
+///
+/// The terminator sorts before any other byte and escape sequence, a part is
+/// therefore sorted before all the parts it is a prefix of.
+pub(crate) fn encode_escaped(bytes: &[u8], output: &mut Vec<u8>) {
+    for &byte in bytes {
+        output.push(byte);
+        if byte == 0x00 {
+            output.push(0xFF);
+        }
+    }
+    output.extend_from_slice(&[0x00, 0x01]);
+}
+
+/// Decodes an escaped and terminated part, it is only copied if it contains escaped bytes.
+pub(crate) fn decode_escaped(bytes: &[u8]) -> Result<(Cow<'_, [u8]>, &[u8]), TupleError> {
+    let mut unescaped: Option<Vec<u8>> = None;
+    let mut start = 0;
+    loop {
+        let zero = match bytes[start..].iter().position(|&byte| byte == 0x00) {
+            Some(position) => start + position,
+            None => return Err(TupleError::Truncated),
+        };
+        match bytes.get(zero + 1) {
+            Some(0x01) => {
+                let part = match unescaped {
+                    Some(mut part) => {
+                        part.extend_from_slice(&bytes[start..zero]);
+                        Cow::Owned(part)
+                    }
+                    None => Cow::Borrowed(&bytes[..zero]),
+                };
+                return Ok((part, &bytes[zero + 2..]));
+            }
+            Some(0xFF) => {
+                unescaped.get_or_insert_with(Vec::new).extend_from_slice(&bytes[start..=zero]);
+                start = zero + 2;
+            }
+            Some(_) => return Err(TupleError::InvalidEscape),
+            None => return Err(TupleError::Truncated),
+        }
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($part:ident $item:ident),+) => {
+        impl<'a, $($part: KeyPart<'a>),+> BytesEncode<'a> for Tuple<($($part,)+)> {
+            type EItem = ($(&'a $part::EItem,)+);
+            type Error = TupleError;
+
+            fn bytes_encode(item: &'a Self::EItem) -> Result<Cow<'a, [u8]>, TupleError> {
+                let ($($item,)+) = item;
+                let mut output = Vec::new();
+                $($part::encode_part($item, &mut output)?;)+
+                Ok(Cow::Owned(output))
+            }
+        }
+
+        impl<'a, $($part: KeyPart<'a>),+> BytesDecode<'a> for Tuple<($($part,)+)> {
+            type DItem = ($($part::DItem,)+);
+            type Error = TupleError;
+
+            fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, TupleError> {
+                let rest = bytes;
+                $(let ($item, rest) = $part::decode_part(rest)?;)+
+                if rest.is_empty() {
+                    Ok(($($item,)+))
+                } else {
+                    Err(TupleError::TrailingBytes)
+                }
+            }
+        }
+    };
+}
+
+impl_tuple!(A a);
+impl_tuple!(A a, B b);
+impl_tuple!(A a, B b, C c);
+impl_tuple!(A a, B b, C c, D d);
+impl_tuple!(A a, B b, C c, D d, E e);
+impl_tuple!(A a, B b, C c, D d, E e, F f);
+impl_tuple!(A a, B b, C c, D d, E e, F f, G g);
+impl_tuple!(A a, B b, C c, D d, E e, F f, G g, H h);
+
+/// The error returned when the bytes are not a valid [`Tuple`] encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TupleError {
+    /// The bytes ended in the middle of a part.
+    Truncated,
+    /// A variable-size part contains an invalid escape sequence.
+    InvalidEscape,
+    /// There are bytes left after the last part.
+    TrailingBytes,
+    /// A string part is not valid UTF-8.
+    InvalidUtf8,
+}
+
+impl fmt::Display for TupleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TupleError::Truncated => f.write_str("the tuple key is truncated"),
+            TupleError::InvalidEscape => {
+                f.write_str("the tuple key contains an invalid escape sequence")
+            }
+            TupleError::TrailingBytes => f.write_str("the tuple key has trailing bytes"),
+            TupleError::InvalidUtf8 => {
+                f.write_str("the tuple key contains an invalid UTF-8 string")
+            }
+        }
+    }
+}
+
+impl error::Error for TupleError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Key = Tuple<(U32<BigEndian>, OrderedI64, Str)>;
+
+    #[test]
+    fn byte_order_follows_tuple_order() {
+        let mut keys: Vec<(u32, i64, &str)> = vec![
+            (2, 0, "a"),
+            (1, 5, "b"),
+            (1, -5, "a\0b"),
+            (1, -5, "a"),
+            (1, -5, ""),
+            (1, -5, "a\0"),
+            (1, -5, "a\0\0"),
+            (0, 9, "z"),
+        ];
+        keys.sort();
+
+        let encoded: Vec<_> = keys
+            .iter()
+            .map(|(tenant, time, name)| {
+                Key::bytes_encode(&(tenant, time, name)).unwrap().into_owned()
+            })
+            .collect();
+        assert!(encoded.windows(2).all(|w| w[0] < w[1]));
+
+        for (bytes, key) in encoded.iter().zip(&keys) {
+            let (tenant, time, name) = Key::bytes_decode(bytes).unwrap();
+            assert_eq!((tenant, time, name.as_ref()), *key);
+        }
+    }
+
+    #[test]
+    fn parts_are_escaped_and_terminated() {
+        let bytes = Tuple::<(Bytes, U8)>::bytes_encode(&(&[1, 0, 2][..], &7)).unwrap().into_owned();
+        assert_eq!(bytes, &[1, 0, 0xFF, 2, 0, 1, 7][..]);
+
+        let (part, rest) = decode_escaped(&bytes).unwrap();
+        assert_eq!((part.as_ref(), rest), (&[1, 0, 2][..], &[7][..]));
+        assert!(matches!(part, Cow::Owned(_)));
+
+        let (part, _) = decode_escaped(&[1, 2, 0, 1]).unwrap();
+        assert!(matches!(part, Cow::Borrowed([1, 2])));
+    }
+
+    #[test]
+    fn prefix_tuple_is_a_byte_prefix() {
+        let key = Key::bytes_encode(&(&1, &-5, "a\0b")).unwrap();
+        let prefix = Tuple::<(U32<BigEndian>, OrderedI64)>::bytes_encode(&(&1, &-5)).unwrap();
+        assert!(key.starts_with(&prefix));
+
+        let shorter = Key::bytes_encode(&(&1, &-5, "a")).unwrap();
+        assert!(!key.starts_with(&shorter));
+    }
+
+    #[test]
+    fn invalid_bytes() {
+        let error = |bytes| Tuple::<(U8, Str)>::bytes_decode(bytes).unwrap_err();
+
+        assert_eq!(error(&[]), TupleError::Truncated);
+        assert_eq!(error(&[7, b'a']), TupleError::Truncated);
+        assert_eq!(error(&[7, b'a', 0]), TupleError::Truncated);
+        assert_eq!(error(&[7, b'a', 0, 2]), TupleError::InvalidEscape);
+        assert_eq!(error(&[7, b'a', 0, 1, 9]), TupleError::TrailingBytes);
+
+        assert_eq!(error(&[7, 0xC0, 0, 1]), TupleError::InvalidUtf8);
+        assert_eq!(error(&[7, 0xC0, 0, 0xFF, 0, 1]), TupleError::InvalidUtf8);
+    }
+}
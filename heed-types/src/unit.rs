@@ -1,27 +1,30 @@
 use std::borrow::Cow;
+use std::convert::Infallible;
 use std::{error, fmt};
 
-use heed_traits::{BoxedError, BytesDecode, BytesEncode};
+use heed_traits::{BytesDecode, BytesEncode};
 
 /// Describes the unit `()` type.
 pub enum Unit {}
 
 impl BytesEncode<'_> for Unit {
     type EItem = ();
+    type Error = Infallible;
 
-    fn bytes_encode(_item: &Self::EItem) -> Result<Cow<[u8]>, BoxedError> {
+    fn bytes_encode(_item: &Self::EItem) -> Result<Cow<'_, [u8]>, Self::Error> {
         Ok(Cow::Borrowed(&[]))
     }
 }
 
 impl BytesDecode<'_> for Unit {
     type DItem = ();
+    type Error = NonEmptyError;
 
-    fn bytes_decode(bytes: &[u8]) -> Result<Self::DItem, BoxedError> {
+    fn bytes_decode(bytes: &[u8]) -> Result<Self::DItem, Self::Error> {
         if bytes.is_empty() {
             Ok(())
         } else {
-            Err(NonEmptyError.into())
+            Err(NonEmptyError)
         }
     }
 }
@@ -0,0 +1,239 @@
+use std::borrow::Cow;
+use std::marker::PhantomData;
+use std::{error, fmt, io};
+
+use heed_traits::{BoxedError, BytesDecode, BytesDecodeOwned, BytesEncode, EncodeIntoWriterError};
+
+/// Describes a codec that prefixes the values with the version of their type, to keep
+/// reading the values written with the previous versions of a type after it evolved.
+///
+/// The values are always written with the latest version `T`. When the version of a
+/// value is not the latest one, it is decoded with the codec of its version and upgraded
+/// to `T` with the `From` conversions between the successive versions, there is no
+/// need to migrate the whole database when a type changes.
+///
+/// ```
+/// use heed_traits::{BytesDecode, BytesEncode};
+/// use heed_types::{NoPrevious, SerdeJson, Version, Versioned};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct UserV1 {
+///     name: String,
+/// }
+///
+/// impl Version for UserV1 {
+///     const VERSION: u8 = 1;
+///     type Codec = SerdeJson<Self>;
+///     type Previous = NoPrevious;
+/// }
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct User {
+///     name: String,
+///     admin: bool,
+/// }
+///
+/// impl Version for User {
+///     const VERSION: u8 = 2;
+///     type Codec = SerdeJson<Self>;
+///     type Previous = UserV1;
+/// }
+///
+/// impl From<UserV1> for User {
+///     fn from(UserV1 { name }: UserV1) -> User {
+///         User { name, admin: false }
+///     }
+/// }
+///
+/// let old = UserV1 { name: "kero".into() };
+/// let bytes = Versioned::<UserV1>::bytes_encode(&old).unwrap();
+/// let user = Versioned::<User>::bytes_decode(&bytes).unwrap();
+/// assert_eq!(user, User { name: "kero".into(), admin: false });
+/// ```
+pub struct Versioned<T>(PhantomData<T>);
+
+impl<'a, T> BytesEncode<'a> for Versioned<T>
+where
+    T: Version + 'a,
+{
+    type EItem = T;
+    type Error = BoxedError;
+
+    fn bytes_encode(item: &'a Self::EItem) -> Result<Cow<'a, [u8]>, Self::Error> {
+        let bytes = T::Codec::bytes_encode(item).map_err(Into::into)?;
+        let mut output = Vec::with_capacity(1 + bytes.len());
+        output.push(T::VERSION);
+        output.extend_from_slice(&bytes);
+        Ok(Cow::Owned(output))
+    }
+
+    fn encoded_size(item: &Self::EItem) -> Option<usize> {
+        T::Codec::encoded_size(item).map(|size| size + 1)
+    }
+
+    fn encodes_into_writer(_item: &Self::EItem) -> bool {
+        true
+    }
+
+    fn bytes_encode_into_writer<W: io::Write>(
+        item: &'a Self::EItem,
+        mut writer: W,
+    ) -> Result<(), EncodeIntoWriterError<Self::Error>> {
+        writer.write_all(&[T::VERSION])?;
+        T::Codec::bytes_encode_into_writer(item, writer).map_err(|e| e.map_encoding(Into::into))
+    }
+}
+
+impl<'a, T> BytesDecode<'a> for Versioned<T>
+where
+    T: Version + 'a,
+{
+    type DItem = T;
+    type Error = BoxedError;
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, Self::Error> {
+        match bytes.split_first() {
+            Some((&version, bytes)) => <T as PreviousVersion<T>>::decode_version(version, bytes),
+            None => Err(VersionedError::MissingVersion.into()),
+        }
+    }
+}
+
+unsafe impl<T> Send for Versioned<T> {}
+
+unsafe impl<T> Sync for Versioned<T> {}
+
+/// A version of a type stored with the [`Versioned`] codec.
+pub trait Version: Sized {
+    /// The version written in front of the values, it must be unique among the versions.
+    const VERSION: u8;
+
+    /// The codec used to encode and decode this version.
+    type Codec: for<'a> BytesEncode<'a, EItem = Self> + BytesDecodeOwned<DItem = Self>;
+
+    /// The previous version of the type, that can be converted into this version,
+    /// or [`NoPrevious`] for the first version.
+    type Previous: PreviousVersion<Self>;
+}
+
+/// A previous version that can be upgraded to `T`.
+///
+/// It is implemented for every [`Version`] that can be converted into `T`
+/// and for [`NoPrevious`], it should not be implemented manually.
+pub trait PreviousVersion<T> {
+    /// Decodes the bytes written with the given version and upgrades them to `T`.
+    fn decode_version(version: u8, bytes: &[u8]) -> Result<T, BoxedError>;
+}
+
+impl<T, P> PreviousVersion<T> for P
+where
+    P: Version + Into<T>,
+{
+    fn decode_version(version: u8, bytes: &[u8]) -> Result<T, BoxedError> {
+        if version == P::VERSION {
+            P::Codec::bytes_decode_owned(bytes).map(Into::into).map_err(Into::into)
+        } else {
+            P::Previous::decode_version(version, bytes).map(Into::into)
+        }
+    }
+}
+
+/// Marks the first version of a type, that does not have a previous version.
+pub enum NoPrevious {}
+
+impl<T> PreviousVersion<T> for NoPrevious {
+    fn decode_version(version: u8, _bytes: &[u8]) -> Result<T, BoxedError> {
+        Err(VersionedError::UnknownVersion(version).into())
+    }
+}
+
+/// An error that happens when versioned bytes cannot be decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionedError {
+    /// The bytes are empty and do not contain the version.
+    MissingVersion,
+    /// The version is not one of the versions of the type.
+    UnknownVersion(u8),
+}
+
+impl fmt::Display for VersionedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VersionedError::MissingVersion => f.write_str("missing version"),
+            VersionedError::UnknownVersion(version) => write!(f, "unknown version {}", version),
+        }
+    }
+}
+
+impl error::Error for VersionedError {}
+
+#[cfg(test)]
+mod tests {
+    use byteorder::BigEndian;
+
+    use super::*;
+    use crate::{U16, U32, U8};
+
+    // The successive versions of a number, upgraded by widening it.
+
+    impl Version for u8 {
+        const VERSION: u8 = 1;
+        type Codec = U8;
+        type Previous = NoPrevious;
+    }
+
+    impl Version for u16 {
+        const VERSION: u8 = 2;
+        type Codec = U16<BigEndian>;
+        type Previous = u8;
+    }
+
+    impl Version for u32 {
+        const VERSION: u8 = 3;
+        type Codec = U32<BigEndian>;
+        type Previous = u16;
+    }
+
+    fn error(bytes: &[u8]) -> VersionedError {
+        let error = Versioned::<u32>::bytes_decode(bytes).unwrap_err();
+        *error.downcast_ref::<VersionedError>().unwrap()
+    }
+
+    #[test]
+    fn values_are_prefixed_by_their_version() {
+        let bytes = Versioned::<u32>::bytes_encode(&7).unwrap();
+        assert_eq!(bytes, &[3, 0, 0, 0, 7][..]);
+        assert_eq!(Versioned::<u32>::encoded_size(&7), Some(5));
+
+        let mut written = Vec::new();
+        Versioned::<u32>::bytes_encode_into_writer(&7, &mut written).unwrap();
+        assert_eq!(bytes, written);
+    }
+
+    #[test]
+    fn previous_versions_are_upgraded() {
+        let v1 = Versioned::<u8>::bytes_encode(&1).unwrap();
+        let v2 = Versioned::<u16>::bytes_encode(&300).unwrap();
+        let v3 = Versioned::<u32>::bytes_encode(&70_000).unwrap();
+
+        assert_eq!(Versioned::<u32>::bytes_decode(&v1).unwrap(), 1);
+        assert_eq!(Versioned::<u32>::bytes_decode(&v2).unwrap(), 300);
+        assert_eq!(Versioned::<u32>::bytes_decode(&v3).unwrap(), 70_000);
+
+        // The old versions can still be read with their own type.
+        assert_eq!(Versioned::<u16>::bytes_decode(&v1).unwrap(), 1);
+        assert!(Versioned::<u16>::bytes_decode(&v3).is_err());
+    }
+
+    #[test]
+    fn invalid_bytes() {
+        assert_eq!(error(&[]), VersionedError::MissingVersion);
+        assert_eq!(error(&[4, 0]), VersionedError::UnknownVersion(4));
+        assert_eq!(error(&[0]), VersionedError::UnknownVersion(0));
+
+        // The bytes of a known version are decoded with its codec.
+        let error = Versioned::<u32>::bytes_decode(&[2, 0]).unwrap_err();
+        assert!(error.downcast_ref::<VersionedError>().is_none());
+    }
+}
@@ -0,0 +1,178 @@
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use heed_traits::Comparator;
+use icu_collator::{Collator, CollatorOptions};
+use icu_locid::Locale;
+
+#[cfg(doc)]
+use crate::{Database, DatabaseOpenOptions, LexicographicComparator};
+
+/// A comparator that orders the UTF-8 string keys with the collation rules of the locale `L`,
+/// e.g. with the French locale `"élan"` is ordered between `"elan"` and `"eluder"`.
+///
+/// It is set with [`DatabaseOpenOptions::key_comparator`] and orders the keys of the
+/// [`Database::iter`] and [`Database::range`] iterators, the bounds of the ranges are also
+/// compared with the collation rules. The keys are usually encoded with the `Str` codec, the
+/// ill-formed UTF-8 sequences are compared as if they were replacement characters.
+///
+/// The strings that are equal for the collation, like the canonically equivalent strings,
+/// are ordered by their bytes to keep them distinct in the database.
+///
+/// It is not a [`LexicographicComparator`] and cannot be used with the prefix iterators:
+/// the keys starting with a prefix are not contiguous in the collation order.
+///
+/// ```
+/// # use std::error::Error;
+/// # use std::ops::Bound;
+/// # use heed::EnvOpenOptions;
+/// use heed::icu_locid::{locale, Locale};
+/// use heed::types::*;
+/// use heed::{CollationLocale, CollatorComparator};
+///
+/// enum French {}
+///
+/// impl CollationLocale for French {
+///     fn locale() -> Locale {
+///         locale!("fr")
+///     }
+/// }
+///
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// # let dir = tempfile::tempdir()?;
+/// # let env = unsafe { EnvOpenOptions::new().open(dir.path())? };
+/// let mut wtxn = env.write_txn()?;
+/// let db = env
+///     .database_options()
+///     .types::<Str, Unit>()
+///     .key_comparator::<CollatorComparator<French>>()
+///     .create(&mut wtxn)?;
+///
+/// for name in ["Zoé", "eluder", "élan", "elan", "Émile"] {
+///     db.put(&mut wtxn, name, &())?;
+/// }
+///
+/// let names: Vec<_> = db.iter(&wtxn)?.map(|r| r.map(|(k, _)| k)).collect::<Result<_, _>>()?;
+/// assert_eq!(names, ["elan", "élan", "eluder", "Émile", "Zoé"]);
+///
+/// let range = (Bound::Included("e"), Bound::Excluded("f"));
+/// let names: Vec<_> =
+///     db.range(&wtxn, &range)?.map(|r| r.map(|(k, _)| k)).collect::<Result<_, _>>()?;
+/// assert_eq!(names, ["elan", "élan", "eluder", "Émile"]);
+/// # Ok(()) }
+/// ```
+pub struct CollatorComparator<L>(PhantomData<L>);
+
+impl<L: CollationLocale + 'static> Comparator for CollatorComparator<L> {
+    fn compare(a: &[u8], b: &[u8]) -> Ordering {
+        with_collator::<L, _>(|collator| collator.compare_utf8(a, b)).then_with(|| a.cmp(b))
+    }
+}
+
+/// The locale and options of the collation rules used by a [`CollatorComparator`].
+///
+/// The locale is described at the type level so that the comparator can be given to LMDB,
+/// the collation rules must never change for a database that already contains keys.
+pub trait CollationLocale {
+    /// The locale of the collation rules, [`Locale::UND`] uses the root collation rules.
+    fn locale() -> Locale;
+
+    /// The options of the collator, like the strength of the comparisons.
+    fn options() -> CollatorOptions {
+        CollatorOptions::new()
+    }
+}
+
+/// The root collation rules, defined by the Unicode Collation Algorithm.
+pub enum RootCollation {}
+
+impl CollationLocale for RootCollation {
+    fn locale() -> Locale {
+        Locale::UND
+    }
+}
+
+thread_local! {
+    /// The collators are costly to create, they are created once per thread and locale.
+    static COLLATORS: RefCell<HashMap<TypeId, Rc<Collator>>> = RefCell::new(HashMap::new());
+}
+
+fn with_collator<L: CollationLocale + 'static, T>(f: impl FnOnce(&Collator) -> T) -> T {
+    let collator = COLLATORS.with(|collators| {
+        let mut collators = collators.borrow_mut();
+        let collator = collators.entry(TypeId::of::<L>()).or_insert_with(|| {
+            let locale = L::locale();
+            let collator = Collator::try_new(&(&locale).into(), L::options())
+                .expect("the compiled collation data supports every locale");
+            Rc::new(collator)
+        });
+        Rc::clone(collator)
+    });
+    f(&collator)
+}
+
+#[cfg(test)]
+mod tests {
+    use icu_collator::Strength;
+    use icu_locid::locale;
+
+    use super::*;
+
+    enum Swedish {}
+
+    impl CollationLocale for Swedish {
+        fn locale() -> Locale {
+            locale!("sv")
+        }
+    }
+
+    enum PrimaryStrength {}
+
+    impl CollationLocale for PrimaryStrength {
+        fn locale() -> Locale {
+            Locale::UND
+        }
+
+        fn options() -> CollatorOptions {
+            let mut options = CollatorOptions::new();
+            options.strength = Some(Strength::Primary);
+            options
+        }
+    }
+
+    fn sorted<C: Comparator>(keys: &[&'static str]) -> Vec<&'static str> {
+        let mut keys = keys.to_vec();
+        keys.sort_by(|a, b| C::compare(a.as_bytes(), b.as_bytes()));
+        keys
+    }
+
+    #[test]
+    fn locale_rules() {
+        let keys = ["zebra", "ångström", "apple", "Äpple", "öl"];
+        assert_eq!(
+            sorted::<CollatorComparator<RootCollation>>(&keys),
+            ["ångström", "apple", "Äpple", "öl", "zebra"]
+        );
+        assert_eq!(
+            sorted::<CollatorComparator<Swedish>>(&keys),
+            ["apple", "zebra", "ångström", "Äpple", "öl"]
+        );
+    }
+
+    #[test]
+    fn collation_equal_keys_stay_distinct() {
+        type Cmp = CollatorComparator<PrimaryStrength>;
+        assert_eq!(Cmp::compare("Kero".as_bytes(), "kero".as_bytes()), Ordering::Less);
+        assert_eq!(Cmp::compare("kero".as_bytes(), "kero".as_bytes()), Ordering::Equal);
+        assert_eq!(
+            sorted::<Cmp>(&["kéro", "kero", "Kero", "kera"]),
+            ["kera", "Kero", "kero", "kéro"]
+        );
+        // The canonically equivalent strings are equal for the collation, then ordered by bytes.
+        assert_eq!(Cmp::compare("e\u{301}".as_bytes(), "é".as_bytes()), Ordering::Less);
+    }
+}
@@ -0,0 +1,259 @@
+use std::cmp::Ordering;
+
+use heed_traits::{Comparator, LexicographicComparator};
+
+#[cfg(doc)]
+use crate::mdb::ffi;
+#[cfg(doc)]
+use crate::Database;
+
+/// A representation of LMDB's default comparator behavior.
+///
+/// This enum is used to indicate the absence of a custom comparator for an LMDB
+/// database instance. When a [`Database`] is created or opened with
+/// [`DefaultComparator`], it signifies that the comparator should not be explicitly
+/// set via [`ffi::mdb_set_compare`] or [`ffi::mdb_set_dupsort`]. Consequently, the database
+/// instance utilizes LMDB's built-in default comparator, which inherently performs
+/// lexicographic comparison of keys.
+///
+/// This comparator's lexicographic implementation is employed in scenarios involving
+/// prefix iterators. Specifically, methods other than [`Comparator::compare`] are utilized
+/// to determine the lexicographic successors and predecessors of byte sequences, which
+/// is essential for these iterators' operation.
+///
+/// When a custom comparator is provided, the wrapper is responsible for setting
+/// it with the [`ffi::mdb_set_compare`] function, which overrides the default comparison
+/// behavior of LMDB with the user-defined logic.
+pub enum DefaultComparator {}
+
+impl LexicographicComparator for DefaultComparator {
+    #[inline]
+    fn compare_elem(a: u8, b: u8) -> Ordering {
+        a.cmp(&b)
+    }
+
+    #[inline]
+    fn successor(elem: u8) -> Option<u8> {
+        match elem {
+            u8::MAX => None,
+            elem => Some(elem + 1),
+        }
+    }
+
+    #[inline]
+    fn predecessor(elem: u8) -> Option<u8> {
+        match elem {
+            u8::MIN => None,
+            elem => Some(elem - 1),
+        }
+    }
+
+    #[inline]
+    fn max_elem() -> u8 {
+        u8::MAX
+    }
+
+    #[inline]
+    fn min_elem() -> u8 {
+        u8::MIN
+    }
+}
+
+/// A lexicographic comparator that orders the bytes in reverse, from `255` to `0`.
+///
+/// The keys are ordered from the highest to the lowest byte by byte, but a key is still
+/// ordered before the longer keys it prefixes. As it is a [`LexicographicComparator`],
+/// it can be used with the prefix iterators, e.g. [`Database::rev_prefix_iter`] returns
+/// the keys starting with a prefix from the lowest to the highest bytes.
+pub enum ReverseLexicographicComparator {}
+
+impl LexicographicComparator for ReverseLexicographicComparator {
+    #[inline]
+    fn compare_elem(a: u8, b: u8) -> Ordering {
+        b.cmp(&a)
+    }
+
+    #[inline]
+    fn successor(elem: u8) -> Option<u8> {
+        elem.checked_sub(1)
+    }
+
+    #[inline]
+    fn predecessor(elem: u8) -> Option<u8> {
+        elem.checked_add(1)
+    }
+
+    #[inline]
+    fn max_elem() -> u8 {
+        u8::MIN
+    }
+
+    #[inline]
+    fn min_elem() -> u8 {
+        u8::MAX
+    }
+}
+
+/// A comparator that orders the keys ignoring the case of the ASCII letters.
+///
+/// The keys that only differ by the case of their ASCII letters are equal, it means that
+/// `"Kero"` and `"kero"` are the same key and putting one overwrites the entry of the other.
+/// The other bytes, including the non-ASCII UTF-8 characters, are compared as is.
+///
+/// It is not a [`LexicographicComparator`] and cannot be used with the prefix iterators.
+pub enum AsciiCaseInsensitiveComparator {}
+
+impl Comparator for AsciiCaseInsensitiveComparator {
+    fn compare(a: &[u8], b: &[u8]) -> Ordering {
+        let a = a.iter().map(u8::to_ascii_lowercase);
+        let b = b.iter().map(u8::to_ascii_lowercase);
+        a.cmp(b)
+    }
+}
+
+/// A comparator that orders the ASCII digits of the keys by their numeric value,
+/// e.g. `"file2"` is ordered before `"file10"`.
+///
+/// The keys are split into runs of digits and runs of other bytes, the runs of other
+/// bytes are compared lexicographically and the runs of digits by their value. The keys
+/// that only differ by the leading zeros of their numbers, like `"v01"` and `"v1"`, are
+/// ordered lexicographically to keep them distinct.
+///
+/// It is not a [`LexicographicComparator`] and cannot be used with the prefix iterators.
+pub enum NaturalOrderComparator {}
+
+impl Comparator for NaturalOrderComparator {
+    fn compare(a: &[u8], b: &[u8]) -> Ordering {
+        let (mut x, mut y) = (a, b);
+        loop {
+            match (x.first(), y.first()) {
+                (None, None) => return a.cmp(b),
+                (None, Some(_)) => return Ordering::Less,
+                (Some(_), None) => return Ordering::Greater,
+                (Some(c), Some(d)) if c.is_ascii_digit() && d.is_ascii_digit() => {
+                    let (number_x, rest_x) = split_digits(x);
+                    let (number_y, rest_y) = split_digits(y);
+                    let number_x = trim_leading_zeros(number_x);
+                    let number_y = trim_leading_zeros(number_y);
+                    // A number with more significant digits is greater, otherwise
+                    // the digits of numbers of the same length compare like their values.
+                    match number_x.len().cmp(&number_y.len()).then_with(|| number_x.cmp(number_y)) {
+                        Ordering::Equal => (x, y) = (rest_x, rest_y),
+                        ordering => return ordering,
+                    }
+                }
+                (Some(c), Some(d)) => match c.cmp(d) {
+                    Ordering::Equal => (x, y) = (&x[1..], &y[1..]),
+                    ordering => return ordering,
+                },
+            }
+        }
+    }
+}
+
+fn split_digits(bytes: &[u8]) -> (&[u8], &[u8]) {
+    let len = bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+    bytes.split_at(len)
+}
+
+fn trim_leading_zeros(digits: &[u8]) -> &[u8] {
+    let zeros = digits.iter().take_while(|&&b| b == b'0').count();
+    &digits[zeros..]
+}
+
+/// A comparator that orders the keys as big-endian two's complement signed integers.
+///
+/// The signed integers of `heed::types`, like `I64<BigEndian>`, are stored in big-endian
+/// but the negative numbers are ordered after the positive ones by the default comparator
+/// as their sign bit is set. This comparator orders them by their value, it can be used
+/// with the keys of any size, from `I8` to `I128`, as long as all the keys of a database
+/// have the same size.
+///
+/// It is not a [`LexicographicComparator`] and cannot be used with the prefix iterators.
+pub enum BigEndianSignedComparator {}
+
+impl Comparator for BigEndianSignedComparator {
+    fn compare(a: &[u8], b: &[u8]) -> Ordering {
+        match (a.split_first(), b.split_first()) {
+            (Some((&sign_a, a)), Some((&sign_b, b))) => {
+                // Flipping the sign bit orders the negative numbers before the positive ones.
+                (sign_a ^ 0x80).cmp(&(sign_b ^ 0x80)).then_with(|| a.cmp(b))
+            }
+            _ => a.len().cmp(&b.len()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted<C: Comparator>(keys: &[&'static str]) -> Vec<&'static str> {
+        let mut keys = keys.to_vec();
+        keys.sort_by(|a, b| C::compare(a.as_bytes(), b.as_bytes()));
+        keys
+    }
+
+    #[test]
+    fn reverse_lexicographic() {
+        let keys = ["a", "ab", "b", "", "ba", "aa"];
+        assert_eq!(
+            sorted::<ReverseLexicographicComparator>(&keys),
+            ["", "b", "ba", "a", "ab", "aa"]
+        );
+        assert_eq!(ReverseLexicographicComparator::successor(0), None);
+        assert_eq!(ReverseLexicographicComparator::predecessor(u8::MAX), None);
+        let max = ReverseLexicographicComparator::max_elem();
+        let min = ReverseLexicographicComparator::min_elem();
+        assert_eq!(ReverseLexicographicComparator::compare_elem(min, max), Ordering::Less);
+        for elem in 1..=u8::MAX {
+            let successor = ReverseLexicographicComparator::successor(elem).unwrap();
+            let ordering = ReverseLexicographicComparator::compare_elem(elem, successor);
+            assert_eq!(ordering, Ordering::Less);
+            assert_eq!(ReverseLexicographicComparator::predecessor(successor), Some(elem));
+        }
+    }
+
+    #[test]
+    fn ascii_case_insensitive() {
+        let keys = ["banana", "Apple", "cherry", "apricot", "Banana2"];
+        assert_eq!(
+            sorted::<AsciiCaseInsensitiveComparator>(&keys),
+            ["Apple", "apricot", "banana", "Banana2", "cherry"]
+        );
+        assert_eq!(AsciiCaseInsensitiveComparator::compare(b"KeRo", b"kero"), Ordering::Equal);
+        assert_eq!(
+            AsciiCaseInsensitiveComparator::compare(b"k\xc3\x89", b"k\xc3\xa9"),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn natural_order() {
+        let keys = ["file10", "file2", "file1", "file", "file02", "file10a", "file10-1", "1", "a"];
+        assert_eq!(
+            sorted::<NaturalOrderComparator>(&keys),
+            ["1", "a", "file", "file1", "file02", "file2", "file10", "file10-1", "file10a"]
+        );
+        assert_eq!(NaturalOrderComparator::compare(b"v01", b"v1"), Ordering::Less);
+        assert_eq!(NaturalOrderComparator::compare(b"v1", b"v1"), Ordering::Equal);
+        let large = NaturalOrderComparator::compare(
+            b"99999999999999999999999",
+            b"100000000000000000000000",
+        );
+        assert_eq!(large, Ordering::Less);
+    }
+
+    #[test]
+    fn big_endian_signed() {
+        let mut numbers = vec![0i64, -1, 1, i64::MIN, i64::MAX, -300, 300];
+        let mut keys: Vec<_> = numbers.iter().map(|n| n.to_be_bytes()).collect();
+        keys.sort_by(|a, b| BigEndianSignedComparator::compare(a, b));
+        numbers.sort();
+        assert_eq!(keys.into_iter().map(i64::from_be_bytes).collect::<Vec<_>>(), numbers);
+
+        let mut keys: Vec<_> = (i8::MIN..=i8::MAX).rev().map(|n| n.to_be_bytes()).collect();
+        keys.sort_by(|a, b| BigEndianSignedComparator::compare(a, b));
+        assert!(keys.into_iter().map(i8::from_be_bytes).eq(i8::MIN..=i8::MAX));
+    }
+}
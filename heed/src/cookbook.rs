@@ -145,6 +145,13 @@
 //! in big endian we can create a prefix codec that restricts a subset of the data. It is recommended
 //! to create codecs to encode prefixes when possible instead of using a slice of bytes.
 //!
+//! Codecs that allocate their bytes can also implement [`BytesEncode::bytes_encode_into_writer`],
+//! the databases then encode the keys and values into buffers reused by the transaction.
+//!
+//! The codecs of this example can also be generated with the `HeedCodec` derive macro
+//! available with the `derive` feature, it encodes the integers in an order-preserving way
+//! and generates a prefix codec for the fields marked with `#[heed(prefix)]`.
+//!
 //! ```
 //! use std::borrow::Cow;
 //! use std::error::Error;
@@ -171,9 +178,10 @@
 //!
 //! impl<'a> BytesEncode<'a> for LogKeyCodec {
 //!     type EItem = LogKey;
+//!     type Error = BoxedError;
 //!
 //!     /// Encodes the u32 timestamp in big endian followed by the log level with a single byte.
-//!     fn bytes_encode(log: &Self::EItem) -> Result<Cow<[u8]>, BoxedError> {
+//!     fn bytes_encode(log: &Self::EItem) -> Result<Cow<[u8]>, Self::Error> {
 //!         let (timestamp_bytes, level_byte) = match log {
 //!             LogKey { timestamp, level: Level::Debug } => (timestamp.to_be_bytes(), 0),
 //!             LogKey { timestamp, level: Level::Warn } => (timestamp.to_be_bytes(), 1),
@@ -189,8 +197,9 @@
 //!
 //! impl<'a> BytesDecode<'a> for LogKeyCodec {
 //!     type DItem = LogKey;
+//!     type Error = BoxedError;
 //!
-//!     fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, BoxedError> {
+//!     fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, Self::Error> {
 //!         use std::mem::size_of;
 //!
 //!         let timestamp = match bytes.get(..size_of::<u32>()) {
@@ -217,17 +226,19 @@
 //!
 //! impl<'a> BytesEncode<'a> for LogAtHalfTimestampCodec {
 //!     type EItem = u32;
+//!     type Error = BoxedError;
 //!
 //!     /// This method encodes only the prefix of the keys in this particular case, the timestamp.
-//!     fn bytes_encode(half_timestamp: &Self::EItem) -> Result<Cow<[u8]>, BoxedError> {
+//!     fn bytes_encode(half_timestamp: &Self::EItem) -> Result<Cow<[u8]>, Self::Error> {
 //!         Ok(Cow::Owned(half_timestamp.to_be_bytes()[..2].to_vec()))
 //!     }
 //! }
 //!
 //! impl<'a> BytesDecode<'a> for LogAtHalfTimestampCodec {
 //!     type DItem = LogKey;
+//!     type Error = BoxedError;
 //!
-//!     fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, BoxedError> {
+//!     fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, Self::Error> {
 //!         LogKeyCodec::bytes_decode(bytes)
 //!     }
 //! }
@@ -1,5 +1,5 @@
 use std::ops::{Deref, DerefMut};
-use std::{marker, mem, ptr};
+use std::{mem, ptr};
 
 use crate::mdb::error::mdb_result;
 use crate::mdb::ffi;
@@ -7,17 +7,38 @@ use crate::*;
 
 pub struct RoCursor<'txn> {
     cursor: *mut ffi::MDB_cursor,
-    _marker: marker::PhantomData<&'txn ()>,
+    txn: &'txn RoTxn<'txn>,
+    dbi: ffi::MDB_dbi,
 }
 
 impl<'txn> RoCursor<'txn> {
-    pub(crate) fn new(txn: &'txn RoTxn, dbi: ffi::MDB_dbi) -> Result<RoCursor<'txn>> {
+    pub(crate) fn new<E>(txn: &'txn RoTxn, dbi: ffi::MDB_dbi) -> Result<RoCursor<'txn>, Error<E>> {
         let mut cursor: *mut ffi::MDB_cursor = ptr::null_mut();
         unsafe { mdb_result(ffi::mdb_cursor_open(txn.txn, dbi, &mut cursor))? }
-        Ok(RoCursor { cursor, _marker: marker::PhantomData })
+        Ok(RoCursor { cursor, txn, dbi })
     }
 
-    pub fn current(&mut self) -> Result<Option<(&'txn [u8], &'txn [u8])>> {
+    /// Compares two keys with the comparator of the database of this cursor,
+    /// the custom comparators are taken into account.
+    pub(crate) fn compare_keys(&self, a: &[u8], b: &[u8]) -> std::cmp::Ordering {
+        let a = unsafe { crate::into_val(a) };
+        let b = unsafe { crate::into_val(b) };
+        unsafe { ffi::mdb_cmp(ffi::mdb_cursor_txn(self.cursor), self.dbi, &a, &b) }.cmp(&0)
+    }
+
+    /// Converts the decoding error of the entry with the given key into an [`Error`]
+    /// with the iteration context, if enabled with [`EnvOpenOptions::error_context`].
+    pub(crate) fn decoding_error<E>(&self, key: &[u8], error: E) -> Error<E> {
+        let error = Error::Decoding(error);
+        self.txn.env.error_with_context(self.dbi, Operation::Iteration, Some(key), error)
+    }
+
+    /// The transaction of this cursor, whose buffers are reused to encode the entries.
+    pub(crate) fn txn(&self) -> &'txn RoTxn<'txn> {
+        self.txn
+    }
+
+    pub fn current<E>(&mut self) -> Result<Option<(&'txn [u8], &'txn [u8])>, Error<E>> {
         let mut key_val = mem::MaybeUninit::uninit();
         let mut data_val = mem::MaybeUninit::uninit();
 
@@ -42,7 +63,10 @@ impl<'txn> RoCursor<'txn> {
         }
     }
 
-    pub fn move_on_first(&mut self, op: MoveOperation) -> Result<Option<(&'txn [u8], &'txn [u8])>> {
+    pub fn move_on_first<E>(
+        &mut self,
+        op: MoveOperation,
+    ) -> Result<Option<(&'txn [u8], &'txn [u8])>, Error<E>> {
         let mut key_val = mem::MaybeUninit::uninit();
         let mut data_val = mem::MaybeUninit::uninit();
 
@@ -83,7 +107,10 @@ impl<'txn> RoCursor<'txn> {
         }
     }
 
-    pub fn move_on_last(&mut self, op: MoveOperation) -> Result<Option<(&'txn [u8], &'txn [u8])>> {
+    pub fn move_on_last<E>(
+        &mut self,
+        op: MoveOperation,
+    ) -> Result<Option<(&'txn [u8], &'txn [u8])>, Error<E>> {
         let mut key_val = mem::MaybeUninit::uninit();
         let mut data_val = mem::MaybeUninit::uninit();
 
@@ -124,7 +151,7 @@ impl<'txn> RoCursor<'txn> {
         }
     }
 
-    pub fn move_on_key(&mut self, key: &[u8]) -> Result<bool> {
+    pub fn move_on_key<E>(&mut self, key: &[u8]) -> Result<bool, Error<E>> {
         let mut key_val = unsafe { crate::into_val(key) };
 
         // Move the cursor to the specified key
@@ -144,10 +171,10 @@ impl<'txn> RoCursor<'txn> {
         }
     }
 
-    pub fn move_on_key_greater_than_or_equal_to(
+    pub fn move_on_key_greater_than_or_equal_to<E>(
         &mut self,
         key: &[u8],
-    ) -> Result<Option<(&'txn [u8], &'txn [u8])>> {
+    ) -> Result<Option<(&'txn [u8], &'txn [u8])>, Error<E>> {
         let mut key_val = unsafe { crate::into_val(key) };
         let mut data_val = mem::MaybeUninit::uninit();
 
@@ -172,7 +199,10 @@ impl<'txn> RoCursor<'txn> {
         }
     }
 
-    pub fn move_on_prev(&mut self, op: MoveOperation) -> Result<Option<(&'txn [u8], &'txn [u8])>> {
+    pub fn move_on_prev<E>(
+        &mut self,
+        op: MoveOperation,
+    ) -> Result<Option<(&'txn [u8], &'txn [u8])>, Error<E>> {
         let mut key_val = mem::MaybeUninit::uninit();
         let mut data_val = mem::MaybeUninit::uninit();
 
@@ -203,7 +233,10 @@ impl<'txn> RoCursor<'txn> {
         }
     }
 
-    pub fn move_on_next(&mut self, op: MoveOperation) -> Result<Option<(&'txn [u8], &'txn [u8])>> {
+    pub fn move_on_next<E>(
+        &mut self,
+        op: MoveOperation,
+    ) -> Result<Option<(&'txn [u8], &'txn [u8])>, Error<E>> {
         let mut key_val = mem::MaybeUninit::uninit();
         let mut data_val = mem::MaybeUninit::uninit();
 
@@ -246,7 +279,7 @@ pub struct RwCursor<'txn> {
 }
 
 impl<'txn> RwCursor<'txn> {
-    pub(crate) fn new(txn: &'txn RwTxn, dbi: ffi::MDB_dbi) -> Result<RwCursor<'txn>> {
+    pub(crate) fn new<E>(txn: &'txn RwTxn, dbi: ffi::MDB_dbi) -> Result<RwCursor<'txn>, Error<E>> {
         Ok(RwCursor { cursor: RoCursor::new(txn, dbi)? })
     }
 
@@ -263,7 +296,7 @@ impl<'txn> RwCursor<'txn> {
     /// > or the end of the transaction.](http://www.lmdb.tech/doc/group__mdb.html#structMDB__val)
     ///
     /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
-    pub unsafe fn del_current(&mut self) -> Result<bool> {
+    pub unsafe fn del_current<E>(&mut self) -> Result<bool, Error<E>> {
         // Delete the current entry
         let result = mdb_result(ffi::mdb_cursor_del(self.cursor.cursor, 0));
 
@@ -297,7 +330,7 @@ impl<'txn> RwCursor<'txn> {
     /// > or the end of the transaction.](http://www.lmdb.tech/doc/group__mdb.html#structMDB__val)
     ///
     /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
-    pub unsafe fn put_current(&mut self, key: &[u8], data: &[u8]) -> Result<bool> {
+    pub unsafe fn put_current<E>(&mut self, key: &[u8], data: &[u8]) -> Result<bool, Error<E>> {
         let mut key_val = crate::into_val(key);
         let mut data_val = crate::into_val(data);
 
@@ -329,13 +362,13 @@ impl<'txn> RwCursor<'txn> {
     /// # Safety
     ///
     /// Please read the safety notes of the [`Self::put_current`] method.
-    pub unsafe fn put_current_reserved_with_flags<F>(
+    pub unsafe fn put_current_reserved_with_flags<F, E>(
         &mut self,
         flags: PutFlags,
         key: &[u8],
         data_size: usize,
         write_func: F,
-    ) -> Result<bool>
+    ) -> Result<bool, Error<E>>
     where
         F: FnOnce(&mut ReservedSpace) -> io::Result<()>,
     {
@@ -380,12 +413,12 @@ impl<'txn> RwCursor<'txn> {
     /// > or the end of the transaction.](http://www.lmdb.tech/doc/group__mdb.html#structMDB__val)
     ///
     /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
-    pub unsafe fn put_current_with_flags(
+    pub unsafe fn put_current_with_flags<E>(
         &mut self,
         flags: PutFlags,
         key: &[u8],
         data: &[u8],
-    ) -> Result<()> {
+    ) -> Result<(), Error<E>> {
         let mut key_val = crate::into_val(key);
         let mut data_val = crate::into_val(data);
 
@@ -1,16 +1,19 @@
-use std::borrow::Cow;
-use std::ops::{Bound, RangeBounds};
-use std::{any, fmt, marker, mem, ptr};
+use std::convert::Infallible;
+use std::ops::{Bound, Deref, RangeBounds};
+use std::sync::Arc;
+use std::{any, fmt, io, marker, mem, ptr};
 
 use heed_traits::{Comparator, LexicographicComparator};
 use types::{DecodeIgnore, LazyDecode};
 
+use crate::comparator::DefaultComparator;
 use crate::cursor::MoveOperation;
-use crate::env::DefaultComparator;
-use crate::iteration_method::MoveOnCurrentKeyDuplicates;
+use crate::dyn_comparator::{DynComparator, RuntimeComparator};
+use crate::iteration_method::{MoveOnCurrentKeyDuplicates, MoveThroughDuplicateValues};
 use crate::mdb::error::mdb_result;
 use crate::mdb::ffi;
 use crate::mdb::lmdb_flags::{AllDatabaseFlags, DatabaseFlags};
+use crate::txn::ScratchBuffer;
 use crate::*;
 
 /// Options and flags which can be used to configure how a [`Database`] is opened.
@@ -55,11 +58,13 @@ use crate::*;
 /// # Ok(()) }
 /// ```
 #[derive(Debug)]
-pub struct DatabaseOpenOptions<'e, 'n, KC, DC, C = DefaultComparator> {
+pub struct DatabaseOpenOptions<'e, 'n, KC, DC, C = DefaultComparator, CDUP = DefaultComparator> {
     env: &'e Env,
-    types: marker::PhantomData<(KC, DC, C)>,
+    types: marker::PhantomData<(KC, DC, C, CDUP)>,
     name: Option<&'n str>,
     flags: AllDatabaseFlags,
+    /// The flags required by the integer codecs, added to the flags when opening the database.
+    integer_flags: AllDatabaseFlags,
 }
 
 impl<'e> DatabaseOpenOptions<'e, 'static, Unspecified, Unspecified> {
@@ -70,32 +75,54 @@ impl<'e> DatabaseOpenOptions<'e, 'static, Unspecified, Unspecified> {
             types: Default::default(),
             name: None,
             flags: AllDatabaseFlags::empty(),
+            integer_flags: AllDatabaseFlags::empty(),
         }
     }
 }
 
-impl<'e, 'n, KC, DC, C> DatabaseOpenOptions<'e, 'n, KC, DC, C> {
+impl<'e, 'n, KC, DC, C, CDUP> DatabaseOpenOptions<'e, 'n, KC, DC, C, CDUP> {
     /// Change the type of the database.
     ///
     /// The default types are [`Unspecified`] and require a call to [`Database::remap_types`]
-    /// to use the [`Database`].
+    /// to use the [`Database`]. The flags added for the integer codecs are removed.
     pub fn types<NKC, NDC>(self) -> DatabaseOpenOptions<'e, 'n, NKC, NDC> {
         DatabaseOpenOptions {
             env: self.env,
             types: Default::default(),
             name: self.name,
             flags: self.flags,
+            integer_flags: AllDatabaseFlags::empty(),
         }
     }
     /// Change the customized key compare function of the database.
     ///
     /// By default no customized compare function will be set when opening a database.
-    pub fn key_comparator<NC>(self) -> DatabaseOpenOptions<'e, 'n, KC, DC, NC> {
+    pub fn key_comparator<NC>(self) -> DatabaseOpenOptions<'e, 'n, KC, DC, NC, CDUP> {
         DatabaseOpenOptions {
             env: self.env,
             types: Default::default(),
             name: self.name,
             flags: self.flags,
+            integer_flags: self.integer_flags,
+        }
+    }
+
+    /// Change the customized compare function of the duplicate values of the database.
+    ///
+    /// By default no customized compare function will be set when opening a database
+    /// and the duplicate values are ordered lexicographically. It is only used by the
+    /// databases opened with the [`DatabaseFlags::DUP_SORT`] flag, to order the values
+    /// returned by [`Database::get_duplicates`] and the other iterators over duplicates.
+    ///
+    /// Like the key comparator, the comparator must be the same every time the database
+    /// is opened, a panic while comparing the values aborts the process.
+    pub fn dup_sort_comparator<NCDUP>(self) -> DatabaseOpenOptions<'e, 'n, KC, DC, C, NCDUP> {
+        DatabaseOpenOptions {
+            env: self.env,
+            types: Default::default(),
+            name: self.name,
+            flags: self.flags,
+            integer_flags: self.integer_flags,
         }
     }
 
@@ -108,11 +135,19 @@ impl<'e, 'n, KC, DC, C> DatabaseOpenOptions<'e, 'n, KC, DC, C> {
     }
 
     /// Specify the set of flags used to open the database.
+    ///
+    /// The flags added by [`DatabaseOpenOptions::integer_key`] and
+    /// [`DatabaseOpenOptions::integer_dup`] are kept apart and always used.
     pub fn flags(&mut self, flags: DatabaseFlags) -> &mut Self {
         self.flags = AllDatabaseFlags::from_bits(flags.bits()).unwrap();
         self
     }
 
+    /// The flags given to LMDB to open the database.
+    fn all_flags(&self) -> AllDatabaseFlags {
+        self.flags | self.integer_flags
+    }
+
     /// Opens a typed database that already exists in this environment.
     ///
     /// If the database was previously opened in this program run, types will be checked.
@@ -131,16 +166,20 @@ impl<'e, 'n, KC, DC, C> DatabaseOpenOptions<'e, 'n, KC, DC, C> {
     ///
     /// If not done, you might raise `Io(Os { code: 22, kind: InvalidInput, message: "Invalid argument" })`
     /// known as `EINVAL`.
-    pub fn open(&self, rtxn: &RoTxn) -> Result<Option<Database<KC, DC, C>>>
+    pub fn open(&self, rtxn: &RoTxn) -> Result<Option<Database<KC, DC, C, CDUP>>>
     where
         KC: 'static,
         DC: 'static,
         C: Comparator + 'static,
+        CDUP: Comparator + 'static,
     {
         assert_eq_env_txn!(self.env, rtxn);
 
-        match self.env.raw_init_database::<C>(rtxn.txn, self.name, self.flags) {
-            Ok(dbi) => Ok(Some(Database::new(self.env.env_mut_ptr() as _, dbi))),
+        match self.env.raw_init_database::<C, CDUP>(rtxn, self.name, self.all_flags()) {
+            Ok(dbi) => {
+                let dup_sort = is_dup_sort(rtxn.txn, dbi)?;
+                Ok(Some(Database::new(self.env.env_mut_ptr() as _, dbi, dup_sort)))
+            }
             Err(Error::Mdb(e)) if e.not_found() => Ok(None),
             Err(e) => Err(e),
         }
@@ -155,29 +194,289 @@ impl<'e, 'n, KC, DC, C> DatabaseOpenOptions<'e, 'n, KC, DC, C> {
     /// LMDB has an important restriction on the unnamed database when named ones are opened.
     /// The names of the named databases are stored as keys in the unnamed one and are immutable,
     /// and these keys can only be read and not written.
-    pub fn create(&self, wtxn: &mut RwTxn) -> Result<Database<KC, DC, C>>
+    pub fn create(&self, wtxn: &mut RwTxn) -> Result<Database<KC, DC, C, CDUP>>
     where
         KC: 'static,
         DC: 'static,
         C: Comparator + 'static,
+        CDUP: Comparator + 'static,
     {
         assert_eq_env_txn!(self.env, wtxn);
 
-        let flags = self.flags | AllDatabaseFlags::CREATE;
-        match self.env.raw_init_database::<C>(wtxn.txn.txn, self.name, flags) {
-            Ok(dbi) => Ok(Database::new(self.env.env_mut_ptr() as _, dbi)),
+        let flags = self.all_flags() | AllDatabaseFlags::CREATE;
+        match self.env.raw_init_database::<C, CDUP>(wtxn, self.name, flags) {
+            Ok(dbi) => {
+                let dup_sort = is_dup_sort(wtxn.txn.txn, dbi)?;
+                Ok(Database::new(self.env.env_mut_ptr() as _, dbi, dup_sort))
+            }
             Err(e) => Err(e),
         }
     }
 }
 
-impl<KC, DC, C> Clone for DatabaseOpenOptions<'_, '_, KC, DC, C> {
+impl<'e, 'n, KC, DC, CDUP> DatabaseOpenOptions<'e, 'n, KC, DC, DefaultComparator, CDUP> {
+    /// Change the key compare function of the database for a comparator chosen at runtime.
+    ///
+    /// LMDB only gives the keys to the compare functions, the comparator is therefore
+    /// registered for the environment and the database, and called by a trampoline set
+    /// with `mdb_set_compare`. It is released when the environment is closed or when the
+    /// transaction that opened the database is aborted. At most 64 databases can use a
+    /// runtime comparator at the same time, opening more returns an
+    /// [`Error::DynComparatorsFull`]. Opening the database again keeps the comparator
+    /// it was first opened with.
+    ///
+    /// The comparator cannot be installed on a database already opened without one,
+    /// like the unnamed database that LMDB always keeps opened, an
+    /// [`MdbError::Incompatible`](crate::MdbError::Incompatible) error is returned.
+    ///
+    /// The comparator type of the database is [`RuntimeComparator`]: the range
+    /// iterators use the runtime comparator but the prefix iterators cannot be used.
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # use heed::EnvOpenOptions;
+    /// use std::sync::Arc;
+    ///
+    /// use heed::types::*;
+    /// use heed::DynComparator;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let dir = tempfile::tempdir()?;
+    /// # let env = unsafe { EnvOpenOptions::new().max_dbs(1).open(dir.path())? };
+    /// // The ordering is read from a schema file.
+    /// let descending = true;
+    /// let comparator: Arc<dyn DynComparator> = if descending {
+    ///     Arc::new(|a: &[u8], b: &[u8]| b.cmp(a))
+    /// } else {
+    ///     Arc::new(|a: &[u8], b: &[u8]| a.cmp(b))
+    /// };
+    ///
+    /// let mut wtxn = env.write_txn()?;
+    /// let db = env
+    ///     .database_options()
+    ///     .types::<Str, Unit>()
+    ///     .name("letters")
+    ///     .dyn_key_comparator(comparator)
+    ///     .create(&mut wtxn)?;
+    ///
+    /// for key in ["a", "c", "b", "d"] {
+    ///     db.put(&mut wtxn, key, &())?;
+    /// }
+    ///
+    /// let keys: Vec<_> = db.iter(&wtxn)?.map(|r| r.map(|(k, _)| k)).collect::<Result<_, _>>()?;
+    /// assert_eq!(keys, ["d", "c", "b", "a"]);
+    /// # Ok(()) }
+    /// ```
+    pub fn dyn_key_comparator(
+        self,
+        comparator: Arc<dyn DynComparator>,
+    ) -> DynDatabaseOpenOptions<'e, 'n, KC, DC, CDUP> {
+        DynDatabaseOpenOptions { options: self, comparator }
+    }
+
+    /// Adds the [`DatabaseFlags::INTEGER_KEY`] flag, the keys are ordered as native integers.
+    ///
+    /// The key codec must be a [`IntegerCodec`] to encode the keys as LMDB expects,
+    /// the flag is kept when [`DatabaseOpenOptions::flags`] is called afterward.
+    pub fn integer_key(&mut self) -> &mut Self
+    where
+        KC: IntegerCodec,
+    {
+        self.integer_flags |= AllDatabaseFlags::INTEGER_KEY;
+        self
+    }
+}
+
+impl<KC, DC, C> DatabaseOpenOptions<'_, '_, KC, DC, C, DefaultComparator> {
+    /// Adds the [`DatabaseFlags::DUP_SORT`], [`DatabaseFlags::DUP_FIXED`] and
+    /// [`DatabaseFlags::INTEGER_DUP`] flags, the duplicate values are ordered as native integers.
+    ///
+    /// The data codec must be a [`IntegerCodec`] to encode the values as LMDB expects,
+    /// the flags are kept when [`DatabaseOpenOptions::flags`] is called afterward.
+    pub fn integer_dup(&mut self) -> &mut Self
+    where
+        DC: IntegerCodec,
+    {
+        self.integer_flags |= INTEGER_DUP_FLAGS;
+        self
+    }
+}
+
+/// The flags added by [`DatabaseOpenOptions::integer_dup`].
+const INTEGER_DUP_FLAGS: AllDatabaseFlags = AllDatabaseFlags::DUP_SORT
+    .union(AllDatabaseFlags::DUP_FIXED)
+    .union(AllDatabaseFlags::INTEGER_DUP);
+
+/// A codec encoding native integers of the size expected by the [`DatabaseFlags::INTEGER_KEY`]
+/// and [`DatabaseFlags::INTEGER_DUP`] flags, either an `unsigned int` or a `size_t`.
+///
+/// It is only implemented for the [`NativeU32Key`] and [`NativeUsizeKey`] codecs, the other
+/// integer codecs, like `U64<BigEndian>`, would be silently misordered by LMDB.
+///
+/// [`NativeU32Key`]: crate::types::NativeU32Key
+/// [`NativeUsizeKey`]: crate::types::NativeUsizeKey
+pub trait IntegerCodec: sealed::Sealed {}
+
+impl IntegerCodec for types::NativeU32Key {}
+
+impl IntegerCodec for types::NativeUsizeKey {}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for crate::types::NativeU32Key {}
+
+    impl Sealed for crate::types::NativeUsizeKey {}
+}
+
+/// A database whose keys are native integers ordered by LMDB with the
+/// [`DatabaseFlags::INTEGER_KEY`] flag.
+///
+/// It is created by [`Env::create_integer_key_database`] and opened by
+/// [`Env::open_integer_key_database`], that only accept the [`IntegerCodec`]s for the keys.
+/// It dereferences to the underlying [`Database`], whose methods are used to read and write.
+pub struct IntegerKeyDatabase<KC: IntegerCodec, DC> {
+    database: Database<KC, DC>,
+}
+
+impl<KC: IntegerCodec, DC> IntegerKeyDatabase<KC, DC> {
+    pub(crate) fn new(database: Database<KC, DC>) -> IntegerKeyDatabase<KC, DC> {
+        IntegerKeyDatabase { database }
+    }
+
+    /// Returns the underlying database.
+    pub fn into_database(self) -> Database<KC, DC> {
+        self.database
+    }
+}
+
+impl<KC: IntegerCodec, DC> Deref for IntegerKeyDatabase<KC, DC> {
+    type Target = Database<KC, DC>;
+
+    fn deref(&self) -> &Database<KC, DC> {
+        &self.database
+    }
+}
+
+impl<KC: IntegerCodec, DC> Clone for IntegerKeyDatabase<KC, DC> {
+    fn clone(&self) -> IntegerKeyDatabase<KC, DC> {
+        *self
+    }
+}
+
+impl<KC: IntegerCodec, DC> Copy for IntegerKeyDatabase<KC, DC> {}
+
+impl<KC: IntegerCodec, DC> fmt::Debug for IntegerKeyDatabase<KC, DC> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("IntegerKeyDatabase")
+            .field("key_codec", &any::type_name::<KC>())
+            .field("data_codec", &any::type_name::<DC>())
+            .finish()
+    }
+}
+
+impl<KC, DC, C, CDUP> Clone for DatabaseOpenOptions<'_, '_, KC, DC, C, CDUP> {
     fn clone(&self) -> Self {
         *self
     }
 }
 
-impl<KC, DC, C> Copy for DatabaseOpenOptions<'_, '_, KC, DC, C> {}
+impl<KC, DC, C, CDUP> Copy for DatabaseOpenOptions<'_, '_, KC, DC, C, CDUP> {}
+
+/// Options and flags to open or create a database whose keys are compared by a
+/// [`DynComparator`], built with [`DatabaseOpenOptions::dyn_key_comparator`].
+///
+/// Unlike [`DatabaseOpenOptions`] it holds the comparator and is therefore only [`Clone`].
+pub struct DynDatabaseOpenOptions<'e, 'n, KC, DC, CDUP = DefaultComparator> {
+    options: DatabaseOpenOptions<'e, 'n, KC, DC, DefaultComparator, CDUP>,
+    comparator: Arc<dyn DynComparator>,
+}
+
+impl<'e, 'n, KC, DC, CDUP> DynDatabaseOpenOptions<'e, 'n, KC, DC, CDUP> {
+    /// Change the customized compare function of the duplicate values of the database.
+    ///
+    /// See [`DatabaseOpenOptions::dup_sort_comparator`].
+    pub fn dup_sort_comparator<NCDUP>(self) -> DynDatabaseOpenOptions<'e, 'n, KC, DC, NCDUP> {
+        DynDatabaseOpenOptions {
+            options: self.options.dup_sort_comparator(),
+            comparator: self.comparator,
+        }
+    }
+
+    /// Change the name of the database.
+    ///
+    /// By default the database is unnamed and there only is a single unnamed database.
+    pub fn name(&mut self, name: &'n str) -> &mut Self {
+        self.options.name(name);
+        self
+    }
+
+    /// Specify the set of flags used to open the database.
+    pub fn flags(&mut self, flags: DatabaseFlags) -> &mut Self {
+        self.options.flags(flags);
+        self
+    }
+
+    /// Opens a typed database that already exists in this environment, whose keys are
+    /// compared by the runtime comparator.
+    ///
+    /// See [`DatabaseOpenOptions::dyn_key_comparator`] for how the comparator is used.
+    pub fn open(&self, rtxn: &RoTxn) -> Result<Option<Database<KC, DC, RuntimeComparator, CDUP>>>
+    where
+        KC: 'static,
+        DC: 'static,
+        CDUP: Comparator + 'static,
+    {
+        let DatabaseOpenOptions { env, name, .. } = self.options;
+        let flags = self.options.all_flags();
+        assert_eq_env_txn!(env, rtxn);
+
+        let comparator = self.comparator.clone();
+        match env.raw_init_dyn_database::<CDUP>(rtxn, name, flags, comparator) {
+            Ok(dbi) => {
+                let dup_sort = is_dup_sort(rtxn.txn, dbi)?;
+                Ok(Some(Database::new(env.env_mut_ptr() as _, dbi, dup_sort)))
+            }
+            Err(Error::Mdb(e)) if e.not_found() => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Creates a typed database that can already exist in this environment, whose keys
+    /// are compared by the runtime comparator.
+    ///
+    /// See [`DatabaseOpenOptions::dyn_key_comparator`] for how the comparator is used.
+    pub fn create(&self, wtxn: &mut RwTxn) -> Result<Database<KC, DC, RuntimeComparator, CDUP>>
+    where
+        KC: 'static,
+        DC: 'static,
+        CDUP: Comparator + 'static,
+    {
+        let DatabaseOpenOptions { env, name, .. } = self.options;
+        assert_eq_env_txn!(env, wtxn);
+
+        let flags = self.options.all_flags() | AllDatabaseFlags::CREATE;
+        let comparator = self.comparator.clone();
+        let dbi = env.raw_init_dyn_database::<CDUP>(wtxn, name, flags, comparator)?;
+        let dup_sort = is_dup_sort(wtxn.txn.txn, dbi)?;
+        Ok(Database::new(env.env_mut_ptr() as _, dbi, dup_sort))
+    }
+}
+
+impl<KC, DC, CDUP> Clone for DynDatabaseOpenOptions<'_, '_, KC, DC, CDUP> {
+    fn clone(&self) -> Self {
+        DynDatabaseOpenOptions { options: self.options, comparator: self.comparator.clone() }
+    }
+}
+
+impl<KC, DC, CDUP> fmt::Debug for DynDatabaseOpenOptions<'_, '_, KC, DC, CDUP> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynDatabaseOpenOptions")
+            .field("name", &self.options.name)
+            .field("flags", &self.options.all_flags())
+            .finish_non_exhaustive()
+    }
+}
 
 /// A typed database that accepts only the types it was created with.
 ///
@@ -291,15 +590,21 @@ impl<KC, DC, C> Copy for DatabaseOpenOptions<'_, '_, KC, DC, C> {}
 /// wtxn.commit()?;
 /// # Ok(()) }
 /// ```
-pub struct Database<KC, DC, C = DefaultComparator> {
+pub struct Database<KC, DC, C = DefaultComparator, CDUP = DefaultComparator, E = BoxedError> {
     pub(crate) env_ident: usize,
     pub(crate) dbi: ffi::MDB_dbi,
-    marker: marker::PhantomData<(KC, DC, C)>,
+    /// Whether the database was opened with the [`DatabaseFlags::DUP_SORT`] flag.
+    dup_sort: bool,
+    marker: marker::PhantomData<(KC, DC, C, CDUP, E)>,
 }
 
-impl<KC, DC, C> Database<KC, DC, C> {
-    pub(crate) fn new(env_ident: usize, dbi: ffi::MDB_dbi) -> Database<KC, DC, C> {
-        Database { env_ident, dbi, marker: std::marker::PhantomData }
+impl<KC, DC, C, CDUP, E> Database<KC, DC, C, CDUP, E> {
+    pub(crate) fn new(
+        env_ident: usize,
+        dbi: ffi::MDB_dbi,
+        dup_sort: bool,
+    ) -> Database<KC, DC, C, CDUP, E> {
+        Database { env_ident, dbi, dup_sort, marker: std::marker::PhantomData }
     }
 
     /// Retrieves the value associated with a key.
@@ -339,16 +644,25 @@ impl<KC, DC, C> Database<KC, DC, C> {
     /// wtxn.commit()?;
     /// # Ok(()) }
     /// ```
-    pub fn get<'a, 'txn>(&self, txn: &'txn RoTxn, key: &'a KC::EItem) -> Result<Option<DC::DItem>>
+    pub fn get<'a, 'txn>(
+        &self,
+        txn: &'txn RoTxn,
+        key: &'a KC::EItem,
+    ) -> Result<Option<DC::DItem>, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         DC: BytesDecode<'txn>,
+        DC::Error: Into<E>,
     {
         assert_eq_env_db_txn!(self, txn);
 
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer
+            .encode::<KC>(key)
+            .map_err(|e| self.with_context(txn, Operation::Get, None, Error::encoding(e)))?;
 
-        let mut key_val = unsafe { crate::into_val(&key_bytes) };
+        let mut key_val = unsafe { crate::into_val(key_bytes) };
         let mut data_val = mem::MaybeUninit::uninit();
 
         let result = unsafe {
@@ -358,11 +672,18 @@ impl<KC, DC, C> Database<KC, DC, C> {
         match result {
             Ok(()) => {
                 let data = unsafe { crate::from_val(data_val.assume_init()) };
-                let data = DC::bytes_decode(data).map_err(Error::Decoding)?;
+                let data = DC::bytes_decode(data).map_err(|e| {
+                    self.with_context(
+                        txn,
+                        Operation::Get,
+                        Some(key_bytes),
+                        Error::Decoding(e.into()),
+                    )
+                })?;
                 Ok(Some(data))
             }
             Err(e) if e.not_found() => Ok(None),
-            Err(e) => Err(e.into()),
+            Err(e) => Err(self.with_context(txn, Operation::Get, Some(key_bytes), e.into())),
         }
     }
 
@@ -422,15 +743,19 @@ impl<KC, DC, C> Database<KC, DC, C> {
         &self,
         txn: &'txn RoTxn,
         key: &'a KC::EItem,
-    ) -> Result<Option<RoIter<'txn, KC, DC, MoveOnCurrentKeyDuplicates>>>
+    ) -> Result<Option<RoIter<'txn, KC, DC, MoveOnCurrentKeyDuplicates, E>>, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
     {
         assert_eq_env_db_txn!(self, txn);
 
         let mut cursor = RoCursor::new(txn, self.dbi)?;
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        if cursor.move_on_key(&key_bytes)? {
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer
+            .encode::<KC>(key)
+            .map_err(|e| self.with_context(txn, Operation::Get, None, Error::encoding(e)))?;
+        if cursor.move_on_key(key_bytes)? {
             Ok(Some(RoIter::new(cursor)))
         } else {
             Ok(None)
@@ -485,21 +810,32 @@ impl<KC, DC, C> Database<KC, DC, C> {
         &self,
         txn: &'txn RoTxn,
         key: &'a KC::EItem,
-    ) -> Result<Option<(KC::DItem, DC::DItem)>>
+    ) -> Result<Option<(KC::DItem, DC::DItem)>, Error<E>>
     where
         KC: BytesEncode<'a> + BytesDecode<'txn>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         DC: BytesDecode<'txn>,
+        <KC as BytesDecode<'txn>>::Error: Into<E>,
+        DC::Error: Into<E>,
     {
         assert_eq_env_db_txn!(self, txn);
 
         let mut cursor = RoCursor::new(txn, self.dbi)?;
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        cursor.move_on_key_greater_than_or_equal_to(&key_bytes)?;
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer
+            .encode::<KC>(key)
+            .map_err(|e| self.with_context(txn, Operation::Get, None, Error::encoding(e)))?;
+        cursor.move_on_key_greater_than_or_equal_to(key_bytes)?;
 
         match cursor.move_on_prev(MoveOperation::NoDup) {
-            Ok(Some((key, data))) => match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+            Ok(Some((key, data))) => match (
+                KC::bytes_decode(key).map_err(Into::into),
+                DC::bytes_decode(data).map_err(Into::into),
+            ) {
                 (Ok(key), Ok(data)) => Ok(Some((key, data))),
-                (Err(e), _) | (_, Err(e)) => Err(Error::Decoding(e)),
+                (Err(e), _) | (_, Err(e)) => {
+                    Err(self.with_context(txn, Operation::Get, Some(key), Error::Decoding(e)))
+                }
             },
             Ok(None) => Ok(None),
             Err(e) => Err(e),
@@ -554,25 +890,38 @@ impl<KC, DC, C> Database<KC, DC, C> {
         &self,
         txn: &'txn RoTxn,
         key: &'a KC::EItem,
-    ) -> Result<Option<(KC::DItem, DC::DItem)>>
+    ) -> Result<Option<(KC::DItem, DC::DItem)>, Error<E>>
     where
         KC: BytesEncode<'a> + BytesDecode<'txn>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         DC: BytesDecode<'txn>,
+        <KC as BytesDecode<'txn>>::Error: Into<E>,
+        DC::Error: Into<E>,
     {
         assert_eq_env_db_txn!(self, txn);
 
         let mut cursor = RoCursor::new(txn, self.dbi)?;
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        let result = match cursor.move_on_key_greater_than_or_equal_to(&key_bytes) {
-            Ok(Some((key, data))) if key == &key_bytes[..] => Ok(Some((key, data))),
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer
+            .encode::<KC>(key)
+            .map_err(|e| self.with_context(txn, Operation::Get, None, Error::encoding(e)))?;
+        let result = match cursor.move_on_key_greater_than_or_equal_to(key_bytes) {
+            Ok(Some((key, data))) if cursor.compare_keys(key, key_bytes).is_eq() => {
+                Ok(Some((key, data)))
+            }
             Ok(_) => cursor.move_on_prev(MoveOperation::NoDup),
             Err(e) => Err(e),
         };
 
         match result {
-            Ok(Some((key, data))) => match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+            Ok(Some((key, data))) => match (
+                KC::bytes_decode(key).map_err(Into::into),
+                DC::bytes_decode(data).map_err(Into::into),
+            ) {
                 (Ok(key), Ok(data)) => Ok(Some((key, data))),
-                (Err(e), _) | (_, Err(e)) => Err(Error::Decoding(e)),
+                (Err(e), _) | (_, Err(e)) => {
+                    Err(self.with_context(txn, Operation::Get, Some(key), Error::Decoding(e)))
+                }
             },
             Ok(None) => Ok(None),
             Err(e) => Err(e),
@@ -627,25 +976,36 @@ impl<KC, DC, C> Database<KC, DC, C> {
         &self,
         txn: &'txn RoTxn,
         key: &'a KC::EItem,
-    ) -> Result<Option<(KC::DItem, DC::DItem)>>
+    ) -> Result<Option<(KC::DItem, DC::DItem)>, Error<E>>
     where
         KC: BytesEncode<'a> + BytesDecode<'txn>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         DC: BytesDecode<'txn>,
+        <KC as BytesDecode<'txn>>::Error: Into<E>,
+        DC::Error: Into<E>,
     {
         assert_eq_env_db_txn!(self, txn);
 
         let mut cursor = RoCursor::new(txn, self.dbi)?;
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        let entry = match cursor.move_on_key_greater_than_or_equal_to(&key_bytes)? {
-            Some((key, data)) if key > &key_bytes[..] => Some((key, data)),
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer
+            .encode::<KC>(key)
+            .map_err(|e| self.with_context(txn, Operation::Get, None, Error::encoding(e)))?;
+        let entry = match cursor.move_on_key_greater_than_or_equal_to(key_bytes)? {
+            Some((key, data)) if cursor.compare_keys(key, key_bytes).is_gt() => Some((key, data)),
             Some((_key, _data)) => cursor.move_on_next(MoveOperation::NoDup)?,
             None => None,
         };
 
         match entry {
-            Some((key, data)) => match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+            Some((key, data)) => match (
+                KC::bytes_decode(key).map_err(Into::into),
+                DC::bytes_decode(data).map_err(Into::into),
+            ) {
                 (Ok(key), Ok(data)) => Ok(Some((key, data))),
-                (Err(e), _) | (_, Err(e)) => Err(Error::Decoding(e)),
+                (Err(e), _) | (_, Err(e)) => {
+                    Err(self.with_context(txn, Operation::Get, Some(key), Error::Decoding(e)))
+                }
             },
             None => Ok(None),
         }
@@ -699,19 +1059,30 @@ impl<KC, DC, C> Database<KC, DC, C> {
         &self,
         txn: &'txn RoTxn,
         key: &'a KC::EItem,
-    ) -> Result<Option<(KC::DItem, DC::DItem)>>
+    ) -> Result<Option<(KC::DItem, DC::DItem)>, Error<E>>
     where
         KC: BytesEncode<'a> + BytesDecode<'txn>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         DC: BytesDecode<'txn>,
+        <KC as BytesDecode<'txn>>::Error: Into<E>,
+        DC::Error: Into<E>,
     {
         assert_eq_env_db_txn!(self, txn);
 
         let mut cursor = RoCursor::new(txn, self.dbi)?;
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        match cursor.move_on_key_greater_than_or_equal_to(&key_bytes) {
-            Ok(Some((key, data))) => match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer
+            .encode::<KC>(key)
+            .map_err(|e| self.with_context(txn, Operation::Get, None, Error::encoding(e)))?;
+        match cursor.move_on_key_greater_than_or_equal_to(key_bytes) {
+            Ok(Some((key, data))) => match (
+                KC::bytes_decode(key).map_err(Into::into),
+                DC::bytes_decode(data).map_err(Into::into),
+            ) {
                 (Ok(key), Ok(data)) => Ok(Some((key, data))),
-                (Err(e), _) | (_, Err(e)) => Err(Error::Decoding(e)),
+                (Err(e), _) | (_, Err(e)) => {
+                    Err(self.with_context(txn, Operation::Get, Some(key), Error::Decoding(e)))
+                }
             },
             Ok(None) => Ok(None),
             Err(e) => Err(e),
@@ -754,18 +1125,25 @@ impl<KC, DC, C> Database<KC, DC, C> {
     /// wtxn.commit()?;
     /// # Ok(()) }
     /// ```
-    pub fn first<'txn>(&self, txn: &'txn RoTxn) -> Result<Option<(KC::DItem, DC::DItem)>>
+    pub fn first<'txn>(&self, txn: &'txn RoTxn) -> Result<Option<(KC::DItem, DC::DItem)>, Error<E>>
     where
         KC: BytesDecode<'txn>,
         DC: BytesDecode<'txn>,
+        KC::Error: Into<E>,
+        DC::Error: Into<E>,
     {
         assert_eq_env_db_txn!(self, txn);
 
         let mut cursor = RoCursor::new(txn, self.dbi)?;
         match cursor.move_on_first(MoveOperation::Any) {
-            Ok(Some((key, data))) => match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+            Ok(Some((key, data))) => match (
+                KC::bytes_decode(key).map_err(Into::into),
+                DC::bytes_decode(data).map_err(Into::into),
+            ) {
                 (Ok(key), Ok(data)) => Ok(Some((key, data))),
-                (Err(e), _) | (_, Err(e)) => Err(Error::Decoding(e)),
+                (Err(e), _) | (_, Err(e)) => {
+                    Err(self.with_context(txn, Operation::Get, Some(key), Error::Decoding(e)))
+                }
             },
             Ok(None) => Ok(None),
             Err(e) => Err(e),
@@ -808,18 +1186,25 @@ impl<KC, DC, C> Database<KC, DC, C> {
     /// wtxn.commit()?;
     /// # Ok(()) }
     /// ```
-    pub fn last<'txn>(&self, txn: &'txn RoTxn) -> Result<Option<(KC::DItem, DC::DItem)>>
+    pub fn last<'txn>(&self, txn: &'txn RoTxn) -> Result<Option<(KC::DItem, DC::DItem)>, Error<E>>
     where
         KC: BytesDecode<'txn>,
         DC: BytesDecode<'txn>,
+        KC::Error: Into<E>,
+        DC::Error: Into<E>,
     {
         assert_eq_env_db_txn!(self, txn);
 
         let mut cursor = RoCursor::new(txn, self.dbi)?;
         match cursor.move_on_last(MoveOperation::Any) {
-            Ok(Some((key, data))) => match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+            Ok(Some((key, data))) => match (
+                KC::bytes_decode(key).map_err(Into::into),
+                DC::bytes_decode(data).map_err(Into::into),
+            ) {
                 (Ok(key), Ok(data)) => Ok(Some((key, data))),
-                (Err(e), _) | (_, Err(e)) => Err(Error::Decoding(e)),
+                (Err(e), _) | (_, Err(e)) => {
+                    Err(self.with_context(txn, Operation::Get, Some(key), Error::Decoding(e)))
+                }
             },
             Ok(None) => Ok(None),
             Err(e) => Err(e),
@@ -865,7 +1250,7 @@ impl<KC, DC, C> Database<KC, DC, C> {
     /// wtxn.commit()?;
     /// # Ok(()) }
     /// ```
-    pub fn len(&self, txn: &RoTxn) -> Result<u64> {
+    pub fn len(&self, txn: &RoTxn) -> Result<u64, Error<E>> {
         self.stat(txn).map(|stat| stat.entries as u64)
     }
 
@@ -908,7 +1293,7 @@ impl<KC, DC, C> Database<KC, DC, C> {
     /// wtxn.commit()?;
     /// # Ok(()) }
     /// ```
-    pub fn is_empty(&self, txn: &RoTxn) -> Result<bool> {
+    pub fn is_empty(&self, txn: &RoTxn) -> Result<bool, Error<E>> {
         self.len(txn).map(|l| l == 0)
     }
 
@@ -950,7 +1335,7 @@ impl<KC, DC, C> Database<KC, DC, C> {
     /// wtxn.commit()?;
     /// # Ok(()) }
     /// ```
-    pub fn stat(&self, txn: &RoTxn) -> Result<DatabaseStat> {
+    pub fn stat(&self, txn: &RoTxn) -> Result<DatabaseStat, Error<E>> {
         assert_eq_env_db_txn!(self, txn);
 
         let mut db_stat = mem::MaybeUninit::uninit();
@@ -972,16 +1357,19 @@ impl<KC, DC, C> Database<KC, DC, C> {
         }
     }
 
-    /// Return a lexicographically ordered iterator of all key-value pairs in this database.
+    /// Traverses every entry of this database and reports the anomalies.
     ///
-    /// You can make this iterator `Send`able between threads by
-    /// using the `read-txn-no-tls` crate feature.
+    /// The keys ordering is checked against the comparator of this database, the duplicates
+    /// ordering is checked if it is a `DUP_SORT` database, and every key and data
+    /// is decoded with the codecs of this database. See [`Env::verify`] to verify
+    /// all the databases of an environment.
+    ///
+    /// Only the first `max_anomalies` anomalies are kept in the report, all of them are counted.
     ///
     /// ```
     /// # use std::fs;
     /// # use std::path::Path;
     /// # use heed::EnvOpenOptions;
-    /// use heed::Database;
     /// use heed::types::*;
     /// use heed::byteorder::BigEndian;
     ///
@@ -995,35 +1383,64 @@ impl<KC, DC, C> Database<KC, DC, C> {
     /// type BEI32 = I32<BigEndian>;
     ///
     /// let mut wtxn = env.write_txn()?;
-    /// let db: Database<BEI32, Str> = env.create_database(&mut wtxn, Some("iter-i32"))?;
+    /// let db = env.create_database::<Bytes, Str>(&mut wtxn, Some("iter-i32"))?;
     ///
     /// # db.clear(&mut wtxn)?;
-    /// db.put(&mut wtxn, &42, "i-am-forty-two")?;
-    /// db.put(&mut wtxn, &27, "i-am-twenty-seven")?;
-    /// db.put(&mut wtxn, &13, "i-am-thirteen")?;
+    /// db.put(&mut wtxn, &[0, 0, 0, 42], "i-am-forty-two")?;
+    /// db.put(&mut wtxn, &[0, 0, 27], "i-am-too-short")?;
     ///
-    /// let mut iter = db.iter(&wtxn)?;
-    /// assert_eq!(iter.next().transpose()?, Some((13, "i-am-thirteen")));
-    /// assert_eq!(iter.next().transpose()?, Some((27, "i-am-twenty-seven")));
-    /// assert_eq!(iter.next().transpose()?, Some((42, "i-am-forty-two")));
-    /// assert_eq!(iter.next().transpose()?, None);
+    /// let db = db.remap_key_type::<BEI32>();
+    /// let report = db.verify(&wtxn, 100)?;
+    /// assert_eq!(report.number_of_entries, 2);
+    /// assert_eq!(report.number_of_anomalies, 1);
+    /// assert_eq!(report.anomalies[0].key, [0, 0, 27]);
     ///
-    /// drop(iter);
     /// wtxn.commit()?;
     /// # Ok(()) }
     /// ```
-    pub fn iter<'txn>(&self, txn: &'txn RoTxn) -> Result<RoIter<'txn, KC, DC>> {
+    pub fn verify<'txn>(
+        &self,
+        txn: &'txn RoTxn,
+        max_anomalies: usize,
+    ) -> Result<VerifyReport, Error<E>>
+    where
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+    {
         assert_eq_env_db_txn!(self, txn);
-        RoCursor::new(txn, self.dbi).map(|cursor| RoIter::new(cursor))
+
+        let mut report = VerifyReport::default();
+        verify::verify_database(
+            &mut report,
+            max_anomalies,
+            txn,
+            self.dbi,
+            None,
+            true,
+            |key, data| {
+                KC::bytes_decode(key).map_err(|e| {
+                    AnomalyKind::KeyDecoding(Into::<BoxedError>::into(e).to_string())
+                })?;
+                DC::bytes_decode(data).map_err(|e| {
+                    AnomalyKind::DataDecoding(Into::<BoxedError>::into(e).to_string())
+                })?;
+                Ok(())
+            },
+        )?;
+
+        Ok(report)
     }
 
-    /// Return a mutable lexicographically ordered iterator of all key-value pairs in this database.
+    /// Decodes every entry of this database with its codecs and reports the ones that fail.
+    ///
+    /// It is useful to find the entries that are no longer valid after a codec change.
+    /// Only the first `max_invalid_entries` invalid entries are kept in the report,
+    /// all of them are counted.
     ///
     /// ```
     /// # use std::fs;
     /// # use std::path::Path;
     /// # use heed::EnvOpenOptions;
-    /// use heed::Database;
     /// use heed::types::*;
     /// use heed::byteorder::BigEndian;
     ///
@@ -1034,46 +1451,134 @@ impl<KC, DC, C> Database<KC, DC, C> {
     /// #     .max_dbs(3000)
     /// #     .open(dir.path())?
     /// # };
-    /// type BEI32 = I32<BigEndian>;
+    /// type BEU32 = U32<BigEndian>;
     ///
     /// let mut wtxn = env.write_txn()?;
-    /// let db: Database<BEI32, Str> = env.create_database(&mut wtxn, Some("iter-i32"))?;
+    /// let db = env.create_database::<Str, Bytes>(&mut wtxn, Some("validate"))?;
     ///
     /// # db.clear(&mut wtxn)?;
-    /// db.put(&mut wtxn, &42, "i-am-forty-two")?;
-    /// db.put(&mut wtxn, &27, "i-am-twenty-seven")?;
-    /// db.put(&mut wtxn, &13, "i-am-thirteen")?;
+    /// db.put(&mut wtxn, "forty-two", &42u32.to_be_bytes())?;
+    /// db.put(&mut wtxn, "too-short", &[0, 27])?;
     ///
-    /// let mut iter = db.iter_mut(&mut wtxn)?;
-    /// assert_eq!(iter.next().transpose()?, Some((13, "i-am-thirteen")));
-    /// let ret = unsafe { iter.del_current()? };
-    /// assert!(ret);
+    /// let db = db.remap_data_type::<BEU32>();
+    /// let report = db.validate(&wtxn, 100)?;
+    /// assert_eq!(report.number_of_entries, 2);
+    /// assert_eq!(report.number_of_invalid_entries, 1);
+    /// assert_eq!(report.invalid_entries[0].key, b"too-short");
+    /// assert!(report.invalid_entries[0].key_error.is_none());
+    /// assert!(report.invalid_entries[0].data_error.is_some());
     ///
-    /// assert_eq!(iter.next().transpose()?, Some((27, "i-am-twenty-seven")));
-    /// assert_eq!(iter.next().transpose()?, Some((42, "i-am-forty-two")));
-    /// let ret = unsafe { iter.put_current(&42, "i-am-the-new-forty-two")? };
-    /// assert!(ret);
+    /// wtxn.commit()?;
+    /// # Ok(()) }
+    /// ```
+    pub fn validate<'txn>(
+        &self,
+        txn: &'txn RoTxn,
+        max_invalid_entries: usize,
+    ) -> Result<ValidationReport, Error<E>>
+    where
+        KC: BytesDecode<'txn>,
+        DC: BytesDecode<'txn>,
+    {
+        assert_eq_env_db_txn!(self, txn);
+
+        let database =
+            self.remap_types::<types::Bytes, types::Bytes>().remap_error_type::<Infallible>();
+        let mut report = ValidationReport::default();
+        for result in database.iter(txn).map_err(infallible_codecs)? {
+            let (key, data) = result.map_err(infallible_codecs)?;
+            report.number_of_entries += 1;
+            if let Some(entry) = verify::invalid_entry::<KC, DC>(key, data) {
+                report.record(max_invalid_entries, entry);
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Decodes every entry of this database with its codecs, reports the ones that fail
+    /// and moves them into the `quarantine` database.
     ///
-    /// assert_eq!(iter.next().transpose()?, None);
+    /// The invalid entries are written as is into the `quarantine` database and deleted
+    /// from this one. Use a `DUP_SORT` quarantine database to keep all the invalid
+    /// duplicates of a key. Every invalid entry is moved but only the first
+    /// `max_invalid_entries` ones are kept in the report.
     ///
-    /// drop(iter);
+    /// ```
+    /// # use std::fs;
+    /// # use std::path::Path;
+    /// # use heed::EnvOpenOptions;
+    /// use heed::types::*;
+    /// use heed::byteorder::BigEndian;
     ///
-    /// let ret = db.get(&wtxn, &13)?;
-    /// assert_eq!(ret, None);
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let dir = tempfile::tempdir()?;
+    /// # let env = unsafe { EnvOpenOptions::new()
+    /// #     .map_size(10 * 1024 * 1024) // 10MB
+    /// #     .max_dbs(3000)
+    /// #     .open(dir.path())?
+    /// # };
+    /// type BEU32 = U32<BigEndian>;
     ///
-    /// let ret = db.get(&wtxn, &42)?;
-    /// assert_eq!(ret, Some("i-am-the-new-forty-two"));
+    /// let mut wtxn = env.write_txn()?;
+    /// let db = env.create_database::<Str, Bytes>(&mut wtxn, Some("validate"))?;
+    /// let quarantine = env.create_database::<Bytes, Bytes>(&mut wtxn, Some("quarantine"))?;
+    ///
+    /// # db.clear(&mut wtxn)?;
+    /// db.put(&mut wtxn, "forty-two", &42u32.to_be_bytes())?;
+    /// db.put(&mut wtxn, "too-short", &[0, 27])?;
+    ///
+    /// let db = db.remap_data_type::<BEU32>();
+    /// let report = db.validate_and_quarantine(&mut wtxn, quarantine, 100)?;
+    /// assert_eq!(report.number_of_invalid_entries, 1);
+    ///
+    /// assert_eq!(db.len(&wtxn)?, 1);
+    /// assert_eq!(db.get(&wtxn, "forty-two")?, Some(42));
+    /// assert_eq!(quarantine.get(&wtxn, b"too-short")?, Some(&[0, 27][..]));
     ///
     /// wtxn.commit()?;
     /// # Ok(()) }
     /// ```
-    pub fn iter_mut<'txn>(&self, txn: &'txn mut RwTxn) -> Result<RwIter<'txn, KC, DC>> {
+    pub fn validate_and_quarantine(
+        &self,
+        txn: &mut RwTxn,
+        quarantine: Database<types::Bytes, types::Bytes>,
+        max_invalid_entries: usize,
+    ) -> Result<ValidationReport, Error<E>>
+    where
+        KC: for<'a> BytesDecode<'a>,
+        DC: for<'a> BytesDecode<'a>,
+    {
         assert_eq_env_db_txn!(self, txn);
+        assert_eq_env_db_txn!(quarantine, txn);
+        assert!(self.dbi != quarantine.dbi, "The quarantine database must be another database");
+
+        let database =
+            self.remap_types::<types::Bytes, types::Bytes>().remap_error_type::<Infallible>();
+        let mut report = ValidationReport::default();
+        let mut invalid_entries = Vec::new();
+
+        for result in database.iter(txn).map_err(infallible_codecs)? {
+            let (key, data) = result.map_err(infallible_codecs)?;
+            report.number_of_entries += 1;
+            if let Some(entry) = verify::invalid_entry::<KC, DC>(key, data) {
+                invalid_entries.push((key.to_vec(), data.to_vec()));
+                report.record(max_invalid_entries, entry);
+            }
+        }
 
-        RwCursor::new(txn, self.dbi).map(|cursor| RwIter::new(cursor))
+        for (key, data) in invalid_entries {
+            quarantine
+                .remap_error_type::<Infallible>()
+                .put(txn, &key, &data)
+                .map_err(infallible_codecs)?;
+            database.delete_one_duplicate(txn, &key, &data).map_err(infallible_codecs)?;
+        }
+
+        Ok(report)
     }
 
-    /// Return a reversed lexicographically ordered iterator of all key-value pairs in this database.
+    /// Return a lexicographically ordered iterator of all key-value pairs in this database.
     ///
     /// You can make this iterator `Send`able between threads by
     /// using the `read-txn-no-tls` crate feature.
@@ -1103,24 +1608,25 @@ impl<KC, DC, C> Database<KC, DC, C> {
     /// db.put(&mut wtxn, &27, "i-am-twenty-seven")?;
     /// db.put(&mut wtxn, &13, "i-am-thirteen")?;
     ///
-    /// let mut iter = db.rev_iter(&wtxn)?;
-    /// assert_eq!(iter.next().transpose()?, Some((42, "i-am-forty-two")));
-    /// assert_eq!(iter.next().transpose()?, Some((27, "i-am-twenty-seven")));
+    /// let mut iter = db.iter(&wtxn)?;
     /// assert_eq!(iter.next().transpose()?, Some((13, "i-am-thirteen")));
+    /// assert_eq!(iter.next().transpose()?, Some((27, "i-am-twenty-seven")));
+    /// assert_eq!(iter.next().transpose()?, Some((42, "i-am-forty-two")));
     /// assert_eq!(iter.next().transpose()?, None);
     ///
     /// drop(iter);
     /// wtxn.commit()?;
     /// # Ok(()) }
     /// ```
-    pub fn rev_iter<'txn>(&self, txn: &'txn RoTxn) -> Result<RoRevIter<'txn, KC, DC>> {
+    pub fn iter<'txn>(
+        &self,
+        txn: &'txn RoTxn,
+    ) -> Result<RoIter<'txn, KC, DC, MoveThroughDuplicateValues, E>, Error<E>> {
         assert_eq_env_db_txn!(self, txn);
-
-        RoCursor::new(txn, self.dbi).map(|cursor| RoRevIter::new(cursor))
+        RoCursor::new(txn, self.dbi).map(|cursor| RoIter::new(cursor))
     }
 
-    /// Return a mutable reversed lexicographically ordered iterator of all key-value\
-    /// pairs in this database.
+    /// Return a mutable lexicographically ordered iterator of all key-value pairs in this database.
     ///
     /// ```
     /// # use std::fs;
@@ -1147,21 +1653,130 @@ impl<KC, DC, C> Database<KC, DC, C> {
     /// db.put(&mut wtxn, &27, "i-am-twenty-seven")?;
     /// db.put(&mut wtxn, &13, "i-am-thirteen")?;
     ///
-    /// let mut iter = db.rev_iter_mut(&mut wtxn)?;
-    /// assert_eq!(iter.next().transpose()?, Some((42, "i-am-forty-two")));
+    /// let mut iter = db.iter_mut(&mut wtxn)?;
+    /// assert_eq!(iter.next().transpose()?, Some((13, "i-am-thirteen")));
     /// let ret = unsafe { iter.del_current()? };
     /// assert!(ret);
     ///
     /// assert_eq!(iter.next().transpose()?, Some((27, "i-am-twenty-seven")));
-    /// assert_eq!(iter.next().transpose()?, Some((13, "i-am-thirteen")));
-    /// let ret = unsafe { iter.put_current(&13, "i-am-the-new-thirteen")? };
+    /// assert_eq!(iter.next().transpose()?, Some((42, "i-am-forty-two")));
+    /// let ret = unsafe { iter.put_current(&42, "i-am-the-new-forty-two")? };
     /// assert!(ret);
     ///
     /// assert_eq!(iter.next().transpose()?, None);
     ///
     /// drop(iter);
     ///
-    /// let ret = db.get(&wtxn, &42)?;
+    /// let ret = db.get(&wtxn, &13)?;
+    /// assert_eq!(ret, None);
+    ///
+    /// let ret = db.get(&wtxn, &42)?;
+    /// assert_eq!(ret, Some("i-am-the-new-forty-two"));
+    ///
+    /// wtxn.commit()?;
+    /// # Ok(()) }
+    /// ```
+    pub fn iter_mut<'txn>(
+        &self,
+        txn: &'txn mut RwTxn,
+    ) -> Result<RwIter<'txn, KC, DC, MoveThroughDuplicateValues, E>, Error<E>> {
+        assert_eq_env_db_txn!(self, txn);
+
+        RwCursor::new(txn, self.dbi).map(|cursor| RwIter::new(cursor))
+    }
+
+    /// Return a reversed lexicographically ordered iterator of all key-value pairs in this database.
+    ///
+    /// You can make this iterator `Send`able between threads by
+    /// using the `read-txn-no-tls` crate feature.
+    ///
+    /// ```
+    /// # use std::fs;
+    /// # use std::path::Path;
+    /// # use heed::EnvOpenOptions;
+    /// use heed::Database;
+    /// use heed::types::*;
+    /// use heed::byteorder::BigEndian;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let dir = tempfile::tempdir()?;
+    /// # let env = unsafe { EnvOpenOptions::new()
+    /// #     .map_size(10 * 1024 * 1024) // 10MB
+    /// #     .max_dbs(3000)
+    /// #     .open(dir.path())?
+    /// # };
+    /// type BEI32 = I32<BigEndian>;
+    ///
+    /// let mut wtxn = env.write_txn()?;
+    /// let db: Database<BEI32, Str> = env.create_database(&mut wtxn, Some("iter-i32"))?;
+    ///
+    /// # db.clear(&mut wtxn)?;
+    /// db.put(&mut wtxn, &42, "i-am-forty-two")?;
+    /// db.put(&mut wtxn, &27, "i-am-twenty-seven")?;
+    /// db.put(&mut wtxn, &13, "i-am-thirteen")?;
+    ///
+    /// let mut iter = db.rev_iter(&wtxn)?;
+    /// assert_eq!(iter.next().transpose()?, Some((42, "i-am-forty-two")));
+    /// assert_eq!(iter.next().transpose()?, Some((27, "i-am-twenty-seven")));
+    /// assert_eq!(iter.next().transpose()?, Some((13, "i-am-thirteen")));
+    /// assert_eq!(iter.next().transpose()?, None);
+    ///
+    /// drop(iter);
+    /// wtxn.commit()?;
+    /// # Ok(()) }
+    /// ```
+    pub fn rev_iter<'txn>(
+        &self,
+        txn: &'txn RoTxn,
+    ) -> Result<RoRevIter<'txn, KC, DC, MoveThroughDuplicateValues, E>, Error<E>> {
+        assert_eq_env_db_txn!(self, txn);
+
+        RoCursor::new(txn, self.dbi).map(|cursor| RoRevIter::new(cursor))
+    }
+
+    /// Return a mutable reversed lexicographically ordered iterator of all key-value\
+    /// pairs in this database.
+    ///
+    /// ```
+    /// # use std::fs;
+    /// # use std::path::Path;
+    /// # use heed::EnvOpenOptions;
+    /// use heed::Database;
+    /// use heed::types::*;
+    /// use heed::byteorder::BigEndian;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let dir = tempfile::tempdir()?;
+    /// # let env = unsafe { EnvOpenOptions::new()
+    /// #     .map_size(10 * 1024 * 1024) // 10MB
+    /// #     .max_dbs(3000)
+    /// #     .open(dir.path())?
+    /// # };
+    /// type BEI32 = I32<BigEndian>;
+    ///
+    /// let mut wtxn = env.write_txn()?;
+    /// let db: Database<BEI32, Str> = env.create_database(&mut wtxn, Some("iter-i32"))?;
+    ///
+    /// # db.clear(&mut wtxn)?;
+    /// db.put(&mut wtxn, &42, "i-am-forty-two")?;
+    /// db.put(&mut wtxn, &27, "i-am-twenty-seven")?;
+    /// db.put(&mut wtxn, &13, "i-am-thirteen")?;
+    ///
+    /// let mut iter = db.rev_iter_mut(&mut wtxn)?;
+    /// assert_eq!(iter.next().transpose()?, Some((42, "i-am-forty-two")));
+    /// let ret = unsafe { iter.del_current()? };
+    /// assert!(ret);
+    ///
+    /// assert_eq!(iter.next().transpose()?, Some((27, "i-am-twenty-seven")));
+    /// assert_eq!(iter.next().transpose()?, Some((13, "i-am-thirteen")));
+    /// let ret = unsafe { iter.put_current(&13, "i-am-the-new-thirteen")? };
+    /// assert!(ret);
+    ///
+    /// assert_eq!(iter.next().transpose()?, None);
+    ///
+    /// drop(iter);
+    ///
+    /// let ret = db.get(&wtxn, &42)?;
     /// assert_eq!(ret, None);
     ///
     /// let ret = db.get(&wtxn, &13)?;
@@ -1170,7 +1785,10 @@ impl<KC, DC, C> Database<KC, DC, C> {
     /// wtxn.commit()?;
     /// # Ok(()) }
     /// ```
-    pub fn rev_iter_mut<'txn>(&self, txn: &'txn mut RwTxn) -> Result<RwRevIter<'txn, KC, DC>> {
+    pub fn rev_iter_mut<'txn>(
+        &self,
+        txn: &'txn mut RwTxn,
+    ) -> Result<RwRevIter<'txn, KC, DC, MoveThroughDuplicateValues, E>, Error<E>> {
         assert_eq_env_db_txn!(self, txn);
 
         RwCursor::new(txn, self.dbi).map(|cursor| RwRevIter::new(cursor))
@@ -1223,36 +1841,15 @@ impl<KC, DC, C> Database<KC, DC, C> {
         &self,
         txn: &'txn RoTxn,
         range: &'a R,
-    ) -> Result<RoRange<'txn, KC, DC>>
+    ) -> Result<RoRange<'txn, KC, DC, MoveThroughDuplicateValues, E>, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         R: RangeBounds<KC::EItem>,
     {
         assert_eq_env_db_txn!(self, txn);
 
-        let start_bound = match range.start_bound() {
-            Bound::Included(bound) => {
-                let bytes = KC::bytes_encode(bound).map_err(Error::Encoding)?;
-                Bound::Included(bytes.into_owned())
-            }
-            Bound::Excluded(bound) => {
-                let bytes = KC::bytes_encode(bound).map_err(Error::Encoding)?;
-                Bound::Excluded(bytes.into_owned())
-            }
-            Bound::Unbounded => Bound::Unbounded,
-        };
-
-        let end_bound = match range.end_bound() {
-            Bound::Included(bound) => {
-                let bytes = KC::bytes_encode(bound).map_err(Error::Encoding)?;
-                Bound::Included(bytes.into_owned())
-            }
-            Bound::Excluded(bound) => {
-                let bytes = KC::bytes_encode(bound).map_err(Error::Encoding)?;
-                Bound::Excluded(bytes.into_owned())
-            }
-            Bound::Unbounded => Bound::Unbounded,
-        };
+        let (start_bound, end_bound) = self.encode_range(txn, range)?;
 
         RoCursor::new(txn, self.dbi).map(|cursor| RoRange::new(cursor, start_bound, end_bound))
     }
@@ -1315,36 +1912,15 @@ impl<KC, DC, C> Database<KC, DC, C> {
         &self,
         txn: &'txn mut RwTxn,
         range: &'a R,
-    ) -> Result<RwRange<'txn, KC, DC>>
+    ) -> Result<RwRange<'txn, KC, DC, MoveThroughDuplicateValues, E>, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         R: RangeBounds<KC::EItem>,
     {
         assert_eq_env_db_txn!(self, txn);
 
-        let start_bound = match range.start_bound() {
-            Bound::Included(bound) => {
-                let bytes = KC::bytes_encode(bound).map_err(Error::Encoding)?;
-                Bound::Included(bytes.into_owned())
-            }
-            Bound::Excluded(bound) => {
-                let bytes = KC::bytes_encode(bound).map_err(Error::Encoding)?;
-                Bound::Excluded(bytes.into_owned())
-            }
-            Bound::Unbounded => Bound::Unbounded,
-        };
-
-        let end_bound = match range.end_bound() {
-            Bound::Included(bound) => {
-                let bytes = KC::bytes_encode(bound).map_err(Error::Encoding)?;
-                Bound::Included(bytes.into_owned())
-            }
-            Bound::Excluded(bound) => {
-                let bytes = KC::bytes_encode(bound).map_err(Error::Encoding)?;
-                Bound::Excluded(bytes.into_owned())
-            }
-            Bound::Unbounded => Bound::Unbounded,
-        };
+        let (start_bound, end_bound) = self.encode_range(txn, range)?;
 
         RwCursor::new(txn, self.dbi).map(|cursor| RwRange::new(cursor, start_bound, end_bound))
     }
@@ -1397,36 +1973,15 @@ impl<KC, DC, C> Database<KC, DC, C> {
         &self,
         txn: &'txn RoTxn,
         range: &'a R,
-    ) -> Result<RoRevRange<'txn, KC, DC>>
+    ) -> Result<RoRevRange<'txn, KC, DC, MoveThroughDuplicateValues, E>, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         R: RangeBounds<KC::EItem>,
     {
         assert_eq_env_db_txn!(self, txn);
 
-        let start_bound = match range.start_bound() {
-            Bound::Included(bound) => {
-                let bytes = KC::bytes_encode(bound).map_err(Error::Encoding)?;
-                Bound::Included(bytes.into_owned())
-            }
-            Bound::Excluded(bound) => {
-                let bytes = KC::bytes_encode(bound).map_err(Error::Encoding)?;
-                Bound::Excluded(bytes.into_owned())
-            }
-            Bound::Unbounded => Bound::Unbounded,
-        };
-
-        let end_bound = match range.end_bound() {
-            Bound::Included(bound) => {
-                let bytes = KC::bytes_encode(bound).map_err(Error::Encoding)?;
-                Bound::Included(bytes.into_owned())
-            }
-            Bound::Excluded(bound) => {
-                let bytes = KC::bytes_encode(bound).map_err(Error::Encoding)?;
-                Bound::Excluded(bytes.into_owned())
-            }
-            Bound::Unbounded => Bound::Unbounded,
-        };
+        let (start_bound, end_bound) = self.encode_range(txn, range)?;
 
         RoCursor::new(txn, self.dbi).map(|cursor| RoRevRange::new(cursor, start_bound, end_bound))
     }
@@ -1489,36 +2044,15 @@ impl<KC, DC, C> Database<KC, DC, C> {
         &self,
         txn: &'txn mut RwTxn,
         range: &'a R,
-    ) -> Result<RwRevRange<'txn, KC, DC>>
+    ) -> Result<RwRevRange<'txn, KC, DC, MoveThroughDuplicateValues, E>, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         R: RangeBounds<KC::EItem>,
     {
         assert_eq_env_db_txn!(self, txn);
 
-        let start_bound = match range.start_bound() {
-            Bound::Included(bound) => {
-                let bytes = KC::bytes_encode(bound).map_err(Error::Encoding)?;
-                Bound::Included(bytes.into_owned())
-            }
-            Bound::Excluded(bound) => {
-                let bytes = KC::bytes_encode(bound).map_err(Error::Encoding)?;
-                Bound::Excluded(bytes.into_owned())
-            }
-            Bound::Unbounded => Bound::Unbounded,
-        };
-
-        let end_bound = match range.end_bound() {
-            Bound::Included(bound) => {
-                let bytes = KC::bytes_encode(bound).map_err(Error::Encoding)?;
-                Bound::Included(bytes.into_owned())
-            }
-            Bound::Excluded(bound) => {
-                let bytes = KC::bytes_encode(bound).map_err(Error::Encoding)?;
-                Bound::Excluded(bytes.into_owned())
-            }
-            Bound::Unbounded => Bound::Unbounded,
-        };
+        let (start_bound, end_bound) = self.encode_range(txn, range)?;
 
         RwCursor::new(txn, self.dbi).map(|cursor| RwRevRange::new(cursor, start_bound, end_bound))
     }
@@ -1572,15 +2106,18 @@ impl<KC, DC, C> Database<KC, DC, C> {
         &self,
         txn: &'txn RoTxn,
         prefix: &'a KC::EItem,
-    ) -> Result<RoPrefix<'txn, KC, DC, C>>
+    ) -> Result<RoPrefix<'txn, KC, DC, C, MoveThroughDuplicateValues, E>, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         C: LexicographicComparator,
     {
         assert_eq_env_db_txn!(self, txn);
 
-        let prefix_bytes = KC::bytes_encode(prefix).map_err(Error::Encoding)?;
-        let prefix_bytes = prefix_bytes.into_owned();
+        let mut prefix_bytes = txn.start_bound_buffer();
+        prefix_bytes
+            .encode_to_buffer::<KC>(prefix)
+            .map_err(|e| self.with_context(txn, Operation::Iteration, None, Error::encoding(e)))?;
         RoCursor::new(txn, self.dbi).map(|cursor| RoPrefix::new(cursor, prefix_bytes))
     }
 
@@ -1643,15 +2180,18 @@ impl<KC, DC, C> Database<KC, DC, C> {
         &self,
         txn: &'txn mut RwTxn,
         prefix: &'a KC::EItem,
-    ) -> Result<RwPrefix<'txn, KC, DC, C>>
+    ) -> Result<RwPrefix<'txn, KC, DC, C, MoveThroughDuplicateValues, E>, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         C: LexicographicComparator,
     {
         assert_eq_env_db_txn!(self, txn);
 
-        let prefix_bytes = KC::bytes_encode(prefix).map_err(Error::Encoding)?;
-        let prefix_bytes = prefix_bytes.into_owned();
+        let mut prefix_bytes = txn.start_bound_buffer();
+        prefix_bytes
+            .encode_to_buffer::<KC>(prefix)
+            .map_err(|e| self.with_context(txn, Operation::Iteration, None, Error::encoding(e)))?;
         RwCursor::new(txn, self.dbi).map(|cursor| RwPrefix::new(cursor, prefix_bytes))
     }
 
@@ -1704,15 +2244,18 @@ impl<KC, DC, C> Database<KC, DC, C> {
         &self,
         txn: &'txn RoTxn,
         prefix: &'a KC::EItem,
-    ) -> Result<RoRevPrefix<'txn, KC, DC, C>>
+    ) -> Result<RoRevPrefix<'txn, KC, DC, C, MoveThroughDuplicateValues, E>, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         C: LexicographicComparator,
     {
         assert_eq_env_db_txn!(self, txn);
 
-        let prefix_bytes = KC::bytes_encode(prefix).map_err(Error::Encoding)?;
-        let prefix_bytes = prefix_bytes.into_owned();
+        let mut prefix_bytes = txn.start_bound_buffer();
+        prefix_bytes
+            .encode_to_buffer::<KC>(prefix)
+            .map_err(|e| self.with_context(txn, Operation::Iteration, None, Error::encoding(e)))?;
         RoCursor::new(txn, self.dbi).map(|cursor| RoRevPrefix::new(cursor, prefix_bytes))
     }
 
@@ -1775,21 +2318,32 @@ impl<KC, DC, C> Database<KC, DC, C> {
         &self,
         txn: &'txn mut RwTxn,
         prefix: &'a KC::EItem,
-    ) -> Result<RwRevPrefix<'txn, KC, DC, C>>
+    ) -> Result<RwRevPrefix<'txn, KC, DC, C, MoveThroughDuplicateValues, E>, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         C: LexicographicComparator,
     {
         assert_eq_env_db_txn!(self, txn);
 
-        let prefix_bytes = KC::bytes_encode(prefix).map_err(Error::Encoding)?;
-        let prefix_bytes = prefix_bytes.into_owned();
+        let mut prefix_bytes = txn.start_bound_buffer();
+        prefix_bytes
+            .encode_to_buffer::<KC>(prefix)
+            .map_err(|e| self.with_context(txn, Operation::Iteration, None, Error::encoding(e)))?;
         RwCursor::new(txn, self.dbi).map(|cursor| RwRevPrefix::new(cursor, prefix_bytes))
     }
 
     /// Insert a key-value pair in this database, replacing any previous value. The entry is
     /// written with no specific flag.
     ///
+    /// When the data codec reports its [encoded size](BytesEncode::encoded_size), the value
+    /// is serialized directly into the space reserved by LMDB instead of an intermediate
+    /// buffer. This is not possible with [`DatabaseFlags::DUP_SORT`] databases. The other
+    /// values are encoded into a buffer reused by the transaction before being written.
+    /// An encoding error leaves the previous value untouched, like a codec that writes a
+    /// different number of bytes than its encoded size, which returns an
+    /// [`io::ErrorKind::InvalidData`] error.
+    ///
     /// ```
     /// # use std::fs;
     /// # use std::path::Path;
@@ -1822,27 +2376,115 @@ impl<KC, DC, C> Database<KC, DC, C> {
     /// wtxn.commit()?;
     /// # Ok(()) }
     /// ```
-    pub fn put<'a>(&self, txn: &mut RwTxn, key: &'a KC::EItem, data: &'a DC::EItem) -> Result<()>
+    pub fn put<'a>(
+        &self,
+        txn: &mut RwTxn,
+        key: &'a KC::EItem,
+        data: &'a DC::EItem,
+    ) -> Result<(), Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         DC: BytesEncode<'a>,
+        <DC as BytesEncode<'a>>::Error: Into<E>,
     {
         assert_eq_env_db_txn!(self, txn);
 
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        let data_bytes: Cow<[u8]> = DC::bytes_encode(data).map_err(Error::Encoding)?;
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer
+            .encode::<KC>(key)
+            .map_err(|e| self.with_context(txn, Operation::Put, None, Error::encoding(e)))?;
+
+        if !self.dup_sort {
+            if let Some(data_size) = DC::encoded_size(data) {
+                return self.put_encoded_in_place(txn, key_bytes, data, data_size);
+            }
+        }
+
+        let mut data_buffer = txn.data_buffer();
+        let data_bytes = data_buffer.encode::<DC>(data).map_err(|e| {
+            self.with_context(txn, Operation::Put, Some(key_bytes), Error::encoding(e))
+        })?;
 
-        let mut key_val = unsafe { crate::into_val(&key_bytes) };
-        let mut data_val = unsafe { crate::into_val(&data_bytes) };
+        let mut key_val = unsafe { crate::into_val(key_bytes) };
+        let mut data_val = unsafe { crate::into_val(data_bytes) };
         let flags = 0;
 
         unsafe {
-            mdb_result(ffi::mdb_put(txn.txn.txn, self.dbi, &mut key_val, &mut data_val, flags))?
+            mdb_result(ffi::mdb_put(txn.txn.txn, self.dbi, &mut key_val, &mut data_val, flags))
+                .map_err(|e| self.with_context(txn, Operation::Put, Some(key_bytes), e.into()))?
         }
 
         Ok(())
     }
 
+    /// Reserves `data_size` bytes for the entry and encodes the data directly into them.
+    ///
+    /// The reserved space replaces the previous value before the data is encoded, it is
+    /// copied into the data buffer of the transaction beforehand and written back if the
+    /// codec fails or writes a different number of bytes than its encoded size.
+    fn put_encoded_in_place<'a>(
+        &self,
+        txn: &RwTxn,
+        key_bytes: &[u8],
+        data: &'a DC::EItem,
+        data_size: usize,
+    ) -> Result<(), Error<E>>
+    where
+        DC: BytesEncode<'a>,
+        <DC as BytesEncode<'a>>::Error: Into<E>,
+    {
+        let with_context = |e| self.with_context(txn, Operation::Put, Some(key_bytes), e);
+        let mut key_val = unsafe { crate::into_val(key_bytes) };
+        let mut previous_buffer = txn.data_buffer();
+        let mut previous_val = mem::MaybeUninit::uninit();
+
+        let previous = unsafe {
+            let result =
+                ffi::mdb_get(txn.txn.txn, self.dbi, &mut key_val, previous_val.as_mut_ptr());
+            match mdb_result(result) {
+                Ok(()) => Some(previous_buffer.copy(ffi::from_val(previous_val.assume_init()))),
+                Err(e) if e.not_found() => None,
+                Err(e) => return Err(with_context(e.into())),
+            }
+        };
+
+        let mut reserved = ffi::reserve_size_val(data_size);
+        let flags = ffi::MDB_RESERVE;
+
+        unsafe {
+            mdb_result(ffi::mdb_put(txn.txn.txn, self.dbi, &mut key_val, &mut reserved, flags))
+                .map_err(|e| with_context(e.into()))?
+        }
+
+        let mut reserved = unsafe { ReservedSpace::from_val(reserved) };
+        let error = match DC::bytes_encode_into_writer(data, &mut reserved) {
+            Ok(()) if reserved.remaining() == 0 => return Ok(()),
+            Ok(()) => Error::Io(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "the {} codec wrote a different number of bytes than its encoded size",
+                    any::type_name::<DC>(),
+                ),
+            )),
+            Err(e) => Error::encoding(e),
+        };
+
+        // The entry is put back as it was before the space was reserved.
+        unsafe {
+            let result = match previous {
+                Some(previous) => {
+                    let mut previous_val = crate::into_val(previous);
+                    ffi::mdb_put(txn.txn.txn, self.dbi, &mut key_val, &mut previous_val, 0)
+                }
+                None => ffi::mdb_del(txn.txn.txn, self.dbi, &mut key_val, ptr::null_mut()),
+            };
+            mdb_result(result).map_err(|e| with_context(e.into()))?;
+        }
+
+        Err(with_context(error))
+    }
+
     /// Insert a key-value pair where the value can directly be written to disk, replacing any
     /// previous value.
     ///
@@ -1885,20 +2527,25 @@ impl<KC, DC, C> Database<KC, DC, C> {
         key: &'a KC::EItem,
         data_size: usize,
         write_func: F,
-    ) -> Result<()>
+    ) -> Result<(), Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         F: FnOnce(&mut ReservedSpace) -> io::Result<()>,
     {
         assert_eq_env_db_txn!(self, txn);
 
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        let mut key_val = unsafe { crate::into_val(&key_bytes) };
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer
+            .encode::<KC>(key)
+            .map_err(|e| self.with_context(txn, Operation::Put, None, Error::encoding(e)))?;
+        let mut key_val = unsafe { crate::into_val(key_bytes) };
         let mut reserved = ffi::reserve_size_val(data_size);
         let flags = ffi::MDB_RESERVE;
 
         unsafe {
-            mdb_result(ffi::mdb_put(txn.txn.txn, self.dbi, &mut key_val, &mut reserved, flags))?
+            mdb_result(ffi::mdb_put(txn.txn.txn, self.dbi, &mut key_val, &mut reserved, flags))
+                .map_err(|e| self.with_context(txn, Operation::Put, Some(key_bytes), e.into()))?
         }
 
         let mut reserved = unsafe { ReservedSpace::from_val(reserved) };
@@ -1975,22 +2622,31 @@ impl<KC, DC, C> Database<KC, DC, C> {
         flags: PutFlags,
         key: &'a KC::EItem,
         data: &'a DC::EItem,
-    ) -> Result<()>
+    ) -> Result<(), Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         DC: BytesEncode<'a>,
+        <DC as BytesEncode<'a>>::Error: Into<E>,
     {
         assert_eq_env_db_txn!(self, txn);
 
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        let data_bytes: Cow<[u8]> = DC::bytes_encode(data).map_err(Error::Encoding)?;
-
-        let mut key_val = unsafe { crate::into_val(&key_bytes) };
-        let mut data_val = unsafe { crate::into_val(&data_bytes) };
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer
+            .encode::<KC>(key)
+            .map_err(|e| self.with_context(txn, Operation::Put, None, Error::encoding(e)))?;
+        let mut data_buffer = txn.data_buffer();
+        let data_bytes = data_buffer.encode::<DC>(data).map_err(|e| {
+            self.with_context(txn, Operation::Put, Some(key_bytes), Error::encoding(e))
+        })?;
+
+        let mut key_val = unsafe { crate::into_val(key_bytes) };
+        let mut data_val = unsafe { crate::into_val(data_bytes) };
         let flags = flags.bits();
 
         unsafe {
-            mdb_result(ffi::mdb_put(txn.txn.txn, self.dbi, &mut key_val, &mut data_val, flags))?
+            mdb_result(ffi::mdb_put(txn.txn.txn, self.dbi, &mut key_val, &mut data_val, flags))
+                .map_err(|e| self.with_context(txn, Operation::Put, Some(key_bytes), e.into()))?
         }
 
         Ok(())
@@ -2034,10 +2690,13 @@ impl<KC, DC, C> Database<KC, DC, C> {
         txn: &mut RwTxn,
         key: &'a KC::EItem,
         data: &'a DC::EItem,
-    ) -> Result<Option<DC::DItem>>
+    ) -> Result<Option<DC::DItem>, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         DC: BytesEncode<'a> + BytesDecode<'a>,
+        <DC as BytesEncode<'a>>::Error: Into<E>,
+        <DC as BytesDecode<'a>>::Error: Into<E>,
     {
         self.get_or_put_with_flags(txn, PutFlags::empty(), key, data)
     }
@@ -2082,18 +2741,27 @@ impl<KC, DC, C> Database<KC, DC, C> {
         flags: PutFlags,
         key: &'a KC::EItem,
         data: &'a DC::EItem,
-    ) -> Result<Option<DC::DItem>>
+    ) -> Result<Option<DC::DItem>, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         DC: BytesEncode<'a> + BytesDecode<'a>,
+        <DC as BytesEncode<'a>>::Error: Into<E>,
+        <DC as BytesDecode<'a>>::Error: Into<E>,
     {
         assert_eq_env_db_txn!(self, txn);
 
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        let data_bytes: Cow<[u8]> = DC::bytes_encode(data).map_err(Error::Encoding)?;
-
-        let mut key_val = unsafe { crate::into_val(&key_bytes) };
-        let mut data_val = unsafe { crate::into_val(&data_bytes) };
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer
+            .encode::<KC>(key)
+            .map_err(|e| self.with_context(txn, Operation::Put, None, Error::encoding(e)))?;
+        let mut data_buffer = txn.data_buffer();
+        let data_bytes = data_buffer.encode::<DC>(data).map_err(|e| {
+            self.with_context(txn, Operation::Put, Some(key_bytes), Error::encoding(e))
+        })?;
+
+        let mut key_val = unsafe { crate::into_val(key_bytes) };
+        let mut data_val = unsafe { crate::into_val(data_bytes) };
         let flags = (flags | PutFlags::NO_OVERWRITE).bits();
 
         let result = unsafe {
@@ -2106,10 +2774,19 @@ impl<KC, DC, C> Database<KC, DC, C> {
             // the key already exists: the previous value is stored in the data parameter
             Err(MdbError::KeyExist) => {
                 let bytes = unsafe { crate::from_val(data_val) };
-                let data = DC::bytes_decode(bytes).map_err(Error::Decoding)?;
+                let data = DC::bytes_decode(bytes).map_err(|e| {
+                    self.with_context(
+                        txn,
+                        Operation::Put,
+                        Some(key_bytes),
+                        Error::Decoding(e.into()),
+                    )
+                })?;
                 Ok(Some(data))
             }
-            Err(error) => Err(error.into()),
+            Err(error) => {
+                Err(self.with_context(txn, Operation::Put, Some(key_bytes), error.into()))
+            }
         }
     }
 
@@ -2167,11 +2844,13 @@ impl<KC, DC, C> Database<KC, DC, C> {
         key: &'a KC::EItem,
         data_size: usize,
         write_func: F,
-    ) -> Result<Option<DC::DItem>>
+    ) -> Result<Option<DC::DItem>, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         F: FnOnce(&mut ReservedSpace) -> io::Result<()>,
         DC: BytesDecode<'a>,
+        DC::Error: Into<E>,
     {
         self.get_or_put_reserved_with_flags(txn, PutFlags::empty(), key, data_size, write_func)
     }
@@ -2232,17 +2911,22 @@ impl<KC, DC, C> Database<KC, DC, C> {
         key: &'a KC::EItem,
         data_size: usize,
         write_func: F,
-    ) -> Result<Option<DC::DItem>>
+    ) -> Result<Option<DC::DItem>, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         F: FnOnce(&mut ReservedSpace) -> io::Result<()>,
         DC: BytesDecode<'a>,
+        DC::Error: Into<E>,
     {
         assert_eq_env_db_txn!(self, txn);
 
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer
+            .encode::<KC>(key)
+            .map_err(|e| self.with_context(txn, Operation::Put, None, Error::encoding(e)))?;
 
-        let mut key_val = unsafe { crate::into_val(&key_bytes) };
+        let mut key_val = unsafe { crate::into_val(key_bytes) };
         let mut reserved = ffi::reserve_size_val(data_size);
         let flags = (flags | PutFlags::NO_OVERWRITE).bits() | lmdb_master_sys::MDB_RESERVE;
 
@@ -2264,10 +2948,19 @@ impl<KC, DC, C> Database<KC, DC, C> {
             // the key already exists: the previous value is stored in the data parameter
             Err(MdbError::KeyExist) => {
                 let bytes = unsafe { crate::from_val(reserved) };
-                let data = DC::bytes_decode(bytes).map_err(Error::Decoding)?;
+                let data = DC::bytes_decode(bytes).map_err(|e| {
+                    self.with_context(
+                        txn,
+                        Operation::Put,
+                        Some(key_bytes),
+                        Error::Decoding(e.into()),
+                    )
+                })?;
                 Ok(Some(data))
             }
-            Err(error) => Err(error.into()),
+            Err(error) => {
+                Err(self.with_context(txn, Operation::Put, Some(key_bytes), error.into()))
+            }
         }
     }
 
@@ -2314,14 +3007,18 @@ impl<KC, DC, C> Database<KC, DC, C> {
     /// wtxn.commit()?;
     /// # Ok(()) }
     /// ```
-    pub fn delete<'a>(&self, txn: &mut RwTxn, key: &'a KC::EItem) -> Result<bool>
+    pub fn delete<'a>(&self, txn: &mut RwTxn, key: &'a KC::EItem) -> Result<bool, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
     {
         assert_eq_env_db_txn!(self, txn);
 
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        let mut key_val = unsafe { crate::into_val(&key_bytes) };
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer
+            .encode::<KC>(key)
+            .map_err(|e| self.with_context(txn, Operation::Delete, None, Error::encoding(e)))?;
+        let mut key_val = unsafe { crate::into_val(key_bytes) };
 
         let result = unsafe {
             mdb_result(ffi::mdb_del(txn.txn.txn, self.dbi, &mut key_val, ptr::null_mut()))
@@ -2330,7 +3027,7 @@ impl<KC, DC, C> Database<KC, DC, C> {
         match result {
             Ok(()) => Ok(true),
             Err(e) if e.not_found() => Ok(false),
-            Err(e) => Err(e.into()),
+            Err(e) => Err(self.with_context(txn, Operation::Delete, Some(key_bytes), e.into())),
         }
     }
 
@@ -2398,17 +3095,25 @@ impl<KC, DC, C> Database<KC, DC, C> {
         txn: &mut RwTxn,
         key: &'a KC::EItem,
         data: &'a DC::EItem,
-    ) -> Result<bool>
+    ) -> Result<bool, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         DC: BytesEncode<'a>,
+        <DC as BytesEncode<'a>>::Error: Into<E>,
     {
         assert_eq_env_db_txn!(self, txn);
 
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        let data_bytes: Cow<[u8]> = DC::bytes_encode(data).map_err(Error::Encoding)?;
-        let mut key_val = unsafe { crate::into_val(&key_bytes) };
-        let mut data_val = unsafe { crate::into_val(&data_bytes) };
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer
+            .encode::<KC>(key)
+            .map_err(|e| self.with_context(txn, Operation::Delete, None, Error::encoding(e)))?;
+        let mut data_buffer = txn.data_buffer();
+        let data_bytes = data_buffer.encode::<DC>(data).map_err(|e| {
+            self.with_context(txn, Operation::Delete, Some(key_bytes), Error::encoding(e))
+        })?;
+        let mut key_val = unsafe { crate::into_val(key_bytes) };
+        let mut data_val = unsafe { crate::into_val(data_bytes) };
 
         let result =
             unsafe { mdb_result(ffi::mdb_del(txn.txn.txn, self.dbi, &mut key_val, &mut data_val)) };
@@ -2416,7 +3121,7 @@ impl<KC, DC, C> Database<KC, DC, C> {
         match result {
             Ok(()) => Ok(true),
             Err(e) if e.not_found() => Ok(false),
-            Err(e) => Err(e.into()),
+            Err(e) => Err(self.with_context(txn, Operation::Delete, Some(key_bytes), e.into())),
         }
     }
 
@@ -2469,21 +3174,34 @@ impl<KC, DC, C> Database<KC, DC, C> {
     /// wtxn.commit()?;
     /// # Ok(()) }
     /// ```
-    pub fn delete_range<'a, 'txn, R>(&self, txn: &'txn mut RwTxn, range: &'a R) -> Result<usize>
+    pub fn delete_range<'a, 'txn, R>(
+        &self,
+        txn: &'txn mut RwTxn,
+        range: &'a R,
+    ) -> Result<usize, Error<E>>
     where
         KC: BytesEncode<'a> + BytesDecode<'txn>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         R: RangeBounds<KC::EItem>,
     {
         assert_eq_env_db_txn!(self, txn);
 
+        let (start_bound, end_bound) = self.encode_range(txn, range)?;
+
         let mut count = 0;
-        let mut iter = self.remap_data_type::<DecodeIgnore>().range_mut(txn, range)?;
+        let cursor = RwCursor::new(txn, self.dbi)?;
+        let mut iter =
+            RwRange::<DecodeIgnore, DecodeIgnore, MoveThroughDuplicateValues, Infallible>::new(
+                cursor,
+                start_bound,
+                end_bound,
+            );
 
         while iter.next().is_some() {
             // safety: We do not keep any reference from the database while using `del_current`.
             //         The user can't keep any reference inside of the database as we ask for a
             //         mutable reference to the `txn`.
-            unsafe { iter.del_current()? };
+            unsafe { iter.del_current().map_err(infallible_codecs)? };
             count += 1;
         }
 
@@ -2531,7 +3249,7 @@ impl<KC, DC, C> Database<KC, DC, C> {
     /// wtxn.commit()?;
     /// # Ok(()) }
     /// ```
-    pub fn clear(&self, txn: &mut RwTxn) -> Result<()> {
+    pub fn clear(&self, txn: &mut RwTxn) -> Result<(), Error<E>> {
         assert_eq_env_db_txn!(self, txn);
 
         unsafe { mdb_result(ffi::mdb_drop(txn.txn.txn, self.dbi, 0)).map_err(Into::into) }
@@ -2578,44 +3296,163 @@ impl<KC, DC, C> Database<KC, DC, C> {
     /// wtxn.commit()?;
     /// # Ok(()) }
     /// ```
-    pub fn remap_types<KC2, DC2>(&self) -> Database<KC2, DC2, C> {
-        Database::new(self.env_ident, self.dbi)
+    pub fn remap_types<KC2, DC2>(&self) -> Database<KC2, DC2, C, CDUP, E> {
+        Database::new(self.env_ident, self.dbi, self.dup_sort)
     }
 
     /// Change the key codec type of this database, specifying the new codec.
-    pub fn remap_key_type<KC2>(&self) -> Database<KC2, DC, C> {
+    pub fn remap_key_type<KC2>(&self) -> Database<KC2, DC, C, CDUP, E> {
         self.remap_types::<KC2, DC>()
     }
 
     /// Change the data codec type of this database, specifying the new codec.
-    pub fn remap_data_type<DC2>(&self) -> Database<KC, DC2, C> {
+    pub fn remap_data_type<DC2>(&self) -> Database<KC, DC2, C, CDUP, E> {
         self.remap_types::<KC, DC2>()
     }
 
     /// Wrap the data bytes into a lazy decoder.
-    pub fn lazily_decode_data(&self) -> Database<KC, LazyDecode<DC>, C> {
+    pub fn lazily_decode_data(&self) -> Database<KC, LazyDecode<DC>, C, CDUP, E> {
         self.remap_types::<KC, LazyDecode<DC>>()
     }
+
+    /// Change the type of the encoding and decoding errors returned by this database.
+    ///
+    /// The errors of the key and data codecs must be convertible into this type.
+    /// By default, they are boxed into a [`BoxedError`].
+    ///
+    /// ```
+    /// # use heed::EnvOpenOptions;
+    /// use std::convert::Infallible;
+    /// use std::str::Utf8Error;
+    ///
+    /// use heed::types::*;
+    /// use heed::Error;
+    ///
+    /// /// The errors of the `Str` codec: it cannot fail to encode but can fail to decode.
+    /// #[derive(Debug)]
+    /// enum StrError {
+    ///     Utf8(Utf8Error),
+    /// }
+    ///
+    /// impl From<Infallible> for StrError {
+    ///     fn from(never: Infallible) -> StrError {
+    ///         match never {}
+    ///     }
+    /// }
+    ///
+    /// impl From<Utf8Error> for StrError {
+    ///     fn from(error: Utf8Error) -> StrError {
+    ///         StrError::Utf8(error)
+    ///     }
+    /// }
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let dir = tempfile::tempdir()?;
+    /// # let env = unsafe { EnvOpenOptions::new()
+    /// #     .map_size(10 * 1024 * 1024) // 10MB
+    /// #     .max_dbs(3000)
+    /// #     .open(dir.path())?
+    /// # };
+    /// let mut wtxn = env.write_txn()?;
+    /// let db = env.create_database::<Str, Bytes>(&mut wtxn, Some("typed-errors"))?;
+    ///
+    /// # db.clear(&mut wtxn)?;
+    /// db.put(&mut wtxn, "invalid-utf8", &[0xff, 0xfe])?;
+    ///
+    /// let db = db.remap_data_type::<Str>().remap_error_type::<StrError>();
+    /// match db.get(&wtxn, "invalid-utf8") {
+    ///     Err(Error::Decoding(StrError::Utf8(error))) => assert_eq!(error.valid_up_to(), 0),
+    ///     otherwise => panic!("expected a decoding error, found {:?}", otherwise),
+    /// }
+    ///
+    /// wtxn.commit()?;
+    /// # Ok(()) }
+    /// ```
+    pub fn remap_error_type<E2>(&self) -> Database<KC, DC, C, CDUP, E2> {
+        Database::new(self.env_ident, self.dbi, self.dup_sort)
+    }
+
+    /// Encodes the bounds of the range with the key codec of this database,
+    /// into the buffers of the transaction.
+    fn encode_range<'a, 'txn, R>(
+        &self,
+        txn: &'txn RoTxn,
+        range: &'a R,
+    ) -> Result<EncodedRange<'txn>, Error<E>>
+    where
+        KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
+        R: RangeBounds<KC::EItem>,
+    {
+        let encode = |mut buffer: ScratchBuffer<'txn>, bound: &'a KC::EItem| match buffer
+            .encode_to_buffer::<KC>(bound)
+        {
+            Ok(()) => Ok(buffer),
+            Err(e) => Err(self.with_context(txn, Operation::Iteration, None, Error::encoding(e))),
+        };
+
+        let start_bound = match range.start_bound() {
+            Bound::Included(bound) => Bound::Included(encode(txn.start_bound_buffer(), bound)?),
+            Bound::Excluded(bound) => Bound::Excluded(encode(txn.start_bound_buffer(), bound)?),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+
+        let end_bound = match range.end_bound() {
+            Bound::Included(bound) => Bound::Included(encode(txn.end_bound_buffer(), bound)?),
+            Bound::Excluded(bound) => Bound::Excluded(encode(txn.end_bound_buffer(), bound)?),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+
+        Ok((start_bound, end_bound))
+    }
+
+    /// Attaches the context of the failed operation to the error,
+    /// if enabled with [`EnvOpenOptions::error_context`].
+    fn with_context(
+        &self,
+        txn: &RoTxn,
+        operation: Operation,
+        key: Option<&[u8]>,
+        error: Error<E>,
+    ) -> Error<E> {
+        txn.env.error_with_context(self.dbi, operation, key, error)
+    }
 }
 
-impl<KC, DC, C> Clone for Database<KC, DC, C> {
-    fn clone(&self) -> Database<KC, DC, C> {
+impl<KC, DC, C, CDUP, E> Clone for Database<KC, DC, C, CDUP, E> {
+    fn clone(&self) -> Database<KC, DC, C, CDUP, E> {
         *self
     }
 }
 
-impl<KC, DC, C> Copy for Database<KC, DC, C> {}
+impl<KC, DC, C, CDUP, E> Copy for Database<KC, DC, C, CDUP, E> {}
 
-impl<KC, DC, C> fmt::Debug for Database<KC, DC, C> {
+impl<KC, DC, C, CDUP, E> fmt::Debug for Database<KC, DC, C, CDUP, E> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Database")
             .field("key_codec", &any::type_name::<KC>())
             .field("data_codec", &any::type_name::<DC>())
             .field("comparator", &any::type_name::<C>())
+            .field("dup_sort_comparator", &any::type_name::<CDUP>())
             .finish()
     }
 }
 
+/// Returns `true` if the database was opened with the [`DatabaseFlags::DUP_SORT`] flag.
+fn is_dup_sort(raw_txn: *mut ffi::MDB_txn, dbi: ffi::MDB_dbi) -> Result<bool> {
+    let mut flags = 0;
+    unsafe { mdb_result(ffi::mdb_dbi_flags(raw_txn, dbi, &mut flags))? };
+    Ok(AllDatabaseFlags::from_bits_truncate(flags).contains(AllDatabaseFlags::DUP_SORT))
+}
+
+/// Converts the error of an operation done with codecs that cannot fail, like the ones on raw bytes.
+fn infallible_codecs<E>(error: Error<Infallible>) -> Error<E> {
+    error.map_codec_error(|never| match never {})
+}
+
+/// The encoded bounds of a range of keys.
+type EncodedRange<'txn> = (Bound<ScratchBuffer<'txn>>, Bound<ScratchBuffer<'txn>>);
+
 /// Statistics for a database in the environment.
 #[derive(Debug, Clone, Copy)]
 pub struct DatabaseStat {
@@ -2636,6 +3473,9 @@ pub struct DatabaseStat {
 
 #[cfg(test)]
 mod tests {
+    use std::borrow::Cow;
+
+    use byteorder::BigEndian;
     use heed_types::*;
 
     use super::*;
@@ -2658,6 +3498,1247 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn encode_into_reused_buffers() -> Result<()> {
+        /// A codec that can only be encoded into a writer.
+        enum WriterOnly {}
+
+        impl<'a> BytesEncode<'a> for WriterOnly {
+            type EItem = u32;
+            type Error = BoxedError;
+
+            fn bytes_encode(_item: &'a u32) -> result::Result<Cow<'a, [u8]>, Self::Error> {
+                unreachable!("the database must encode into its buffers")
+            }
+
+            fn encodes_into_writer(_item: &u32) -> bool {
+                true
+            }
+
+            fn bytes_encode_into_writer<W: std::io::Write>(
+                item: &'a u32,
+                mut writer: W,
+            ) -> result::Result<(), EncodeIntoWriterError<BoxedError>> {
+                writer.write_all(&item.to_be_bytes()).map_err(Into::into)
+            }
+        }
+
+        let dir = tempfile::tempdir()?;
+        let env = unsafe { EnvOpenOptions::new().open(dir.path())? };
+        let mut txn = env.write_txn()?;
+        let db = env.create_database::<WriterOnly, WriterOnly>(&mut txn, None)?;
+
+        for i in 0..10 {
+            db.put(&mut txn, &i, &(i * 2))?;
+        }
+        db.delete(&mut txn, &3)?;
+
+        let db = db.remap_types::<WriterOnly, U32<BigEndian>>();
+        assert_eq!(db.get(&txn, &4)?, Some(8));
+        assert_eq!(db.get(&txn, &3)?, None);
+        assert_eq!(db.remap_types::<U32<BigEndian>, U32<BigEndian>>().len(&txn)?, 9);
+        let range: Vec<_> =
+            db.range(&txn, &(2..5))?.remap_key_type::<U32<BigEndian>>().collect::<Result<_>>()?;
+        assert_eq!(range, [(2, 4), (4, 8)]);
+
+        // The codecs that do not encode into a writer are not copied into the buffers.
+        let key = b"borrowed".to_vec();
+        let mut buffer = txn.key_buffer();
+        let bytes = buffer.encode::<Bytes>(&key).unwrap();
+        assert_eq!(bytes.as_ptr(), key.as_ptr());
+        let bytes = buffer.encode::<U32<BigEndian>>(&42).unwrap();
+        assert_eq!(bytes, [0, 0, 0, 42]);
+        drop(buffer);
+
+        // The bounds of the iterators are encoded into the buffers of the transaction
+        // and given back once the iterators are dropped.
+        let mut buffer = txn.start_bound_buffer();
+        buffer.encode_to_buffer::<U32<BigEndian>>(&0).unwrap();
+        let start_ptr = buffer.as_ptr();
+        drop(buffer);
+        let range = db.range(&txn, &(7..))?.remap_key_type::<U32<BigEndian>>();
+        let range: Vec<_> = range.collect::<Result<_>>()?;
+        assert_eq!(range, [(7, 14), (8, 16), (9, 18)]);
+        assert_eq!(txn.start_bound_buffer().as_ptr(), start_ptr);
+
+        Ok(())
+    }
+
+    #[test]
+    fn put_encodes_into_reserved_space() -> Result<()> {
+        /// A codec that cannot encode the empty slices but reports the size of all of them.
+        enum NonEmptyBytes {}
+
+        impl<'a> BytesEncode<'a> for NonEmptyBytes {
+            type EItem = [u8];
+            type Error = BoxedError;
+
+            fn bytes_encode(item: &'a [u8]) -> result::Result<Cow<'a, [u8]>, Self::Error> {
+                if item.is_empty() {
+                    Err("empty slice".into())
+                } else {
+                    Ok(Cow::Borrowed(item))
+                }
+            }
+
+            fn encoded_size(item: &[u8]) -> Option<usize> {
+                Some(item.len())
+            }
+        }
+
+        /// A codec that reports a size of three bytes for all the slices.
+        enum WrongSizeBytes {}
+
+        impl<'a> BytesEncode<'a> for WrongSizeBytes {
+            type EItem = [u8];
+            type Error = Infallible;
+
+            fn bytes_encode(item: &'a [u8]) -> result::Result<Cow<'a, [u8]>, Self::Error> {
+                Ok(Cow::Borrowed(item))
+            }
+
+            fn encoded_size(_item: &[u8]) -> Option<usize> {
+                Some(3)
+            }
+        }
+
+        /// A codec that cannot fail and writes the bytes of the items in reverse order.
+        enum ReversedBytes {}
+
+        impl<'a> BytesEncode<'a> for ReversedBytes {
+            type EItem = [u8];
+            type Error = Infallible;
+
+            fn bytes_encode(item: &'a [u8]) -> result::Result<Cow<'a, [u8]>, Self::Error> {
+                Ok(Cow::Owned(item.iter().rev().copied().collect()))
+            }
+
+            fn encodes_into_writer(_item: &[u8]) -> bool {
+                true
+            }
+
+            fn encoded_size(item: &[u8]) -> Option<usize> {
+                Some(item.len())
+            }
+
+            fn bytes_encode_into_writer<W: io::Write>(
+                item: &'a [u8],
+                mut writer: W,
+            ) -> result::Result<(), EncodeIntoWriterError<Self::Error>> {
+                item.iter().rev().try_for_each(|byte| writer.write_all(&[*byte]))?;
+                Ok(())
+            }
+        }
+
+        let dir = tempfile::tempdir()?;
+        let env = unsafe { EnvOpenOptions::new().max_dbs(3).open(dir.path())? };
+        let mut txn = env.write_txn()?;
+        let db = env.create_database::<Str, NonEmptyBytes>(&mut txn, Some("non-empty"))?;
+        let reversed = env.create_database::<Str, ReversedBytes>(&mut txn, Some("reversed"))?;
+        let dups = env
+            .database_options()
+            .types::<Str, ReversedBytes>()
+            .name("dups")
+            .flags(DatabaseFlags::DUP_SORT)
+            .create(&mut txn)?;
+
+        // An encoding error puts the previous value back.
+        db.put(&mut txn, "hello", b"world")?;
+        let error = db.put(&mut txn, "hello", b"").unwrap_err();
+        assert!(matches!(error, Error::Encoding(_)));
+        assert_eq!(db.remap_data_type::<Bytes>().get(&txn, "hello")?, Some(&b"world"[..]));
+        let error = db.put(&mut txn, "empty", b"").unwrap_err();
+        assert!(matches!(error, Error::Encoding(_)));
+        assert_eq!(db.remap_data_type::<Bytes>().get(&txn, "empty")?, None);
+
+        // Like a codec that writes fewer or more bytes than its encoded size.
+        let wrong_size = db.remap_data_type::<WrongSizeBytes>();
+        for value in [&b"ab"[..], &b"abcd"[..]] {
+            let error = wrong_size.put(&mut txn, "hello", value).unwrap_err();
+            assert!(matches!(error, Error::Io(_)), "{:?}", error);
+            assert_eq!(db.remap_data_type::<Bytes>().get(&txn, "hello")?, Some(&b"world"[..]));
+        }
+
+        // The infallible codecs are written into the reserved space.
+        reversed.put(&mut txn, "a", b"abc")?;
+        reversed.put(&mut txn, "a", b"de")?;
+        let reversed = reversed.remap_data_type::<Bytes>();
+        assert_eq!(reversed.get(&txn, "a")?, Some(&b"ed"[..]));
+
+        // Duplicate databases cannot reserve space and use the regular path.
+        dups.put(&mut txn, "a", b"ab")?;
+        dups.put(&mut txn, "a", b"ba")?;
+        let dups = dups.remap_data_type::<Bytes>();
+        let values: Vec<_> = dups.get_duplicates(&txn, "a")?.unwrap().collect::<Result<_>>()?;
+        assert_eq!(values, [("a", &b"ab"[..]), ("a", &b"ba"[..])]);
+
+        let db =
+            env.create_database::<U32<BigEndian>, SerdeBincode<Vec<String>>>(&mut txn, None)?;
+        let names = vec![String::from("kevin"), String::from("clément")];
+        db.put(&mut txn, &1, &names)?;
+        assert_eq!(db.get(&txn, &1)?, Some(names));
+
+        Ok(())
+    }
+
+    #[test]
+    fn dup_sort_comparator() -> Result<()> {
+        enum StringAsIntCmp {}
+
+        impl Comparator for StringAsIntCmp {
+            fn compare(a: &[u8], b: &[u8]) -> std::cmp::Ordering {
+                let a: i32 = std::str::from_utf8(a).unwrap().parse().unwrap();
+                let b: i32 = std::str::from_utf8(b).unwrap().parse().unwrap();
+                a.cmp(&b)
+            }
+        }
+
+        let dir = tempfile::tempdir()?;
+        let env = unsafe { EnvOpenOptions::new().open(dir.path())? };
+        let mut wtxn = env.write_txn()?;
+        let db = env
+            .database_options()
+            .types::<Str, Str>()
+            .dup_sort_comparator::<StringAsIntCmp>()
+            .flags(DatabaseFlags::DUP_SORT)
+            .create(&mut wtxn)?;
+
+        for value in ["100", "-1000", "20", "-3"] {
+            db.put(&mut wtxn, "numbers", value)?;
+        }
+        db.put(&mut wtxn, "other", "9")?;
+        db.put(&mut wtxn, "other", "10")?;
+
+        let values: Result<Vec<_>> =
+            db.get_duplicates(&wtxn, "numbers")?.unwrap().map(|r| r.map(|(_, v)| v)).collect();
+        assert_eq!(values?, ["-1000", "-3", "20", "100"]);
+        assert_eq!(db.get(&wtxn, "other")?, Some("9"));
+
+        let entries: Vec<_> = db.rev_iter(&wtxn)?.collect::<Result<_>>()?;
+        assert_eq!(
+            entries,
+            [
+                ("other", "10"),
+                ("other", "9"),
+                ("numbers", "100"),
+                ("numbers", "20"),
+                ("numbers", "-3"),
+                ("numbers", "-1000"),
+            ]
+        );
+
+        assert!(db.delete_one_duplicate(&mut wtxn, "numbers", "20")?);
+        let iter = db.get_duplicates(&wtxn, "numbers")?.unwrap();
+        assert_eq!(iter.last().transpose()?, Some(("numbers", "100")));
+
+        assert!(format!("{:?}", db).contains("StringAsIntCmp"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn ready_made_comparators() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let env = unsafe { EnvOpenOptions::new().max_dbs(4).open(dir.path())? };
+        let mut wtxn = env.write_txn()?;
+
+        let reverse = env
+            .database_options()
+            .types::<Str, Unit>()
+            .key_comparator::<ReverseLexicographicComparator>()
+            .name("reverse")
+            .create(&mut wtxn)?;
+        for key in ["a", "ab", "abc", "abd", "b", "aa"] {
+            reverse.put(&mut wtxn, key, &())?;
+        }
+        let keys: Vec<_> =
+            reverse.iter(&wtxn)?.map(|r| r.map(|(k, _)| k)).collect::<Result<_>>()?;
+        assert_eq!(keys, ["b", "a", "ab", "abd", "abc", "aa"]);
+        let keys: Vec<_> =
+            reverse.prefix_iter(&wtxn, "ab")?.map(|r| r.map(|(k, _)| k)).collect::<Result<_>>()?;
+        assert_eq!(keys, ["ab", "abd", "abc"]);
+        let keys: Vec<_> = reverse
+            .rev_prefix_iter(&wtxn, "ab")?
+            .map(|r| r.map(|(k, _)| k))
+            .collect::<Result<_>>()?;
+        assert_eq!(keys, ["abc", "abd", "ab"]);
+
+        let natural = env
+            .database_options()
+            .types::<Str, Unit>()
+            .key_comparator::<NaturalOrderComparator>()
+            .name("natural")
+            .create(&mut wtxn)?;
+        for key in ["file10", "file2", "File3", "file1"] {
+            natural.put(&mut wtxn, key, &())?;
+        }
+        let keys: Vec<_> =
+            natural.iter(&wtxn)?.map(|r| r.map(|(k, _)| k)).collect::<Result<_>>()?;
+        assert_eq!(keys, ["File3", "file1", "file2", "file10"]);
+
+        let signed = env
+            .database_options()
+            .types::<I64<BigEndian>, Unit>()
+            .key_comparator::<BigEndianSignedComparator>()
+            .name("signed")
+            .create(&mut wtxn)?;
+        for key in [3, -2, 0, i64::MIN, -40, i64::MAX] {
+            signed.put(&mut wtxn, &key, &())?;
+        }
+        let keys: Vec<_> =
+            signed.range(&wtxn, &(-40..=0))?.map(|r| r.map(|(k, _)| k)).collect::<Result<_>>()?;
+        assert_eq!(keys, [-40, -2, 0]);
+        let keys: Vec<_> =
+            signed.rev_range(&wtxn, &(..0))?.map(|r| r.map(|(k, _)| k)).collect::<Result<_>>()?;
+        assert_eq!(keys, [-2, -40, i64::MIN]);
+        assert_eq!(signed.delete_range(&mut wtxn, &(-40..))?, 5);
+        assert_eq!(signed.first(&wtxn)?, Some((i64::MIN, ())));
+        assert_eq!(signed.last(&wtxn)?, Some((i64::MIN, ())));
+
+        let case_insensitive = env
+            .database_options()
+            .types::<Str, Str>()
+            .key_comparator::<AsciiCaseInsensitiveComparator>()
+            .name("case-insensitive")
+            .create(&mut wtxn)?;
+        case_insensitive.put(&mut wtxn, "Kero", "first")?;
+        case_insensitive.put(&mut wtxn, "KERO", "second")?;
+        case_insensitive.put(&mut wtxn, "apple", "third")?;
+        assert_eq!(case_insensitive.get(&wtxn, "kero")?, Some("second"));
+        assert_eq!(case_insensitive.len(&wtxn)?, 2);
+        let entries: Vec<_> = case_insensitive
+            .range(&wtxn, &(Bound::Included("APPLE"), Bound::Included("kERO")))?
+            .collect::<Result<_>>()?;
+        let values: Vec<_> = entries.into_iter().map(|(_, v)| v).collect();
+        assert_eq!(values, ["third", "second"]);
+
+        wtxn.commit()?;
+        Ok(())
+    }
+
+    /// Returns the keys found by `get_lower_than`, `get_lower_than_or_equal_to`,
+    /// `get_greater_than` and `get_greater_than_or_equal_to`, in this order.
+    fn neighbours<C: Comparator + 'static>(keys: &[&[u8]], key: &[u8]) -> Result<[Vec<u8>; 4]> {
+        let dir = tempfile::tempdir()?;
+        let env = unsafe { EnvOpenOptions::new().open(dir.path())? };
+        let mut wtxn = env.write_txn()?;
+        let db = env
+            .database_options()
+            .types::<Bytes, Unit>()
+            .key_comparator::<C>()
+            .create(&mut wtxn)?;
+        for key in keys {
+            db.put(&mut wtxn, key, &())?;
+        }
+        let key_of = |entry: Option<(&[u8], ())>| entry.map_or_else(Vec::new, |(k, ())| k.to_vec());
+        Ok([
+            key_of(db.get_lower_than(&wtxn, key)?),
+            key_of(db.get_lower_than_or_equal_to(&wtxn, key)?),
+            key_of(db.get_greater_than(&wtxn, key)?),
+            key_of(db.get_greater_than_or_equal_to(&wtxn, key)?),
+        ])
+    }
+
+    #[test]
+    fn neighbours_with_reverse_lexicographic_comparator() -> Result<()> {
+        // The keys are ordered "c" < "b" < "bb" < "a".
+        let keys: [&[u8]; 3] = [b"a", b"b", b"c"];
+        type C = ReverseLexicographicComparator;
+        assert_eq!(neighbours::<C>(&keys, b"bb")?, [b"b", b"b", b"a", b"a"]);
+        assert_eq!(neighbours::<C>(&keys, b"b")?, [b"c", b"b", b"a", b"b"]);
+        Ok(())
+    }
+
+    #[test]
+    fn neighbours_with_ascii_case_insensitive_comparator() -> Result<()> {
+        let keys: [&[u8]; 3] = [b"apple", b"KERO", b"zebra"];
+        type C = AsciiCaseInsensitiveComparator;
+        assert_eq!(neighbours::<C>(&keys, b"kero")?, [&b"apple"[..], b"KERO", b"zebra", b"KERO"]);
+        Ok(())
+    }
+
+    #[test]
+    fn neighbours_with_natural_order_comparator() -> Result<()> {
+        let keys: [&[u8]; 3] = [b"file2", b"file10", b"file100"];
+        type C = NaturalOrderComparator;
+        assert_eq!(
+            neighbours::<C>(&keys, b"file9")?,
+            [&b"file2"[..], b"file2", b"file10", b"file10"]
+        );
+        assert_eq!(
+            neighbours::<C>(&keys, b"file10")?,
+            [&b"file2"[..], b"file10", b"file100", b"file10"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn neighbours_with_big_endian_signed_comparator() -> Result<()> {
+        let keys: [&[u8]; 3] = [&(-2i8).to_be_bytes(), &(-1i8).to_be_bytes(), &1i8.to_be_bytes()];
+        type C = BigEndianSignedComparator;
+        assert_eq!(
+            neighbours::<C>(&keys, &0i8.to_be_bytes())?,
+            [keys[1], keys[1], keys[2], keys[2]]
+        );
+        assert_eq!(
+            neighbours::<C>(&keys, &(-1i8).to_be_bytes())?,
+            [keys[0], keys[1], keys[2], keys[1]]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn neighbours_with_integer_key_comparator() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let env = unsafe { EnvOpenOptions::new().open(dir.path())? };
+        let mut wtxn = env.write_txn()?;
+        let db = env.create_integer_key_database::<NativeU32Key, Unit>(&mut wtxn, None)?;
+        for key in [1, 256, 65536] {
+            db.put(&mut wtxn, &key, &())?;
+        }
+        let key_of = |entry: Option<(u32, ())>| entry.map(|(k, ())| k);
+        assert_eq!(key_of(db.get_lower_than(&wtxn, &256)?), Some(1));
+        assert_eq!(key_of(db.get_lower_than_or_equal_to(&wtxn, &256)?), Some(256));
+        assert_eq!(key_of(db.get_lower_than_or_equal_to(&wtxn, &2)?), Some(1));
+        assert_eq!(key_of(db.get_greater_than(&wtxn, &2)?), Some(256));
+        assert_eq!(key_of(db.get_greater_than(&wtxn, &256)?), Some(65536));
+        assert_eq!(key_of(db.get_greater_than_or_equal_to(&wtxn, &257)?), Some(65536));
+        Ok(())
+    }
+
+    #[test]
+    fn dyn_key_comparator() -> Result<()> {
+        use std::sync::Arc;
+
+        use crate::dyn_comparator::MAX_DYN_COMPARATORS;
+
+        // Every byte of the keys is a part of the schema, ordered in ascending or descending order.
+        fn schema_comparator(descending: Vec<bool>) -> Arc<dyn DynComparator> {
+            Arc::new(move |a: &[u8], b: &[u8]| {
+                for ((a, b), &descending) in a.iter().zip(b).zip(&descending) {
+                    let ordering = if descending { b.cmp(a) } else { a.cmp(b) };
+                    if ordering.is_ne() {
+                        return ordering;
+                    }
+                }
+                a.len().cmp(&b.len())
+            })
+        }
+
+        let dir = tempfile::tempdir()?;
+        let max_dbs = MAX_DYN_COMPARATORS as u32 + 3;
+        let env = unsafe { EnvOpenOptions::new().max_dbs(max_dbs).open(dir.path())? };
+        let mut wtxn = env.write_txn()?;
+        let mut options = env.database_options().types::<Bytes, Unit>();
+        options.name("schema");
+        let db =
+            options.dyn_key_comparator(schema_comparator(vec![false, true])).create(&mut wtxn)?;
+
+        for key in [[1, 1], [0, 1], [1, 3], [0, 2], [2, 0]] {
+            db.put(&mut wtxn, &key, &())?;
+        }
+        let keys: Vec<_> = db.iter(&wtxn)?.map(|r| r.map(|(k, _)| k)).collect::<Result<_>>()?;
+        assert_eq!(keys, [[0, 2], [0, 1], [1, 3], [1, 1], [2, 0]]);
+        let range = (Bound::Included(&[1, 3][..]), Bound::Included(&[2, 0][..]));
+        let keys: Vec<_> =
+            db.rev_range(&wtxn, &range)?.map(|r| r.map(|(k, _)| k)).collect::<Result<_>>()?;
+        assert_eq!(keys, [[2, 0], [1, 1], [1, 3]]);
+        assert!(format!("{:?}", db).contains("RuntimeComparator"));
+        wtxn.commit()?;
+
+        // Opening the database again keeps its slot and its comparator.
+        let rtxn = env.read_txn()?;
+        assert!(options.dyn_key_comparator(schema_comparator(vec![true])).open(&rtxn)?.is_some());
+        let keys: Vec<_> = db.iter(&rtxn)?.map(|r| r.map(|(k, _)| k)).collect::<Result<_>>()?;
+        assert_eq!(keys, [[0, 2], [0, 1], [1, 3], [1, 1], [2, 0]]);
+        rtxn.commit()?;
+
+        // The slots of the databases opened by an aborted transaction are released.
+        let mut wtxn = env.write_txn()?;
+        let mut nested = env.nested_write_txn(&mut wtxn)?;
+        env.database_options()
+            .types::<Bytes, Unit>()
+            .name("aborted")
+            .dyn_key_comparator(schema_comparator(vec![false]))
+            .create(&mut nested)?;
+        nested.commit()?;
+        wtxn.abort();
+
+        // LMDB reuses the handle of the aborted database, with the new comparator.
+        let mut wtxn = env.write_txn()?;
+        let reused = env
+            .database_options()
+            .types::<Bytes, Unit>()
+            .name("reused")
+            .dyn_key_comparator(schema_comparator(vec![true]))
+            .create(&mut wtxn)?;
+        reused.put(&mut wtxn, &[1], &())?;
+        reused.put(&mut wtxn, &[2], &())?;
+        assert_eq!(reused.first(&wtxn)?, Some((&[2][..], ())));
+        for i in 2..MAX_DYN_COMPARATORS {
+            env.database_options()
+                .types::<Bytes, Unit>()
+                .name(&format!("schema-{}", i))
+                .dyn_key_comparator(schema_comparator(vec![true]))
+                .create(&mut wtxn)?;
+        }
+        let result = env
+            .database_options()
+            .types::<Bytes, Unit>()
+            .name("full")
+            .dyn_key_comparator(schema_comparator(vec![true]))
+            .create(&mut wtxn);
+        assert!(matches!(result, Err(Error::DynComparatorsFull)));
+        wtxn.commit()?;
+
+        // The slots are released when the environment is closed.
+        env.prepare_for_closing().wait();
+        let env = unsafe { EnvOpenOptions::new().max_dbs(max_dbs).open(dir.path())? };
+        let rtxn = env.read_txn()?;
+        let db = env
+            .database_options()
+            .types::<Bytes, Unit>()
+            .name("schema")
+            .dyn_key_comparator(schema_comparator(vec![false, true]))
+            .open(&rtxn)?
+            .unwrap();
+        let keys: Vec<_> = db.iter(&rtxn)?.map(|r| r.map(|(k, _)| k)).collect::<Result<_>>()?;
+        assert_eq!(keys, [[0, 2], [0, 1], [1, 3], [1, 1], [2, 0]]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dyn_key_comparator_on_opened_database() -> Result<()> {
+        use std::sync::Arc;
+
+        let reversed = || -> Arc<dyn DynComparator> { Arc::new(|a: &[u8], b: &[u8]| b.cmp(a)) };
+
+        let dir = tempfile::tempdir()?;
+        let env = unsafe { EnvOpenOptions::new().max_dbs(3).open(dir.path())? };
+        let mut wtxn = env.write_txn()?;
+        let db = env.create_database::<Bytes, Bytes>(&mut wtxn, Some("a"))?;
+        db.put(&mut wtxn, b"b", b"value")?;
+        wtxn.commit()?;
+
+        // The database is already opened with the default comparator by a committed transaction.
+        let rtxn = env.read_txn()?;
+        let mut options = env.database_options().types::<Bytes, Bytes>();
+        let result = options.name("a").dyn_key_comparator(reversed()).open(&rtxn);
+        assert!(matches!(result, Err(Error::Mdb(MdbError::Incompatible))));
+        drop(rtxn);
+
+        let rtxn = env.read_txn()?;
+        assert_eq!(db.get(&rtxn, b"b")?, Some(&b"value"[..]));
+        drop(rtxn);
+
+        // Or by the same transaction.
+        let mut wtxn = env.write_txn()?;
+        env.create_database::<Bytes, Bytes>(&mut wtxn, Some("c"))?;
+        let mut options = env.database_options().types::<Bytes, Bytes>();
+        let result = options.name("c").dyn_key_comparator(reversed()).create(&mut wtxn);
+        assert!(matches!(result, Err(Error::Mdb(MdbError::Incompatible))));
+
+        // LMDB always keeps the unnamed database opened.
+        let options = env.database_options().types::<Bytes, Bytes>();
+        let result = options.dyn_key_comparator(reversed()).create(&mut wtxn);
+        assert!(matches!(result, Err(Error::Mdb(MdbError::Incompatible))));
+        wtxn.commit()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn integer_key_databases() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let env = unsafe { EnvOpenOptions::new().max_dbs(2).open(dir.path())? };
+        let mut wtxn = env.write_txn()?;
+        let db = env.create_integer_key_database::<NativeU32Key, Str>(&mut wtxn, Some("ids"))?;
+        for (id, name) in [(256, "kiwi"), (1, "apple"), (65536, "melon"), (2, "banana")] {
+            db.put(&mut wtxn, &id, name)?;
+        }
+        let ids: Vec<_> = db.iter(&wtxn)?.map(|r| r.map(|(k, _)| k)).collect::<Result<_>>()?;
+        assert_eq!(ids, [1, 2, 256, 65536]);
+        let ids: Vec<_> =
+            db.range(&wtxn, &(2..65536))?.map(|r| r.map(|(k, _)| k)).collect::<Result<_>>()?;
+        assert_eq!(ids, [2, 256]);
+
+        let mut options = env.database_options().types::<NativeUsizeKey, NativeU32Key>();
+        // The given flags replace the previous ones, the integer flags are kept.
+        options.name("postings").flags(DatabaseFlags::REVERSE_KEY);
+        options.integer_key().integer_dup().flags(DatabaseFlags::DUP_SORT);
+        let postings = options.create(&mut wtxn)?;
+        for (word, doc) in [(300, 70000), (3, 2), (300, 2), (300, 256)] {
+            postings.put(&mut wtxn, &word, &doc)?;
+        }
+        let docs: Vec<_> = postings
+            .get_duplicates(&wtxn, &300)?
+            .unwrap()
+            .map(|r| r.map(|(_, v)| v))
+            .collect::<Result<_>>()?;
+        assert_eq!(docs, [2, 256, 70000]);
+        assert_eq!(postings.first(&wtxn)?, Some((3, 2)));
+        wtxn.commit()?;
+
+        let rtxn = env.read_txn()?;
+        let db = env.open_integer_key_database::<NativeU32Key, Str>(&rtxn, Some("ids"))?.unwrap();
+        assert_eq!(db.last(&rtxn)?, Some((65536, "melon")));
+        assert!(NativeU32Key::bytes_decode(&[1, 0, 0]).is_err());
+        assert!(NativeUsizeKey::bytes_decode(&[1, 0, 0]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn typed_decoding_errors() -> Result<()> {
+        #[derive(Debug)]
+        struct StrError(std::str::Utf8Error);
+
+        impl From<Infallible> for StrError {
+            fn from(never: Infallible) -> StrError {
+                match never {}
+            }
+        }
+
+        impl From<std::str::Utf8Error> for StrError {
+            fn from(error: std::str::Utf8Error) -> StrError {
+                StrError(error)
+            }
+        }
+
+        impl std::fmt::Display for StrError {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl std::error::Error for StrError {}
+
+        let dir = tempfile::tempdir()?;
+        let env = unsafe { EnvOpenOptions::new().open(dir.path())? };
+        let mut txn = env.write_txn()?;
+        let db = env.create_database::<Str, Bytes>(&mut txn, None)?;
+
+        db.put(&mut txn, "a", b"valid")?;
+        db.put(&mut txn, "b", &[0xff])?;
+        db.put(&mut txn, "c", b"valid")?;
+
+        let db = db.remap_data_type::<Str>().remap_error_type::<StrError>();
+        assert_eq!(db.get(&txn, "a").unwrap(), Some("valid"));
+        match db.get(&txn, "b") {
+            Err(Error::Decoding(StrError(error))) => assert_eq!(error.valid_up_to(), 0),
+            otherwise => panic!("expected a typed decoding error, found {:?}", otherwise),
+        }
+
+        let mut iter = db.iter(&txn).unwrap();
+        assert_eq!(iter.next().transpose().unwrap(), Some(("a", "valid")));
+        assert!(matches!(iter.next(), Some(Err(Error::Decoding(_)))));
+        assert_eq!(iter.next().transpose().unwrap(), Some(("c", "valid")));
+        drop(iter);
+
+        // Typed errors can be boxed back to be used along other heed errors.
+        let error: Error = db.get(&txn, "b").unwrap_err().map_codec_error(Into::into);
+        assert!(matches!(error, Error::Decoding(_)));
+
+        assert_eq!(
+            db.delete_range(&mut txn, &(Bound::Included("a"), Bound::Excluded("c"))).unwrap(),
+            2
+        );
+        assert_eq!(db.len(&txn).unwrap(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_and_quarantine_duplicates() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let env = unsafe { EnvOpenOptions::new().max_dbs(2).open(dir.path())? };
+        let mut txn = env.write_txn()?;
+        let db = env
+            .database_options()
+            .types::<Str, Bytes>()
+            .name("dups")
+            .flags(DatabaseFlags::DUP_SORT)
+            .create(&mut txn)?;
+        let quarantine = env
+            .database_options()
+            .types::<Bytes, Bytes>()
+            .name("quarantine")
+            .flags(DatabaseFlags::DUP_SORT)
+            .create(&mut txn)?;
+
+        db.put(&mut txn, "a", b"valid")?;
+        db.put(&mut txn, "a", &[0xff])?;
+        db.put(&mut txn, "a", &[0xfe])?;
+        db.put(&mut txn, "b", b"valid")?;
+
+        let db = db.remap_data_type::<Str>();
+        let report = db.validate(&txn, 100)?;
+        assert_eq!(report.number_of_entries, 4);
+        assert_eq!(report.number_of_invalid_entries, 2);
+        assert!(report.invalid_entries.iter().all(|entry| entry.key == b"a"));
+
+        let report = db.validate_and_quarantine(&mut txn, quarantine, 1)?;
+        assert_eq!(report.number_of_invalid_entries, 2);
+        assert_eq!(report.invalid_entries.len(), 1);
+        assert!(db.validate(&txn, 100)?.is_ok());
+
+        let valid: Vec<_> = db.iter(&txn)?.collect::<Result<_>>()?;
+        assert_eq!(valid, [("a", "valid"), ("b", "valid")]);
+        let invalid: Vec<_> = quarantine.iter(&txn)?.collect::<Result<_>>()?;
+        assert_eq!(invalid, [(&b"a"[..], &[0xfe][..]), (&b"a"[..], &[0xff][..])]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn ordered_numbers_range() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let env = unsafe { EnvOpenOptions::new().max_dbs(2).open(dir.path())? };
+        let mut txn = env.write_txn()?;
+        let ints = env.create_database::<OrderedI64, Unit>(&mut txn, Some("ints"))?;
+        let floats = env.create_database::<OrderedF64, Unit>(&mut txn, Some("floats"))?;
+
+        for n in [-300, i64::MIN, 0, 42, -1, i64::MAX, 1] {
+            ints.put(&mut txn, &n, &())?;
+        }
+        for n in [2.5, -0.0, f64::NEG_INFINITY, -1e10, 0.0, 1e-10, -2.5, f64::INFINITY] {
+            floats.put(&mut txn, &n, &())?;
+        }
+
+        let range: Vec<_> =
+            ints.range(&txn, &(-300..=0))?.map(|r| r.map(|(n, _)| n)).collect::<Result<_>>()?;
+        assert_eq!(range, [-300, -1, 0]);
+        assert_eq!(ints.get_lower_than(&txn, &0)?, Some((-1, ())));
+
+        let range: Vec<_> =
+            floats.range(&txn, &(-2.5..1.0))?.map(|r| r.map(|(n, _)| n)).collect::<Result<_>>()?;
+        assert_eq!(range, [-2.5, -0.0, 0.0, 1e-10]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn tuple_keys_prefix_and_range() -> Result<()> {
+        type Key = Tuple<(U32<BigEndian>, OrderedI64, Str)>;
+        type Tenant = Tuple<(U32<BigEndian>,)>;
+        type TenantTime = Tuple<(U32<BigEndian>, OrderedI64)>;
+
+        let dir = tempfile::tempdir()?;
+        let env = unsafe { EnvOpenOptions::new().open(dir.path())? };
+        let mut txn = env.write_txn()?;
+        let db = env.create_database::<Key, Unit>(&mut txn, None)?;
+
+        let keys: [(u32, i64, &str); 5] =
+            [(2, 0, "a"), (1, 5, "b"), (1, -5, "b"), (1, -5, "a"), (0, 9, "z")];
+        for (tenant, time, name) in keys {
+            db.put(&mut txn, &(&tenant, &time, name), &())?;
+        }
+
+        let decode = |(key, ()): ((u32, i64, Cow<str>), ())| (key.0, key.1, key.2.into_owned());
+        let all: Vec<_> = db.iter(&txn)?.map(|r| r.map(decode)).collect::<Result<_>>()?;
+        let mut expected = keys.map(|(t, i, n)| (t, i, n.to_owned()));
+        expected.sort();
+        assert_eq!(all, expected);
+
+        let tenant: Vec<_> = db
+            .remap_key_type::<Tenant>()
+            .prefix_iter(&txn, &(&1,))?
+            .remap_key_type::<Key>()
+            .map(|r| r.map(decode))
+            .collect::<Result<_>>()?;
+        assert_eq!(tenant, expected[1..4]);
+
+        let range: Vec<_> = db
+            .remap_key_type::<TenantTime>()
+            .range(&txn, &((&1, &-5)..(&1, &5)))?
+            .remap_key_type::<Key>()
+            .map(|r| r.map(decode))
+            .collect::<Result<_>>()?;
+        assert_eq!(range, expected[1..3]);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "serde-ordered")]
+    fn serde_ordered_keys() -> Result<()> {
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+        enum Kind {
+            Login,
+            Purchase { amount: f64 },
+            Tagged(Option<String>, Vec<u16>),
+        }
+
+        #[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+        struct Event {
+            tenant: i32,
+            kind: Kind,
+            name: String,
+        }
+
+        let dir = tempfile::tempdir()?;
+        let env = unsafe { EnvOpenOptions::new().open(dir.path())? };
+        let mut txn = env.write_txn()?;
+        let db = env.create_database::<SerdeOrdered<Event>, Unit>(&mut txn, None)?;
+
+        let event = |tenant, kind, name: &str| Event { tenant, kind, name: name.to_owned() };
+        let mut events = vec![
+            event(1, Kind::Purchase { amount: 12.5 }, "b"),
+            event(-4, Kind::Login, "z"),
+            event(1, Kind::Purchase { amount: -3.0 }, "a"),
+            event(1, Kind::Tagged(Some("x".into()), vec![1]), "a"),
+            event(0, Kind::Login, ""),
+        ];
+        for event in &events {
+            db.put(&mut txn, event, &())?;
+        }
+
+        let stored: Vec<_> = db.iter(&txn)?.map(|r| r.map(|(e, ())| e)).collect::<Result<_>>()?;
+        events.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(stored, events);
+
+        let borrowed = db.remap_key_type::<SerdeOrdered<(i32, u32, &str)>>();
+        let login = borrowed.first(&txn)?.map(|(key, ())| key);
+        assert_eq!(login, Some((-4, 0, "z")));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "lz4", feature = "zstd", feature = "serde-json"))]
+    fn compressed_values_change_algorithm() -> Result<()> {
+        enum Dictionary {}
+
+        impl ZstdDictionary for Dictionary {
+            fn dictionary() -> &'static [u8] {
+                b"a log line that looks like all the other log lines"
+            }
+        }
+
+        type Logs<A> = Compressed<SerdeJson<Vec<String>>, A>;
+
+        let dir = tempfile::tempdir()?;
+        let env = unsafe { EnvOpenOptions::new().open(dir.path())? };
+        let mut txn = env.write_txn()?;
+        let db = env.create_database::<Str, Logs<Uncompressed>>(&mut txn, None)?;
+
+        let logs = vec!["a log line that looks like all the other log lines".to_owned(); 20];
+        db.put(&mut txn, "uncompressed", &logs)?;
+        db.remap_data_type::<Logs<Lz4>>().put(&mut txn, "lz4", &logs)?;
+        db.remap_data_type::<Logs<Zstd>>().put(&mut txn, "zstd", &logs)?;
+        db.remap_data_type::<Logs<Zstd<19>>>().put(&mut txn, "zstd-19", &logs)?;
+        db.remap_data_type::<Logs<ZstdDict<Dictionary>>>().put(&mut txn, "dict", &logs)?;
+
+        // The values are decompressed according to their header, whatever the algorithm is.
+        let db = db.remap_data_type::<Logs<ZstdDict<Dictionary>>>();
+        for result in db.iter(&txn)? {
+            let (_, stored) = result?;
+            assert_eq!(stored, logs);
+        }
+
+        let db = db.remap_data_type::<Logs<Lz4>>();
+        assert!(matches!(db.get(&txn, "dict"), Err(Error::Decoding(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "crc32c", feature = "xxhash"))]
+    fn checksummed_values_detect_corruption() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let env = unsafe { EnvOpenOptions::new().open(dir.path())? };
+        let mut txn = env.write_txn()?;
+        let db = env.create_database::<Str, Checksummed<Str, Crc32c>>(&mut txn, None)?;
+
+        db.put(&mut txn, "hello", "world")?;
+        db.put(&mut txn, "intact", "value")?;
+        db.remap_data_type::<Checksummed<Str, Xxh3>>().put(&mut txn, "xxh3", "value")?;
+        assert_eq!(db.get(&txn, "hello")?, Some("world"));
+        assert_eq!(
+            db.remap_data_type::<Checksummed<Str, Xxh3>>().get(&txn, "xxh3")?,
+            Some("value")
+        );
+
+        // Flip a bit of the stored value without updating its checksum.
+        let raw = db.remap_data_type::<Bytes>();
+        let mut corrupted = raw.get(&txn, "hello")?.unwrap().to_vec();
+        corrupted[0] ^= 1;
+        raw.put(&mut txn, "hello", &corrupted)?;
+
+        // The checksum errors are typed to distinguish corruption from codec failures.
+        let db = db.remap_error_type::<ChecksumError>();
+
+        match db.get(&txn, "hello") {
+            Err(Error::Decoding(e @ ChecksumError::Mismatch { .. })) => assert!(e.is_corruption()),
+            otherwise => panic!("expected a checksum mismatch, got {:?}", otherwise),
+        }
+        assert!(matches!(db.get(&txn, "intact"), Ok(Some("value"))));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "aes-gcm", feature = "chacha20poly1305", feature = "serde-json"))]
+    fn encrypted_values_rotate_keys() -> Result<()> {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        static CURRENT_KEY: AtomicU32 = AtomicU32::new(0);
+
+        enum Keys {}
+
+        impl KeyProvider for Keys {
+            fn current_key() -> (u32, EncryptionKey) {
+                let id = CURRENT_KEY.load(Ordering::Relaxed);
+                (id, Keys::key(id).unwrap())
+            }
+
+            fn key(id: u32) -> Option<EncryptionKey> {
+                match id {
+                    0 => Some(EncryptionKey::Aes256Gcm([0; 32])),
+                    1 => Some(EncryptionKey::ChaCha20Poly1305([1; 32])),
+                    _ => None,
+                }
+            }
+        }
+
+        let dir = tempfile::tempdir()?;
+        let env = unsafe { EnvOpenOptions::new().open(dir.path())? };
+        let mut txn = env.write_txn()?;
+        let db = env.create_database::<Str, Encrypted<SerdeJson<String>, Keys>>(&mut txn, None)?;
+
+        db.put(&mut txn, "alice", &"alice@example.com".to_owned())?;
+        CURRENT_KEY.store(1, Ordering::Relaxed);
+        db.put(&mut txn, "bob", &"bob@example.com".to_owned())?;
+
+        let raw = db.remap_data_type::<Bytes>();
+        let alice = raw.get(&txn, "alice")?.unwrap().to_vec();
+        assert_eq!(&alice[..5], &[1, 0, 0, 0, 0]);
+        assert_eq!(&raw.get(&txn, "bob")?.unwrap()[..5], &[2, 0, 0, 0, 1]);
+
+        // The values are decrypted with the key identified by their header.
+        let entries: Vec<_> = db.iter(&txn)?.collect::<Result<_>>()?;
+        assert_eq!(
+            entries,
+            [("alice", "alice@example.com".to_owned()), ("bob", "bob@example.com".to_owned())]
+        );
+
+        // Tampering with the ciphertext is detected.
+        let mut tampered = alice;
+        *tampered.last_mut().unwrap() ^= 1;
+        raw.put(&mut txn, "tampered", &tampered)?;
+        match db.get(&txn, "tampered") {
+            Err(Error::Decoding(e)) => {
+                assert_eq!(e.downcast_ref::<EncryptedError>(), Some(&EncryptedError::Decryption))
+            }
+            otherwise => panic!("expected a decoding error, got {:?}", otherwise),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "serde-bincode")]
+    fn bincode_options() -> Result<()> {
+        use bincode::config::{BigEndian, Bounded, WithOtherEndian, WithOtherLimit};
+        use bincode::{DefaultOptions, Options};
+
+        enum Bounded16 {}
+
+        impl BincodeOptions for Bounded16 {
+            type Options = WithOtherLimit<WithOtherEndian<DefaultOptions, BigEndian>, Bounded>;
+
+            fn options() -> Self::Options {
+                DefaultOptions::new().with_big_endian().with_limit(16)
+            }
+        }
+
+        let dir = tempfile::tempdir()?;
+        let env = unsafe { EnvOpenOptions::new().open(dir.path())? };
+        let mut txn = env.write_txn()?;
+        let db = env.create_database::<Str, SerdeBincode<Vec<u64>>>(&mut txn, None)?;
+
+        let small = vec![1, 2, 3];
+        let large = vec![u64::MAX; 4];
+        let varint = db.remap_data_type::<SerdeBincodeWith<Vec<u64>, DefaultOptions>>();
+        varint.put(&mut txn, "varint", &small)?;
+        assert_eq!(db.remap_data_type::<Bytes>().get(&txn, "varint")?.unwrap(), &[3, 1, 2, 3]);
+        assert_eq!(varint.get(&txn, "varint")?, Some(small.clone()));
+
+        // The size limit is enforced when encoding the values.
+        let bounded = db.remap_data_type::<SerdeBincodeWith<Vec<u64>, Bounded16>>();
+        bounded.put(&mut txn, "bounded", &small)?;
+        assert_eq!(bounded.get(&txn, "bounded")?, Some(small));
+        assert!(matches!(bounded.put(&mut txn, "bounded", &large), Err(Error::Encoding(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde-postcard", feature = "serde-cbor", feature = "serde-bitcode"))]
+    fn serde_formats() -> Result<()> {
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct User<'a> {
+            id: u64,
+            name: &'a str,
+            #[serde(borrow)]
+            avatar: Cow<'a, [u8]>,
+        }
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct OwnedUser {
+            id: u64,
+            name: String,
+            avatar: Vec<u8>,
+        }
+
+        let dir = tempfile::tempdir()?;
+        let env = unsafe { EnvOpenOptions::new().open(dir.path())? };
+        let mut txn = env.write_txn()?;
+        let db = env.create_database::<Str, Bytes>(&mut txn, None)?;
+
+        let user = User { id: 42, name: "kero", avatar: Cow::Borrowed(&[1, 2, 3]) };
+        db.remap_data_type::<SerdePostcard<User>>().put(&mut txn, "postcard", &user)?;
+        db.remap_data_type::<Bitcode<User>>().put(&mut txn, "bitcode", &user)?;
+        db.remap_data_type::<SerdeCbor<User>>().put(&mut txn, "cbor", &user)?;
+
+        // Postcard and bitcode borrow the strings and bytes from the database.
+        let stored = db.remap_data_type::<SerdePostcard<User>>().get(&txn, "postcard")?.unwrap();
+        assert_eq!(stored, user);
+        let stored = db.remap_data_type::<Bitcode<User>>().get(&txn, "bitcode")?.unwrap();
+        assert_eq!(stored, user);
+
+        let stored = db.remap_data_type::<SerdeCbor<OwnedUser>>().get(&txn, "cbor")?.unwrap();
+        assert_eq!(stored, OwnedUser { id: 42, name: "kero".into(), avatar: vec![1, 2, 3] });
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "rkyv")]
+    fn rkyv_archived_values() -> Result<()> {
+        #[derive(rkyv::Archive, rkyv::Serialize)]
+        struct Score {
+            player: u64,
+            points: u32,
+            level: u32,
+        }
+
+        #[derive(rkyv::Archive, rkyv::Serialize)]
+        struct Profile {
+            name: String,
+            friends: Vec<u64>,
+        }
+
+        let dir = tempfile::tempdir()?;
+        let env = unsafe { EnvOpenOptions::new().max_dbs(2).open(dir.path())? };
+        let mut txn = env.write_txn()?;
+
+        // With 8 bytes keys and 16 bytes archives the values are aligned on 16 bytes
+        // and accessed directly from the memory map.
+        let scores =
+            env.create_database::<U64<BigEndian>, Rkyv<Score>>(&mut txn, Some("scores"))?;
+        for player in 0..100 {
+            scores.put(
+                &mut txn,
+                &player,
+                &Score { player, points: player as u32 * 10, level: 1 },
+            )?;
+        }
+
+        let score = scores.get(&txn, &42)?.unwrap();
+        assert!(score.is_borrowed());
+        assert_eq!(score.player, 42);
+        assert_eq!(score.points, 420);
+        assert_eq!(score.level, 1);
+        for (i, result) in scores.iter(&txn)?.enumerate() {
+            let (player, score) = result?;
+            assert!(score.is_borrowed());
+            assert_eq!(player, i as u64);
+            assert_eq!(
+                (score.player.to_native(), score.points.to_native()),
+                (player, i as u32 * 10)
+            );
+        }
+
+        // Otherwise the values are copied into an aligned buffer before being accessed,
+        // LMDB aligns the nodes on 2 bytes and the keys of 5 bytes misalign the values.
+        let profiles = env.create_database::<Str, Rkyv<Profile>>(&mut txn, Some("profiles"))?;
+        profiles.put(
+            &mut txn,
+            "kerol",
+            &Profile { name: "Kerollmops".into(), friends: vec![1, 2] },
+        )?;
+
+        let profile = profiles.get(&txn, "kerol")?.unwrap();
+        assert!(!profile.is_borrowed());
+        assert_eq!(profile.name, "Kerollmops");
+        assert_eq!(profile.friends.as_slice(), &[1, 2]);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde-json", feature = "serde-bincode"))]
+    fn versioned_values_are_upgraded() -> Result<()> {
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize)]
+        struct UserV1 {
+            name: String,
+        }
+
+        impl Version for UserV1 {
+            const VERSION: u8 = 1;
+            type Codec = SerdeJson<Self>;
+            type Previous = NoPrevious;
+        }
+
+        #[derive(Serialize, Deserialize)]
+        struct UserV2 {
+            name: String,
+            age: u8,
+        }
+
+        impl Version for UserV2 {
+            const VERSION: u8 = 2;
+            type Codec = SerdeBincode<Self>;
+            type Previous = UserV1;
+        }
+
+        impl From<UserV1> for UserV2 {
+            fn from(UserV1 { name }: UserV1) -> UserV2 {
+                UserV2 { name, age: 0 }
+            }
+        }
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct User {
+            first_name: String,
+            age: u8,
+            admin: bool,
+        }
+
+        impl Version for User {
+            const VERSION: u8 = 3;
+            type Codec = SerdeBincode<Self>;
+            type Previous = UserV2;
+        }
+
+        impl From<UserV2> for User {
+            fn from(UserV2 { name, age }: UserV2) -> User {
+                User { first_name: name, age, admin: false }
+            }
+        }
+
+        let dir = tempfile::tempdir()?;
+        let env = unsafe { EnvOpenOptions::new().open(dir.path())? };
+        let mut txn = env.write_txn()?;
+        let db = env.create_database::<Str, Versioned<User>>(&mut txn, None)?;
+
+        let v1 = UserV1 { name: "alice".into() };
+        db.remap_data_type::<Versioned<UserV1>>().put(&mut txn, "alice", &v1)?;
+        let v2 = UserV2 { name: "bob".into(), age: 32 };
+        db.remap_data_type::<Versioned<UserV2>>().put(&mut txn, "bob", &v2)?;
+        let carol = User { first_name: "carol".into(), age: 27, admin: true };
+        db.put(&mut txn, "carol", &carol)?;
+
+        let alice = User { first_name: "alice".into(), age: 0, admin: false };
+        let bob = User { first_name: "bob".into(), age: 32, admin: false };
+        assert_eq!(db.get(&txn, "alice")?, Some(alice));
+        let users: Vec<_> =
+            db.iter(&txn)?.map(|r| r.map(|(_, user)| user)).collect::<Result<_>>()?;
+        assert_eq!(users[1..], [bob, carol]);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "derive")]
+    fn derived_codecs() -> Result<()> {
+        use crate::HeedCodec;
+
+        #[derive(Debug, Clone, PartialEq, PartialOrd, HeedCodec)]
+        enum Kind {
+            Login,
+            Purchase { amount: u64, refunded: bool },
+            Tagged(#[heed(length_prefixed)] Vec<u8>, [i16; 2]),
+        }
+
+        #[derive(Debug, Clone, PartialEq, PartialOrd, HeedCodec)]
+        struct Event {
+            #[heed(prefix)]
+            tenant: i32,
+            #[heed(prefix)]
+            name: String,
+            kind: Kind,
+            #[heed(little_endian)]
+            sequence: u32,
+        }
+
+        let dir = tempfile::tempdir()?;
+        let env = unsafe { EnvOpenOptions::new().open(dir.path())? };
+        let mut txn = env.write_txn()?;
+        let db = env.create_database::<Event, Unit>(&mut txn, None)?;
+
+        let event =
+            |tenant, name: &str, kind| Event { tenant, name: name.into(), kind, sequence: 7 };
+        let mut events = vec![
+            event(1, "b", Kind::Purchase { amount: 12, refunded: false }),
+            event(-4, "z", Kind::Login),
+            event(1, "a", Kind::Purchase { amount: 3, refunded: true }),
+            event(1, "a", Kind::Tagged(vec![0, 1], [-2, 5])),
+            event(1, "a", Kind::Tagged(vec![0, 1], [-3, 5])),
+            event(1, "", Kind::Login),
+            event(i32::MIN, "a", Kind::Login),
+        ];
+        for event in &events {
+            db.put(&mut txn, event, &())?;
+        }
+
+        let stored: Vec<_> = db.iter(&txn)?.map(|r| r.map(|(e, ())| e)).collect::<Result<_>>()?;
+        events.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(stored, events);
+
+        let prefixed: Vec<_> = db
+            .remap_key_type::<EventPrefix>()
+            .prefix_iter(&txn, &(1, "a".to_owned()))?
+            .map(|r| r.map(|(e, ())| e))
+            .collect::<Result<_>>()?;
+        assert_eq!(prefixed, events[3..6]);
+
+        let invalid = event(1, "a\0b", Kind::Login);
+        assert!(matches!(db.put(&mut txn, &invalid, &()), Err(Error::Encoding(_))));
+        let raw = db.remap_key_type::<Bytes>();
+        raw.put(&mut txn, &[0, 0, 0, 0], &())?;
+        assert!(matches!(db.first(&txn), Err(Error::Decoding(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "bytemuck")]
+    fn pod_values() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let env = unsafe { EnvOpenOptions::new().max_dbs(2).open(dir.path())? };
+        let mut txn = env.write_txn()?;
+        let points = env.create_database::<Str, Pod<[f64; 3]>>(&mut txn, Some("points"))?;
+        let series = env.create_database::<Str, PodSlice<u64>>(&mut txn, Some("series"))?;
+
+        points.put(&mut txn, "origin", &[0.0, 0.0, 0.0])?;
+        points.put(&mut txn, "unit", &[1.0, 1.0, 1.0])?;
+        series.put(&mut txn, "odd", &[1, 3, 5, 7])?;
+
+        assert_eq!(points.get(&txn, "unit")?.as_deref(), Some(&[1.0, 1.0, 1.0]));
+        assert_eq!(series.get(&txn, "odd")?.as_deref(), Some(&[1, 3, 5, 7][..]));
+
+        Ok(())
+    }
+
     #[test]
     #[cfg(feature = "longer-keys")]
     fn longer_keys() -> Result<()> {
@@ -0,0 +1,304 @@
+//! Helpers for the text format of the `mdb_dump` and `mdb_load` LMDB tools,
+//! used by [`Env::export`](crate::Env::export) and [`Env::import`](crate::Env::import).
+
+use std::io::{self, BufRead, Write};
+use std::mem;
+
+use crate::cursor::MoveOperation;
+use crate::mdb::error::mdb_result;
+use crate::mdb::ffi;
+use crate::mdb::lmdb_flags::AllDatabaseFlags;
+use crate::{EnvInfo, Error, Result, RoCursor, RoTxn};
+
+/// The most recent version of the `mdb_dump` format we are able to read and write.
+const DUMP_VERSION: u32 = 3;
+
+/// The database flags that are part of the header of a database dump,
+/// in the order `mdb_dump` writes them.
+const DUMP_FLAGS: [(AllDatabaseFlags, &str); 6] = [
+    (AllDatabaseFlags::REVERSE_KEY, "reversekey"),
+    (AllDatabaseFlags::DUP_SORT, "dupsort"),
+    (AllDatabaseFlags::INTEGER_KEY, "integerkey"),
+    (AllDatabaseFlags::DUP_FIXED, "dupfixed"),
+    (AllDatabaseFlags::INTEGER_DUP, "integerdup"),
+    (AllDatabaseFlags::REVERSE_DUP, "reversedup"),
+];
+
+const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
+
+/// Writes the header and every entry of a database in the `mdb_dump` bytevalue format.
+///
+/// The entries for which `skip` returns `true` are not written.
+pub(crate) fn write_database<W, F>(
+    writer: &mut W,
+    rtxn: &RoTxn,
+    info: &EnvInfo,
+    dbi: ffi::MDB_dbi,
+    name: Option<&str>,
+    mut skip: F,
+) -> Result<()>
+where
+    W: Write,
+    F: FnMut(&[u8]) -> bool,
+{
+    let mut flags = 0;
+    unsafe { mdb_result(ffi::mdb_dbi_flags(rtxn.txn, dbi, &mut flags))? };
+    let flags = AllDatabaseFlags::from_bits_truncate(flags);
+
+    let mut stat = mem::MaybeUninit::uninit();
+    unsafe { mdb_result(ffi::mdb_stat(rtxn.txn, dbi, stat.as_mut_ptr()))? };
+    let stat = unsafe { stat.assume_init() };
+
+    writeln!(writer, "VERSION={}", DUMP_VERSION)?;
+    writeln!(writer, "format=bytevalue")?;
+    if let Some(name) = name {
+        writeln!(writer, "database={}", name)?;
+    }
+    writeln!(writer, "type=btree")?;
+    writeln!(writer, "mapsize={}", info.map_size)?;
+    writeln!(writer, "maxreaders={}", info.maximum_number_of_readers)?;
+    if flags.contains(AllDatabaseFlags::DUP_SORT) {
+        writeln!(writer, "duplicates=1")?;
+    }
+    for (flag, flag_name) in DUMP_FLAGS {
+        if flags.contains(flag) {
+            writeln!(writer, "{}=1", flag_name)?;
+        }
+    }
+    writeln!(writer, "db_pagesize={}", stat.ms_psize)?;
+    writeln!(writer, "HEADER=END")?;
+
+    let mut line = Vec::new();
+    let mut cursor = RoCursor::new(rtxn, dbi)?;
+    while let Some((key, data)) = cursor.move_on_next(MoveOperation::Any)? {
+        if skip(key) {
+            continue;
+        }
+        write_hex_line(&mut line, key);
+        write_hex_line(&mut line, data);
+        writer.write_all(&line)?;
+        line.clear();
+    }
+
+    writeln!(writer, "DATA=END")?;
+
+    Ok(())
+}
+
+fn write_hex_line(line: &mut Vec<u8>, bytes: &[u8]) {
+    line.reserve(bytes.len() * 2 + 2);
+    line.push(b' ');
+    for byte in bytes {
+        line.push(HEX_CHARS[(byte >> 4) as usize]);
+        line.push(HEX_CHARS[(byte & 0x0f) as usize]);
+    }
+    line.push(b'\n');
+}
+
+/// The header of a database found in a dump.
+#[derive(Debug)]
+pub(crate) struct DumpHeader {
+    /// The name of the database, `None` for the unnamed one.
+    pub name: Option<String>,
+    /// The flags the database must be created with.
+    pub flags: AllDatabaseFlags,
+    /// Whether the entries are written in the printable format.
+    printable: bool,
+}
+
+/// Reads the databases sections of a dump in the `mdb_dump` format.
+pub(crate) struct DumpReader<R> {
+    reader: R,
+    line: Vec<u8>,
+    line_number: usize,
+}
+
+impl<R: BufRead> DumpReader<R> {
+    pub fn new(reader: R) -> DumpReader<R> {
+        DumpReader { reader, line: Vec::new(), line_number: 0 }
+    }
+
+    /// Reads the next database header, returns `None` when the dump is over.
+    pub fn next_header(&mut self) -> Result<Option<DumpHeader>> {
+        let mut header =
+            DumpHeader { name: None, flags: AllDatabaseFlags::empty(), printable: false };
+        let mut found_any = false;
+
+        loop {
+            if !self.read_line()? {
+                return if found_any {
+                    Err(self.invalid("unexpected end of header"))
+                } else {
+                    Ok(None)
+                };
+            }
+
+            // We skip the empty lines between two databases sections.
+            if self.line.is_empty() && !found_any {
+                continue;
+            }
+            found_any = true;
+
+            let line = std::str::from_utf8(&self.line)
+                .map_err(|_| self.invalid("header lines must be valid UTF-8"))?;
+            if line == "HEADER=END" {
+                return Ok(Some(header));
+            }
+
+            let (keyword, value) =
+                line.split_once('=').ok_or_else(|| self.invalid("malformed header line"))?;
+            match keyword {
+                "VERSION" => match value.parse::<u32>() {
+                    Ok(version) if version <= DUMP_VERSION => (),
+                    _ => return Err(self.invalid(format!("unsupported version {}", value))),
+                },
+                "format" => match value {
+                    "bytevalue" => header.printable = false,
+                    "print" => header.printable = true,
+                    _ => return Err(self.invalid(format!("unsupported format {}", value))),
+                },
+                "type" if value != "btree" => {
+                    return Err(self.invalid(format!("unsupported type {}", value)))
+                }
+                "database" if value.contains('\0') => {
+                    return Err(self.invalid("database names cannot contain NUL bytes"))
+                }
+                "database" => header.name = Some(value.to_string()),
+                keyword => {
+                    if let Some((flag, _)) = DUMP_FLAGS.iter().find(|(_, name)| *name == keyword) {
+                        if value == "1" {
+                            header.flags |= *flag;
+                        }
+                    }
+                    // Other keywords (mapsize, maxreaders, db_pagesize...) describe the
+                    // environment the dump comes from and are ignored when loading it.
+                }
+            }
+        }
+    }
+
+    /// Reads the next key/data pair of the current database, returns `None` at the end of it.
+    pub fn next_entry(&mut self, header: &DumpHeader) -> Result<Option<(Vec<u8>, Vec<u8>)>> {
+        let key = match self.next_value(header)? {
+            Some(key) => key,
+            None => return Ok(None),
+        };
+        match self.next_value(header)? {
+            Some(data) => Ok(Some((key, data))),
+            None => Err(self.invalid("missing data for key")),
+        }
+    }
+
+    fn next_value(&mut self, header: &DumpHeader) -> Result<Option<Vec<u8>>> {
+        if !self.read_line()? {
+            return Err(self.invalid("unexpected end of input"));
+        }
+
+        match self.line.split_first() {
+            Some((b' ', value)) if header.printable => unprintable(value).map(Some),
+            Some((b' ', value)) => unhex(value).map(Some).ok_or(()),
+            _ if self.line == b"DATA=END" => return Ok(None),
+            _ => Err(()),
+        }
+        .map_err(|()| self.invalid("malformed data line"))
+    }
+
+    /// Reads a line without its line feed, returns `false` if the input is exhausted.
+    fn read_line(&mut self) -> Result<bool> {
+        self.line.clear();
+        if self.reader.read_until(b'\n', &mut self.line)? == 0 {
+            return Ok(false);
+        }
+        self.line_number += 1;
+        if self.line.last() == Some(&b'\n') {
+            self.line.pop();
+        }
+        Ok(true)
+    }
+
+    fn invalid<S: AsRef<str>>(&self, message: S) -> Error {
+        let message = format!("line {}: {}", self.line_number, message.as_ref());
+        Error::Io(io::Error::new(io::ErrorKind::InvalidData, message))
+    }
+}
+
+/// Formats the given bytes in lowercase hexadecimal, like the entries of a dump.
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        hex.push(char::from(HEX_CHARS[(byte >> 4) as usize]));
+        hex.push(char::from(HEX_CHARS[(byte & 0x0f) as usize]));
+    }
+    hex
+}
+
+/// Parses bytes written in hexadecimal, in lowercase or uppercase.
+///
+/// Returns `None` if the input is not made of pairs of hexadecimal digits.
+fn unhex(hex: &[u8]) -> Option<Vec<u8>> {
+    let pairs = hex.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return None;
+    }
+    pairs.map(|pair| Some(hex_value(pair[0]).ok()? << 4 | hex_value(pair[1]).ok()?)).collect()
+}
+
+fn hex_value(c: u8) -> std::result::Result<u8, ()> {
+    match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => Err(()),
+    }
+}
+
+fn unprintable(line: &[u8]) -> std::result::Result<Vec<u8>, ()> {
+    let mut bytes = Vec::with_capacity(line.len());
+    let mut iter = line.iter().copied();
+    while let Some(c) = iter.next() {
+        if c != b'\\' {
+            bytes.push(c);
+            continue;
+        }
+        match iter.next() {
+            Some(b'\\') => bytes.push(b'\\'),
+            Some(high) => {
+                let low = iter.next().ok_or(())?;
+                bytes.push(hex_value(high)? << 4 | hex_value(low)?);
+            }
+            None => return Err(()),
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unhex_lines() {
+        assert_eq!(unhex(b""), Some(vec![]));
+        assert_eq!(unhex(b"00ff10Ab"), Some(vec![0x00, 0xff, 0x10, 0xab]));
+        assert_eq!(unhex(b"0"), None);
+        assert_eq!(unhex(b"zz"), None);
+    }
+
+    #[test]
+    fn unprintable_lines() {
+        assert_eq!(unprintable(b"hello"), Ok(b"hello".to_vec()));
+        assert_eq!(unprintable(b"a\\\\b\\00"), Ok(b"a\\b\0".to_vec()));
+        assert_eq!(unprintable(b"trailing\\"), Err(()));
+    }
+
+    #[test]
+    fn hex_roundtrip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let mut line = Vec::new();
+        write_hex_line(&mut line, &bytes);
+        assert_eq!(line.first(), Some(&b' '));
+        assert_eq!(line.last(), Some(&b'\n'));
+        assert_eq!(unhex(&line[1..line.len() - 1]).as_ref(), Some(&bytes));
+        assert_eq!(hex(&bytes).as_bytes(), &line[1..line.len() - 1]);
+    }
+}
@@ -0,0 +1,162 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::process::abort;
+use std::ptr;
+use std::sync::atomic::{self, AtomicPtr};
+use std::sync::{Arc, Mutex};
+
+use once_cell::sync::Lazy;
+
+use crate::mdb::ffi;
+#[cfg(doc)]
+use crate::{DatabaseOpenOptions, Error};
+
+/// A key comparator chosen at runtime, e.g. built from a schema file.
+///
+/// Unlike a [`Comparator`](heed_traits::Comparator), it is an object that can hold a state.
+/// It is given to [`DatabaseOpenOptions::dyn_key_comparator`] and is implemented for
+/// the closures comparing two keys.
+///
+/// Like the static comparators, it must order the keys the same way every time the
+/// database is opened and a panic while comparing the keys aborts the process.
+pub trait DynComparator: Send + Sync {
+    /// Compares the raw bytes representation of two keys.
+    fn compare(&self, a: &[u8], b: &[u8]) -> Ordering;
+}
+
+impl<F> DynComparator for F
+where
+    F: Fn(&[u8], &[u8]) -> Ordering + Send + Sync,
+{
+    fn compare(&self, a: &[u8], b: &[u8]) -> Ordering {
+        self(a, b)
+    }
+}
+
+/// The comparator type of the databases opened with a [`DynComparator`].
+///
+/// The keys of these databases are compared by the [`DynComparator`] registered when the
+/// database was opened, this type only marks them and cannot compare keys by itself.
+/// It is not a lexicographic comparator, the prefix iterators cannot be used with these
+/// databases.
+pub enum RuntimeComparator {}
+
+/// The maximum number of databases that can use a runtime comparator at the same time.
+pub(crate) const MAX_DYN_COMPARATORS: usize = 64;
+
+/// The comparators used by the trampolines, a trampoline is dedicated to each slot.
+///
+/// A slot points to a boxed comparator, it is only written when LMDB cannot call its
+/// trampoline: when the database is opened, before `mdb_set_compare`, and once its
+/// handle is closed. The trampolines can therefore read it without locking.
+static SLOTS: [AtomicPtr<Arc<dyn DynComparator>>; MAX_DYN_COMPARATORS] =
+    [EMPTY_SLOT; MAX_DYN_COMPARATORS];
+
+/// A slot without comparator, only used to initialize the slots.
+#[allow(clippy::declare_interior_mutable_const)]
+const EMPTY_SLOT: AtomicPtr<Arc<dyn DynComparator>> = AtomicPtr::new(ptr::null_mut());
+
+/// The slot of each database, identified by its environment and dbi, using a runtime comparator.
+static REGISTRY: Lazy<Mutex<HashMap<(usize, ffi::MDB_dbi), usize>>> = Lazy::new(Mutex::default);
+
+/// LMDB calls the comparators with the keys only. A trampoline is monomorphized for each
+/// slot, the slot tells it which comparator of the registry to call.
+///
+/// # Safety
+///
+/// `a` and `b` must point to valid [`MDB_val`][ffi::MDB_val]s,
+/// like for the `custom_key_cmp_wrapper` of the static comparators.
+unsafe extern "C" fn dyn_key_cmp_trampoline<const SLOT: usize>(
+    a: *const ffi::MDB_val,
+    b: *const ffi::MDB_val,
+) -> i32 {
+    let a = unsafe { ffi::from_val(*a) };
+    let b = unsafe { ffi::from_val(*b) };
+    // safety: the slot is set before the trampoline is given to LMDB
+    //         and only released once LMDB can no longer call it.
+    let comparator = match unsafe { SLOTS[SLOT].load(atomic::Ordering::Acquire).as_ref() } {
+        Some(comparator) => comparator,
+        None => abort(),
+    };
+    // The process is aborted on panic, the comparator is never observed after unwinding.
+    match catch_unwind(AssertUnwindSafe(|| comparator.compare(a, b))) {
+        Ok(Ordering::Less) => -1,
+        Ok(Ordering::Equal) => 0,
+        Ok(Ordering::Greater) => 1,
+        Err(_) => abort(),
+    }
+}
+
+type Trampoline = unsafe extern "C" fn(*const ffi::MDB_val, *const ffi::MDB_val) -> i32;
+
+macro_rules! trampolines {
+    ($($slot:literal)*) => { [$(dyn_key_cmp_trampoline::<$slot> as Trampoline),*] };
+}
+
+static TRAMPOLINES: [Trampoline; MAX_DYN_COMPARATORS] = trampolines!(
+    0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31
+    32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63
+);
+
+/// Returns the trampoline of a database already registered with a runtime comparator.
+///
+/// Opening the same database again keeps its registered comparator: other transactions
+/// can be comparing keys with it and the order of the keys cannot change anyway.
+pub(crate) fn registered(env: *mut ffi::MDB_env, dbi: ffi::MDB_dbi) -> Option<Trampoline> {
+    REGISTRY.lock().unwrap().get(&(env as usize, dbi)).map(|&slot| TRAMPOLINES[slot])
+}
+
+/// Registers the comparator of a database newly opened by a transaction and returns
+/// the trampoline to give to LMDB, or `None` if all the slots are used.
+pub(crate) fn register(
+    env: *mut ffi::MDB_env,
+    dbi: ffi::MDB_dbi,
+    comparator: Arc<dyn DynComparator>,
+) -> Option<Trampoline> {
+    let mut registry = REGISTRY.lock().unwrap();
+    if let Some(&slot) = registry.get(&(env as usize, dbi)) {
+        return Some(TRAMPOLINES[slot]);
+    }
+    let slot = (0..MAX_DYN_COMPARATORS).find(|slot| !registry.values().any(|s| s == slot))?;
+    registry.insert((env as usize, dbi), slot);
+    let comparator = Box::into_raw(Box::new(comparator));
+    SLOTS[slot].store(comparator, atomic::Ordering::Release);
+    Some(TRAMPOLINES[slot])
+}
+
+/// Releases the slots of the given databases of an environment, once their handles are
+/// closed, e.g. because the transaction that opened them was aborted.
+pub(crate) fn release(env: *mut ffi::MDB_env, dbis: &[ffi::MDB_dbi]) {
+    if dbis.is_empty() {
+        return;
+    }
+    let mut registry = REGISTRY.lock().unwrap();
+    for &dbi in dbis {
+        if let Some(slot) = registry.remove(&(env as usize, dbi)) {
+            clear_slot(slot);
+        }
+    }
+}
+
+/// Releases the slots of the databases of an environment, once it is closed.
+pub(crate) fn release_env(env: *mut ffi::MDB_env) {
+    let mut registry = REGISTRY.lock().unwrap();
+    registry.retain(|&(e, _), &mut slot| {
+        let retain = e != env as usize;
+        if !retain {
+            clear_slot(slot);
+        }
+        retain
+    });
+}
+
+/// Empties a slot and drops its comparator, LMDB must no longer be able to call its trampoline.
+fn clear_slot(slot: usize) {
+    let comparator = SLOTS[slot].swap(ptr::null_mut(), atomic::Ordering::AcqRel);
+    if !comparator.is_null() {
+        // safety: the pointer comes from `Box::into_raw` in `register`
+        //         and no trampoline can be reading it anymore.
+        drop(unsafe { Box::from_raw(comparator) });
+    }
+}
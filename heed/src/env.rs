@@ -1,9 +1,11 @@
 use std::any::TypeId;
 use std::cmp::Ordering;
 use std::collections::hash_map::{Entry, HashMap};
+use std::collections::HashSet;
 use std::ffi::{c_void, CString};
 use std::fs::{File, Metadata};
 use std::io::ErrorKind::NotFound;
+use std::io::Write;
 #[cfg(unix)]
 use std::os::unix::{
     ffi::OsStrExt,
@@ -21,16 +23,21 @@ use std::{
 };
 use std::{fmt, io, mem, ptr};
 
-use heed_traits::{Comparator, LexicographicComparator};
+use heed_traits::Comparator;
 use once_cell::sync::Lazy;
 use synchronoise::event::SignalEvent;
 
+use crate::comparator::DefaultComparator;
 use crate::cursor::MoveOperation;
-use crate::database::DatabaseOpenOptions;
+use crate::database::{DatabaseOpenOptions, IntegerCodec, IntegerKeyDatabase};
+use crate::dyn_comparator::{self, DynComparator};
 use crate::mdb::error::mdb_result;
 use crate::mdb::ffi;
 use crate::mdb::lmdb_flags::AllDatabaseFlags;
-use crate::{Database, EnvFlags, Error, Result, RoCursor, RoTxn, RwTxn, Unspecified};
+use crate::{
+    assert_eq_env_txn, dump, verify, AnomalyKind, Database, EnvFlags, Error, ErrorContext,
+    MdbError, Operation, Result, RoCursor, RoTxn, RwTxn, Unspecified, VerifyReport,
+};
 
 /// The list of opened environments, the value is an optional environment, it is None
 /// when someone asks to close the environment, closing is a two-phase step, to make sure
@@ -108,6 +115,8 @@ pub struct EnvOpenOptions {
     max_readers: Option<u32>,
     max_dbs: Option<u32>,
     flags: EnvFlags,
+    #[cfg_attr(feature = "serde", serde(default))]
+    error_context: bool,
 }
 
 impl Default for EnvOpenOptions {
@@ -124,6 +133,7 @@ impl EnvOpenOptions {
             max_readers: None,
             max_dbs: None,
             flags: EnvFlags::empty(),
+            error_context: false,
         }
     }
 
@@ -145,6 +155,43 @@ impl EnvOpenOptions {
         self
     }
 
+    /// Attach an [`ErrorContext`] to the errors returned by the [`Database`] methods.
+    ///
+    /// The context describes the name of the database, the operation that failed and
+    /// the key involved, the errors are wrapped in an [`Error::WithContext`] variant.
+    /// Use [`Error::without_context`] to match on the underlying error. Disabled by default.
+    ///
+    /// ```
+    /// use heed::{EnvOpenOptions, Error, MdbError, Operation};
+    /// use heed::types::*;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let dir = tempfile::tempdir()?;
+    /// let env = unsafe {
+    ///     EnvOpenOptions::new()
+    ///         .max_dbs(10)
+    ///         .error_context(true)
+    ///         .open(dir.path())?
+    /// };
+    ///
+    /// let mut wtxn = env.write_txn()?;
+    /// let db = env.create_database::<Bytes, Str>(&mut wtxn, Some("books"))?;
+    ///
+    /// let empty_key: &[u8] = &[];
+    /// let error = db.put(&mut wtxn, empty_key, "empty").unwrap_err();
+    /// assert!(matches!(error.without_context(), Error::Mdb(MdbError::BadValSize)));
+    ///
+    /// let context = error.context().unwrap();
+    /// assert_eq!(context.database.as_deref(), Some("books"));
+    /// assert_eq!(context.operation, Operation::Put);
+    /// assert_eq!(context.key_len, Some(0));
+    /// # Ok(()) }
+    /// ```
+    pub fn error_context(&mut self, enabled: bool) -> &mut Self {
+        self.error_context = enabled;
+        self
+    }
+
     /// Set one or more [LMDB flags](http://www.lmdb.tech/doc/group__mdb__env.html).
     /// ```
     /// use std::fs;
@@ -307,7 +354,13 @@ impl EnvOpenOptions {
                     match result {
                         Ok(()) => {
                             let signal_event = Arc::new(SignalEvent::manual(false));
-                            let inner = EnvInner { env, path: path.clone() };
+                            let inner = EnvInner {
+                                env,
+                                path: path.clone(),
+                                error_context: self.error_context,
+                                database_names: RwLock::default(),
+                                opened_dbis: RwLock::default(),
+                            };
                             let env = Env(Arc::new(inner));
                             let cache_entry = EnvEntry {
                                 env: Some(env.clone()),
@@ -340,7 +393,7 @@ pub struct Env(Arc<EnvInner>);
 
 impl fmt::Debug for Env {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let EnvInner { env: _, path } = self.0.as_ref();
+        let EnvInner { path, .. } = self.0.as_ref();
         f.debug_struct("Env").field("path", &path.display()).finish_non_exhaustive()
     }
 }
@@ -348,6 +401,13 @@ impl fmt::Debug for Env {
 struct EnvInner {
     env: *mut ffi::MDB_env,
     path: PathBuf,
+    /// Whether to attach an [`ErrorContext`] to the errors.
+    error_context: bool,
+    /// The names of the opened databases, only filled when `error_context` is enabled.
+    database_names: RwLock<HashMap<ffi::MDB_dbi, String>>,
+    /// The databases opened by committed transactions, LMDB keeps them open
+    /// until the environment is closed.
+    opened_dbis: RwLock<HashSet<ffi::MDB_dbi>>,
 }
 
 unsafe impl Send for EnvInner {}
@@ -364,6 +424,7 @@ impl Drop for EnvInner {
                 unsafe {
                     ffi::mdb_env_close(self.env);
                 }
+                dyn_comparator::release_env(self.env);
                 // We signal to all the waiters that the env is closed now.
                 signal_event.signal();
             }
@@ -393,58 +454,6 @@ unsafe extern "C" fn custom_key_cmp_wrapper<C: Comparator>(
     }
 }
 
-/// A representation of LMDB's default comparator behavior.
-///
-/// This enum is used to indicate the absence of a custom comparator for an LMDB
-/// database instance. When a [`Database`] is created or opened with
-/// [`DefaultComparator`], it signifies that the comparator should not be explicitly
-/// set via [`ffi::mdb_set_compare`]. Consequently, the database
-/// instance utilizes LMDB's built-in default comparator, which inherently performs
-/// lexicographic comparison of keys.
-///
-/// This comparator's lexicographic implementation is employed in scenarios involving
-/// prefix iterators. Specifically, methods other than [`Comparator::compare`] are utilized
-/// to determine the lexicographic successors and predecessors of byte sequences, which
-/// is essential for these iterators' operation.
-///
-/// When a custom comparator is provided, the wrapper is responsible for setting
-/// it with the [`ffi::mdb_set_compare`] function, which overrides the default comparison
-/// behavior of LMDB with the user-defined logic.
-pub enum DefaultComparator {}
-
-impl LexicographicComparator for DefaultComparator {
-    #[inline]
-    fn compare_elem(a: u8, b: u8) -> Ordering {
-        a.cmp(&b)
-    }
-
-    #[inline]
-    fn successor(elem: u8) -> Option<u8> {
-        match elem {
-            u8::MAX => None,
-            elem => Some(elem + 1),
-        }
-    }
-
-    #[inline]
-    fn predecessor(elem: u8) -> Option<u8> {
-        match elem {
-            u8::MIN => None,
-            elem => Some(elem - 1),
-        }
-    }
-
-    #[inline]
-    fn max_elem() -> u8 {
-        u8::MAX
-    }
-
-    #[inline]
-    fn min_elem() -> u8 {
-        u8::MIN
-    }
-}
-
 /// Whether to perform compaction while copying an environment.
 #[derive(Debug, Copy, Clone)]
 pub enum CompactionOption {
@@ -484,6 +493,18 @@ impl Env {
         self.0.env
     }
 
+    /// Whether the database was opened by a committed transaction or is the unnamed one.
+    pub(crate) fn is_dbi_opened(&self, dbi: ffi::MDB_dbi) -> bool {
+        dbi == ffi::MAIN_DBI || self.0.opened_dbis.read().unwrap().contains(&dbi)
+    }
+
+    /// Records the databases opened by a transaction that was just committed.
+    pub(crate) fn commit_opened_dbis(&self, dbis: &mut Vec<ffi::MDB_dbi>) {
+        if !dbis.is_empty() {
+            self.0.opened_dbis.write().unwrap().extend(dbis.drain(..));
+        }
+    }
+
     /// The size of the data file on disk.
     ///
     /// # Example
@@ -605,7 +626,7 @@ impl Env {
 
         let rtxn = self.read_txn()?;
         // Open the main database
-        let dbi = self.raw_open_dbi::<DefaultComparator>(rtxn.txn, None, 0)?;
+        let dbi = self.raw_open_dbi::<DefaultComparator, DefaultComparator>(&rtxn, None, 0)?;
 
         // We're going to iterate on the unnamed database
         let mut cursor = RoCursor::new(&rtxn, dbi)?;
@@ -618,7 +639,9 @@ impl Env {
             let key = String::from_utf8(key.to_vec()).unwrap();
             // Calling `ffi::db_stat` on a database instance does not involve key comparison
             // in LMDB, so it's safe to specify a noop key compare function for it.
-            if let Ok(dbi) = self.raw_open_dbi::<DefaultComparator>(rtxn.txn, Some(&key), 0) {
+            if let Ok(dbi) =
+                self.raw_open_dbi::<DefaultComparator, DefaultComparator>(&rtxn, Some(&key), 0)
+            {
                 let mut stat = mem::MaybeUninit::uninit();
                 unsafe { mdb_result(ffi::mdb_stat(rtxn.txn, dbi, stat.as_mut_ptr()))? };
                 let stat = unsafe { stat.assume_init() };
@@ -630,7 +653,7 @@ impl Env {
     }
 
     /// Options and flags which can be used to configure how a [`Database`] is opened.
-    pub fn database_options(&self) -> DatabaseOpenOptions<Unspecified, Unspecified> {
+    pub fn database_options(&self) -> DatabaseOpenOptions<'_, '_, Unspecified, Unspecified> {
         DatabaseOpenOptions::new(self)
     }
 
@@ -693,24 +716,139 @@ impl Env {
         options.create(wtxn)
     }
 
-    pub(crate) fn raw_init_database<C: Comparator + 'static>(
+    /// Opens a database whose keys are native integers that already exists in this environment.
+    ///
+    /// The database must have been created with the [`DatabaseFlags::INTEGER_KEY`] flag,
+    /// e.g. by [`Env::create_integer_key_database`].
+    ///
+    /// [`DatabaseFlags::INTEGER_KEY`]: crate::DatabaseFlags::INTEGER_KEY
+    pub fn open_integer_key_database<KC, DC>(
+        &self,
+        rtxn: &RoTxn,
+        name: Option<&str>,
+    ) -> Result<Option<IntegerKeyDatabase<KC, DC>>>
+    where
+        KC: IntegerCodec + 'static,
+        DC: 'static,
+    {
+        let mut options = self.database_options().types::<KC, DC>();
+        if let Some(name) = name {
+            options.name(name);
+        }
+        Ok(options.integer_key().open(rtxn)?.map(IntegerKeyDatabase::new))
+    }
+
+    /// Creates a database whose keys are native integers ordered numerically by LMDB.
+    ///
+    /// The database is created with the [`DatabaseFlags::INTEGER_KEY`] flag and only accepts
+    /// the key codecs that encode the integers as LMDB expects, like [`NativeU32Key`].
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # use heed::EnvOpenOptions;
+    /// use heed::types::*;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let dir = tempfile::tempdir()?;
+    /// # let env = unsafe { EnvOpenOptions::new().open(dir.path())? };
+    /// let mut wtxn = env.write_txn()?;
+    /// let db = env.create_integer_key_database::<NativeU32Key, Str>(&mut wtxn, None)?;
+    /// db.put(&mut wtxn, &256, "two hundred fifty-six")?;
+    /// db.put(&mut wtxn, &1, "one")?;
+    ///
+    /// assert_eq!(db.first(&wtxn)?, Some((1, "one")));
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// The other integer codecs are rejected at compile time:
+    ///
+    /// ```compile_fail
+    /// # use std::error::Error;
+    /// # use heed::EnvOpenOptions;
+    /// use heed::byteorder::BigEndian;
+    /// use heed::types::*;
+    ///
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let dir = tempfile::tempdir()?;
+    /// # let env = unsafe { EnvOpenOptions::new().open(dir.path())? };
+    /// let mut wtxn = env.write_txn()?;
+    /// let db = env.create_integer_key_database::<U64<BigEndian>, Str>(&mut wtxn, None)?;
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// [`DatabaseFlags::INTEGER_KEY`]: crate::DatabaseFlags::INTEGER_KEY
+    /// [`NativeU32Key`]: crate::types::NativeU32Key
+    pub fn create_integer_key_database<KC, DC>(
+        &self,
+        wtxn: &mut RwTxn,
+        name: Option<&str>,
+    ) -> Result<IntegerKeyDatabase<KC, DC>>
+    where
+        KC: IntegerCodec + 'static,
+        DC: 'static,
+    {
+        let mut options = self.database_options().types::<KC, DC>();
+        if let Some(name) = name {
+            options.name(name);
+        }
+        options.integer_key().create(wtxn).map(IntegerKeyDatabase::new)
+    }
+
+    pub(crate) fn raw_init_database<C: Comparator + 'static, CDUP: Comparator + 'static>(
         &self,
-        raw_txn: *mut ffi::MDB_txn,
+        txn: &RoTxn,
         name: Option<&str>,
         flags: AllDatabaseFlags,
     ) -> Result<u32> {
-        match self.raw_open_dbi::<C>(raw_txn, name, flags.bits()) {
+        match self.raw_open_dbi::<C, CDUP>(txn, name, flags.bits()) {
             Ok(dbi) => Ok(dbi),
             Err(e) => Err(e.into()),
         }
     }
 
-    fn raw_open_dbi<C: Comparator + 'static>(
+    /// Opens a database whose keys are compared by a runtime comparator, registered
+    /// until the environment is closed or the transaction that opened it is aborted.
+    ///
+    /// Returns an [`MdbError::Incompatible`] error if the database is already
+    /// opened without a runtime comparator.
+    pub(crate) fn raw_init_dyn_database<CDUP: Comparator + 'static>(
+        &self,
+        txn: &RoTxn,
+        name: Option<&str>,
+        flags: AllDatabaseFlags,
+        comparator: Arc<dyn DynComparator>,
+    ) -> Result<u32> {
+        let (dbi, new) =
+            self.raw_open_new_dbi::<DefaultComparator, CDUP>(txn, name, flags.bits())?;
+        let trampoline = match dyn_comparator::registered(self.env_mut_ptr(), dbi) {
+            Some(trampoline) => trampoline,
+            // LMDB already compares the keys of this database with another comparator.
+            None if !new => return Err(Error::Mdb(MdbError::Incompatible)),
+            None => dyn_comparator::register(self.env_mut_ptr(), dbi, comparator)
+                .ok_or(Error::DynComparatorsFull)?,
+        };
+        unsafe { mdb_result(ffi::mdb_set_compare(txn.txn, dbi, Some(trampoline)))? };
+        Ok(dbi)
+    }
+
+    fn raw_open_dbi<C: Comparator + 'static, CDUP: Comparator + 'static>(
         &self,
-        raw_txn: *mut ffi::MDB_txn,
+        txn: &RoTxn,
         name: Option<&str>,
         flags: u32,
     ) -> std::result::Result<u32, crate::mdb::lmdb_error::Error> {
+        self.raw_open_new_dbi::<C, CDUP>(txn, name, flags).map(|(dbi, _)| dbi)
+    }
+
+    /// Opens a database and returns whether it was not opened by a committed
+    /// or a parent transaction yet, LMDB closes it if the transaction is aborted.
+    fn raw_open_new_dbi<C: Comparator + 'static, CDUP: Comparator + 'static>(
+        &self,
+        txn: &RoTxn,
+        name: Option<&str>,
+        flags: u32,
+    ) -> std::result::Result<(u32, bool), crate::mdb::lmdb_error::Error> {
+        let raw_txn = txn.txn;
         let mut dbi = 0;
         let name = name.map(|n| CString::new(n).unwrap());
         let name_ptr = match name {
@@ -725,9 +863,39 @@ impl Env {
             if TypeId::of::<C>() != TypeId::of::<DefaultComparator>() {
                 mdb_result(ffi::mdb_set_compare(raw_txn, dbi, Some(custom_key_cmp_wrapper::<C>)))?;
             }
+            if TypeId::of::<CDUP>() != TypeId::of::<DefaultComparator>() {
+                mdb_result(ffi::mdb_set_dupsort(
+                    raw_txn,
+                    dbi,
+                    Some(custom_key_cmp_wrapper::<CDUP>),
+                ))?;
+            }
         };
 
-        Ok(dbi)
+        if let (true, Some(name)) = (self.0.error_context, name) {
+            let name = name.into_string().unwrap();
+            self.0.database_names.write().unwrap().insert(dbi, name);
+        }
+
+        Ok((dbi, txn.record_opened_dbi(dbi)))
+    }
+
+    /// Attaches the context of a failed database operation to the error,
+    /// if enabled with [`EnvOpenOptions::error_context`].
+    pub(crate) fn error_with_context<E>(
+        &self,
+        dbi: ffi::MDB_dbi,
+        operation: Operation,
+        key: Option<&[u8]>,
+        error: Error<E>,
+    ) -> Error<E> {
+        if !self.0.error_context || error.context().is_some() {
+            return error;
+        }
+
+        let database = self.0.database_names.read().unwrap().get(&dbi).cloned();
+        let context = ErrorContext::new(database, operation, key);
+        Error::WithContext { error: Box::new(error), context: Box::new(context) }
     }
 
     /// Create a transaction with read and write access for use with the environment.
@@ -738,7 +906,7 @@ impl Env {
     /// If another write transaction is initiated, while another write transaction exists
     /// the thread initiating the new one will wait on a mutex upon completion of the previous
     /// transaction.
-    pub fn write_txn(&self) -> Result<RwTxn> {
+    pub fn write_txn(&self) -> Result<RwTxn<'_>> {
         RwTxn::new(self)
     }
 
@@ -777,7 +945,7 @@ impl Env {
     ///   map must be resized
     /// * [`crate::MdbError::ReadersFull`]: a read-only transaction was requested, and the reader lock table is
     ///   full
-    pub fn read_txn(&self) -> Result<RoTxn> {
+    pub fn read_txn(&self) -> Result<RoTxn<'_>> {
         RoTxn::new(self)
     }
 
@@ -845,6 +1013,276 @@ impl Env {
         Ok(())
     }
 
+    /// Returns the names of all the named databases of this environment.
+    ///
+    /// The names are read from the keys of the unnamed database, the keys that do not
+    /// correspond to a named database are ignored.
+    ///
+    /// It is crucial to configure [`EnvOpenOptions::max_dbs`] with a sufficiently large value
+    /// before invoking this function. All databases within the environment will be opened.
+    pub fn database_names(&self, rtxn: &RoTxn) -> Result<Vec<String>> {
+        assert_eq_env_txn!(self, rtxn);
+        let names = self.open_database_names(rtxn)?;
+        Ok(names.into_iter().map(|(name, _)| name).collect())
+    }
+
+    /// Opens all the named databases and returns their names along with
+    /// whether they were not opened by this or a committed transaction before.
+    fn open_database_names(&self, rtxn: &RoTxn) -> Result<Vec<(String, bool)>> {
+        let dbi = self.raw_open_dbi::<DefaultComparator, DefaultComparator>(rtxn, None, 0)?;
+        let mut cursor = RoCursor::new(rtxn, dbi)?;
+        let mut names = Vec::new();
+
+        while let Some((key, _value)) = cursor.move_on_next(MoveOperation::NoDup)? {
+            if key.contains(&0) {
+                continue;
+            }
+
+            let name = match std::str::from_utf8(key) {
+                Ok(name) => name,
+                Err(_) => continue,
+            };
+
+            match self.raw_open_new_dbi::<DefaultComparator, DefaultComparator>(rtxn, Some(name), 0)
+            {
+                Ok((_, new)) => names.push((name.to_string(), new)),
+                // This is a regular entry of the unnamed database.
+                Err(crate::mdb::lmdb_error::Error::Incompatible) => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(names)
+    }
+
+    /// Writes all the databases of this environment into the given writer.
+    ///
+    /// The dump is compatible with the text format of the `mdb_dump` and `mdb_load` LMDB tools
+    /// and keeps the [`DatabaseFlags`](crate::DatabaseFlags) of every database. Unlike the
+    /// files produced by [`Env::copy_to_file`] it does not depend on the architecture nor on
+    /// the LMDB build options and can be used to move data between machines.
+    ///
+    /// The unnamed database is only written if it contains entries that are not
+    /// the names of the named databases.
+    ///
+    /// The databases that use a custom comparator must have been opened with it before
+    /// invoking this function, otherwise their entries are written in the lexicographic order.
+    ///
+    /// It is crucial to configure [`EnvOpenOptions::max_dbs`] with a sufficiently large value
+    /// before invoking this function. All databases within the environment will be opened.
+    ///
+    /// ```
+    /// # use std::fs;
+    /// # use std::path::Path;
+    /// # use heed::EnvOpenOptions;
+    /// use heed::types::*;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let dir = tempfile::tempdir()?;
+    /// # let env = unsafe { EnvOpenOptions::new()
+    /// #     .map_size(10 * 1024 * 1024) // 10MB
+    /// #     .max_dbs(3000)
+    /// #     .open(dir.path())?
+    /// # };
+    /// let mut wtxn = env.write_txn()?;
+    /// let db = env.create_database::<Str, Str>(&mut wtxn, Some("colors"))?;
+    /// db.put(&mut wtxn, "red", "#ff0000")?;
+    /// wtxn.commit()?;
+    ///
+    /// let rtxn = env.read_txn()?;
+    /// let mut dump = Vec::new();
+    /// env.export(&rtxn, &mut dump)?;
+    /// drop(rtxn);
+    ///
+    /// # let other_dir = tempfile::tempdir()?;
+    /// # let other_env = unsafe { EnvOpenOptions::new()
+    /// #     .map_size(10 * 1024 * 1024) // 10MB
+    /// #     .max_dbs(3000)
+    /// #     .open(other_dir.path())?
+    /// # };
+    /// let mut wtxn = other_env.write_txn()?;
+    /// other_env.import(&mut wtxn, &dump[..])?;
+    /// let db = other_env.open_database::<Str, Str>(&wtxn, Some("colors"))?.unwrap();
+    /// assert_eq!(db.get(&wtxn, "red")?, Some("#ff0000"));
+    /// wtxn.commit()?;
+    /// # Ok(()) }
+    /// ```
+    pub fn export<W: io::Write>(&self, rtxn: &RoTxn, writer: W) -> Result<()> {
+        let names = self.database_names(rtxn)?;
+
+        let dbi = self.raw_open_dbi::<DefaultComparator, DefaultComparator>(rtxn, None, 0)?;
+        let mut stat = mem::MaybeUninit::uninit();
+        unsafe { mdb_result(ffi::mdb_stat(rtxn.txn, dbi, stat.as_mut_ptr()))? };
+        let stat = unsafe { stat.assume_init() };
+
+        let mut selected: Vec<_> = names.iter().map(|name| Some(name.as_str())).collect();
+        if stat.ms_entries > names.len() {
+            selected.insert(0, None);
+        }
+
+        self.export_databases(rtxn, &selected, writer)
+    }
+
+    /// Writes the given databases of this environment into the given writer.
+    ///
+    /// Use `None` to select the unnamed database, the keys that are the names of
+    /// named databases are not written. See [`Env::export`] for more information
+    /// about the format of the dump.
+    ///
+    /// Returns an [`MdbError::NotFound`](crate::MdbError::NotFound) error if one of
+    /// the databases does not exist.
+    pub fn export_databases<W: io::Write>(
+        &self,
+        rtxn: &RoTxn,
+        names: &[Option<&str>],
+        writer: W,
+    ) -> Result<()> {
+        assert_eq_env_txn!(self, rtxn);
+
+        let mut writer = io::BufWriter::new(writer);
+        let info = self.info();
+
+        // We only need the names of the named databases to skip them in the unnamed one.
+        let database_names =
+            if names.contains(&None) { self.database_names(rtxn)? } else { Vec::new() };
+
+        for name in names {
+            let dbi = self.raw_open_dbi::<DefaultComparator, DefaultComparator>(rtxn, *name, 0)?;
+            match name {
+                Some(_) => dump::write_database(&mut writer, rtxn, &info, dbi, *name, |_| false)?,
+                None => dump::write_database(&mut writer, rtxn, &info, dbi, None, |key| {
+                    database_names.binary_search_by(|name| name.as_bytes().cmp(key)).is_ok()
+                })?,
+            }
+        }
+
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Reads a dump from the given reader and writes its content into this environment.
+    ///
+    /// The dump must be in the text format of the `mdb_dump` LMDB tool, both the `bytevalue`
+    /// and `print` formats are supported. The databases are created with the flags specified
+    /// in the dump if they do not already exist and the entries are inserted into them,
+    /// overwriting the existing values with the same keys.
+    ///
+    /// The databases that use a custom comparator must have been created with it before
+    /// invoking this function, otherwise they are rebuilt in the lexicographic order and
+    /// can no longer be opened with their comparator.
+    ///
+    /// Returns an [`io::ErrorKind::InvalidData`] error if the dump is malformed.
+    pub fn import<R: io::Read>(&self, wtxn: &mut RwTxn, reader: R) -> Result<()> {
+        assert_eq_env_txn!(self, wtxn);
+
+        let mut reader = dump::DumpReader::new(io::BufReader::new(reader));
+
+        while let Some(header) = reader.next_header()? {
+            let flags = header.flags | AllDatabaseFlags::CREATE;
+            let dbi = self.raw_init_database::<DefaultComparator, DefaultComparator>(
+                wtxn,
+                header.name.as_deref(),
+                flags,
+            )?;
+
+            while let Some((key, data)) = reader.next_entry(&header)? {
+                let mut key_val = unsafe { crate::into_val(&key) };
+                let mut data_val = unsafe { crate::into_val(&data) };
+                unsafe {
+                    mdb_result(ffi::mdb_put(wtxn.txn.txn, dbi, &mut key_val, &mut data_val, 0))?
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Traverses every entry of every database of this environment and reports the anomalies.
+    ///
+    /// The keys ordering is checked against the comparator of the databases and the duplicates
+    /// ordering of the `DUP_SORT` databases against their duplicate comparator. When a page
+    /// cannot be read the traversal of the database stops and the error is reported as an
+    /// [`AnomalyKind::Corrupted`](crate::AnomalyKind::Corrupted) anomaly. LMDB then refuses to
+    /// use the transaction so the remaining databases are not traversed.
+    ///
+    /// Only the first `max_anomalies` anomalies are kept in the report, all of them are counted.
+    ///
+    /// The comparator of a database is only known once it has been opened, the databases
+    /// that were not opened before invoking this function, by this transaction or by a
+    /// committed one, are traversed without checking their ordering and their names are
+    /// listed in [`VerifyReport::unordered_databases`]. Open the databases that use a custom
+    /// comparator with it beforehand to check them too.
+    /// Use [`Database::verify`] to also check that the entries can be decoded.
+    ///
+    /// It is crucial to configure [`EnvOpenOptions::max_dbs`] with a sufficiently large value
+    /// before invoking this function. All databases within the environment will be opened.
+    ///
+    /// ```
+    /// # use std::fs;
+    /// # use std::path::Path;
+    /// # use heed::EnvOpenOptions;
+    /// use heed::types::*;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let dir = tempfile::tempdir()?;
+    /// # let env = unsafe { EnvOpenOptions::new()
+    /// #     .map_size(10 * 1024 * 1024) // 10MB
+    /// #     .max_dbs(3000)
+    /// #     .open(dir.path())?
+    /// # };
+    /// let mut wtxn = env.write_txn()?;
+    /// let db = env.create_database::<Str, Str>(&mut wtxn, Some("colors"))?;
+    /// db.put(&mut wtxn, "red", "#ff0000")?;
+    /// db.put(&mut wtxn, "green", "#00ff00")?;
+    /// wtxn.commit()?;
+    ///
+    /// let rtxn = env.read_txn()?;
+    /// let report = env.verify(&rtxn, 100)?;
+    /// assert!(report.is_ok());
+    /// assert_eq!(report.number_of_databases, 2);
+    /// assert_eq!(report.number_of_entries, 3);
+    /// # Ok(()) }
+    /// ```
+    pub fn verify(&self, rtxn: &RoTxn, max_anomalies: usize) -> Result<VerifyReport> {
+        assert_eq_env_txn!(self, rtxn);
+
+        let mut report = VerifyReport::default();
+
+        let dbi = self.raw_open_dbi::<DefaultComparator, DefaultComparator>(rtxn, None, 0)?;
+        let check = |_: &[u8], _: &[u8]| Ok(());
+        if !verify::verify_database(&mut report, max_anomalies, rtxn, dbi, None, true, check)? {
+            return Ok(report);
+        }
+
+        for (name, new) in self.open_database_names(rtxn)? {
+            let dbi = match self.raw_open_dbi::<DefaultComparator, DefaultComparator>(
+                rtxn,
+                Some(&name),
+                0,
+            ) {
+                Ok(dbi) => dbi,
+                Err(e) if verify::is_corruption(e) => {
+                    let kind = AnomalyKind::Corrupted(e);
+                    report.record(max_anomalies, Some(&name), &[], kind);
+                    return Ok(report);
+                }
+                Err(e) => return Err(e.into()),
+            };
+            // LMDB would compare the keys of a database that was not opened
+            // before with the default comparator, whatever its real one is.
+            if new {
+                report.unordered_databases.push(name.clone());
+            }
+            let name = Some(name.as_str());
+            if !verify::verify_database(&mut report, max_anomalies, rtxn, dbi, name, !new, check)? {
+                return Ok(report);
+            }
+        }
+
+        Ok(report)
+    }
+
     /// Flush the data buffers to disk.
     pub fn force_sync(&self) -> Result<()> {
         unsafe { mdb_result(ffi::mdb_env_sync(self.0.env, 1))? }
@@ -974,7 +1412,9 @@ mod tests {
     use std::{fs, thread};
 
     use crate::types::*;
-    use crate::{env_closing_event, EnvOpenOptions, Error};
+    use crate::{
+        env_closing_event, AnomalyKind, DatabaseFlags, EnvOpenOptions, Error, MdbError, Operation,
+    };
 
     #[test]
     fn close_env() {
@@ -1285,4 +1725,356 @@ mod tests {
             assert_eq!(maxkeysize, 511);
         }
     }
+
+    #[test]
+    fn export_import_databases() {
+        let dir = tempfile::tempdir().unwrap();
+        let env = unsafe {
+            EnvOpenOptions::new()
+                .map_size(10 * 1024 * 1024) // 10MB
+                .max_dbs(10)
+                .open(dir.path())
+                .unwrap()
+        };
+
+        let mut wtxn = env.write_txn().unwrap();
+        let main = env.create_database::<Str, Str>(&mut wtxn, None).unwrap();
+        main.put(&mut wtxn, "hello", "world").unwrap();
+        let bytes = env.create_database::<Bytes, Bytes>(&mut wtxn, Some("bytes")).unwrap();
+        bytes.put(&mut wtxn, &[0, 1, 2], &[255, 10, 92]).unwrap();
+        bytes.put(&mut wtxn, &[0], &[]).unwrap();
+        let dups = env
+            .database_options()
+            .types::<Str, Str>()
+            .name("dups")
+            .flags(DatabaseFlags::DUP_SORT | DatabaseFlags::REVERSE_KEY)
+            .create(&mut wtxn)
+            .unwrap();
+        dups.put(&mut wtxn, "colors", "red").unwrap();
+        dups.put(&mut wtxn, "colors", "blue").unwrap();
+        dups.put(&mut wtxn, "shapes", "circle").unwrap();
+        wtxn.commit().unwrap();
+
+        let rtxn = env.read_txn().unwrap();
+        assert_eq!(env.database_names(&rtxn).unwrap(), ["bytes", "dups"]);
+        let mut dump = Vec::new();
+        env.export(&rtxn, &mut dump).unwrap();
+        drop(rtxn);
+
+        let other_dir = tempfile::tempdir().unwrap();
+        let other_env = unsafe {
+            EnvOpenOptions::new()
+                .map_size(10 * 1024 * 1024) // 10MB
+                .max_dbs(10)
+                .open(other_dir.path())
+                .unwrap()
+        };
+
+        let mut wtxn = other_env.write_txn().unwrap();
+        other_env.import(&mut wtxn, &dump[..]).unwrap();
+        wtxn.commit().unwrap();
+
+        let rtxn = other_env.read_txn().unwrap();
+        let main = other_env.open_database::<Str, Str>(&rtxn, None).unwrap().unwrap();
+        assert_eq!(main.get(&rtxn, "hello").unwrap(), Some("world"));
+
+        let bytes = other_env.open_database::<Bytes, Bytes>(&rtxn, Some("bytes")).unwrap().unwrap();
+        assert_eq!(bytes.len(&rtxn).unwrap(), 2);
+        assert_eq!(bytes.get(&rtxn, &[0, 1, 2]).unwrap(), Some(&[255, 10, 92][..]));
+        assert_eq!(bytes.get(&rtxn, &[0]).unwrap(), Some(&[][..]));
+
+        let dups = other_env.open_database::<Str, Str>(&rtxn, Some("dups")).unwrap().unwrap();
+        let values: Vec<_> =
+            dups.get_duplicates(&rtxn, "colors").unwrap().unwrap().map(Result::unwrap).collect();
+        assert_eq!(values, [("colors", "blue"), ("colors", "red")]);
+
+        // Exporting the imported environment gives the same dump back, flags included.
+        let mut other_dump = Vec::new();
+        other_env.export(&rtxn, &mut other_dump).unwrap();
+        let dump = String::from_utf8(dump).unwrap();
+        assert!(dump.contains("database=dups\n"));
+        assert!(dump.contains("duplicates=1\nreversekey=1\ndupsort=1\n"));
+        assert_eq!(dump, String::from_utf8(other_dump).unwrap());
+    }
+
+    #[test]
+    fn export_selected_databases() {
+        let dir = tempfile::tempdir().unwrap();
+        let env = unsafe {
+            EnvOpenOptions::new()
+                .map_size(10 * 1024 * 1024) // 10MB
+                .max_dbs(10)
+                .open(dir.path())
+                .unwrap()
+        };
+
+        let mut wtxn = env.write_txn().unwrap();
+        let first = env.create_database::<Str, Str>(&mut wtxn, Some("first")).unwrap();
+        first.put(&mut wtxn, "a", "b").unwrap();
+        let second = env.create_database::<Str, Str>(&mut wtxn, Some("second")).unwrap();
+        second.put(&mut wtxn, "c", "d").unwrap();
+        wtxn.commit().unwrap();
+
+        let rtxn = env.read_txn().unwrap();
+
+        // The unnamed database only contains the names of the other databases.
+        let mut dump = Vec::new();
+        env.export(&rtxn, &mut dump).unwrap();
+        let dump = String::from_utf8(dump).unwrap();
+        assert_eq!(dump.matches("HEADER=END").count(), 2);
+
+        let mut dump = Vec::new();
+        env.export_databases(&rtxn, &[Some("second")], &mut dump).unwrap();
+        let dump = String::from_utf8(dump).unwrap();
+        assert!(dump.starts_with("VERSION=3\nformat=bytevalue\ndatabase=second\ntype=btree\n"));
+        assert!(dump.ends_with("HEADER=END\n 63\n 64\nDATA=END\n"));
+
+        let result = env.export_databases(&rtxn, &[Some("unknown")], Vec::new());
+        assert!(matches!(result, Err(Error::Mdb(MdbError::NotFound))));
+    }
+
+    #[test]
+    fn import_print_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let env = unsafe {
+            EnvOpenOptions::new()
+                .map_size(10 * 1024 * 1024) // 10MB
+                .max_dbs(10)
+                .open(dir.path())
+                .unwrap()
+        };
+
+        let dump = "VERSION=3\nformat=print\ndatabase=printed\ntype=btree\nmapsize=1048576\n\
+                    maxreaders=126\ndb_pagesize=4096\nHEADER=END\n hello\n wo\\\\rld\\0a\n\
+                    DATA=END\n";
+
+        let mut wtxn = env.write_txn().unwrap();
+        env.import(&mut wtxn, dump.as_bytes()).unwrap();
+        let db = env.open_database::<Str, Str>(&wtxn, Some("printed")).unwrap().unwrap();
+        assert_eq!(db.get(&wtxn, "hello").unwrap(), Some("wo\\rld\n"));
+        wtxn.abort();
+
+        let mut wtxn = env.write_txn().unwrap();
+        let result = env.import(&mut wtxn, "VERSION=4\nHEADER=END\nDATA=END\n".as_bytes());
+        assert!(matches!(result, Err(Error::Io(e)) if e.kind() == ErrorKind::InvalidData));
+        let result = env.import(&mut wtxn, "VERSION=3\nHEADER=END\n zz\n".as_bytes());
+        assert!(matches!(result, Err(Error::Io(e)) if e.kind() == ErrorKind::InvalidData));
+        let result = env.import(&mut wtxn, "VERSION=3\ndatabase=a\0b\nHEADER=END\n".as_bytes());
+        assert!(matches!(result, Err(Error::Io(e)) if e.kind() == ErrorKind::InvalidData));
+    }
+
+    #[test]
+    fn verify_environment() {
+        enum ReverseComparator {}
+
+        impl crate::Comparator for ReverseComparator {
+            fn compare(a: &[u8], b: &[u8]) -> std::cmp::Ordering {
+                b.cmp(a)
+            }
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let env = unsafe {
+            EnvOpenOptions::new()
+                .map_size(10 * 1024 * 1024) // 10MB
+                .max_dbs(10)
+                .open(dir.path())
+                .unwrap()
+        };
+
+        let mut wtxn = env.write_txn().unwrap();
+        let dups = env
+            .database_options()
+            .types::<Str, Str>()
+            .name("dups")
+            .flags(DatabaseFlags::DUP_SORT)
+            .create(&mut wtxn)
+            .unwrap();
+        dups.put(&mut wtxn, "colors", "red").unwrap();
+        dups.put(&mut wtxn, "colors", "blue").unwrap();
+        let reversed = env
+            .database_options()
+            .types::<Str, Unit>()
+            .key_comparator::<ReverseComparator>()
+            .name("reversed")
+            .create(&mut wtxn)
+            .unwrap();
+        for key in ["a", "b", "c"] {
+            reversed.put(&mut wtxn, key, &()).unwrap();
+        }
+        wtxn.commit().unwrap();
+
+        let rtxn = env.read_txn().unwrap();
+        let report = env.verify(&rtxn, 100).unwrap();
+        assert!(report.is_ok(), "{:?}", report);
+        assert_eq!(report.number_of_databases, 3);
+        assert_eq!(report.number_of_entries, 2 + 2 + 3);
+        drop(rtxn);
+        env.prepare_for_closing().wait();
+
+        // Once reopened, the comparators are unknown until the databases are opened again.
+        let env = unsafe {
+            EnvOpenOptions::new()
+                .map_size(10 * 1024 * 1024) // 10MB
+                .max_dbs(10)
+                .open(dir.path())
+                .unwrap()
+        };
+
+        let rtxn = env.read_txn().unwrap();
+        let report = env.verify(&rtxn, 100).unwrap();
+        assert!(report.is_ok(), "{:?}", report);
+        assert_eq!(report.number_of_databases, 3);
+        assert_eq!(report.number_of_entries, 2 + 2 + 3);
+        assert_eq!(report.unordered_databases, ["dups", "reversed"]);
+        drop(rtxn);
+
+        let wtxn = env.write_txn().unwrap();
+        env.database_options()
+            .types::<Str, Unit>()
+            .key_comparator::<ReverseComparator>()
+            .name("reversed")
+            .open(&wtxn)
+            .unwrap()
+            .unwrap();
+        env.open_database::<Str, Str>(&wtxn, Some("dups")).unwrap().unwrap();
+        wtxn.commit().unwrap();
+
+        let rtxn = env.read_txn().unwrap();
+        let report = env.verify(&rtxn, 100).unwrap();
+        assert!(report.is_ok(), "{:?}", report);
+        assert!(report.unordered_databases.is_empty());
+        drop(rtxn);
+
+        // A database opened with the wrong comparator is reported.
+        let dir = tempfile::tempdir().unwrap();
+        let env = unsafe { EnvOpenOptions::new().max_dbs(10).open(dir.path()).unwrap() };
+        let mut wtxn = env.write_txn().unwrap();
+        let reversed = env
+            .database_options()
+            .types::<Str, Unit>()
+            .key_comparator::<ReverseComparator>()
+            .name("reversed")
+            .create(&mut wtxn)
+            .unwrap();
+        for key in ["a", "b", "c"] {
+            reversed.put(&mut wtxn, key, &()).unwrap();
+        }
+        wtxn.commit().unwrap();
+        env.prepare_for_closing().wait();
+
+        let env = unsafe { EnvOpenOptions::new().max_dbs(10).open(dir.path()).unwrap() };
+        let rtxn = env.read_txn().unwrap();
+        env.open_database::<Str, Unit>(&rtxn, Some("reversed")).unwrap().unwrap();
+        let report = env.verify(&rtxn, 100).unwrap();
+        assert!(report.unordered_databases.is_empty());
+        assert_eq!(report.number_of_anomalies, 2);
+        let anomalies: Vec<_> = report
+            .anomalies
+            .iter()
+            .map(|a| (a.database.as_deref(), a.key.as_slice(), &a.kind))
+            .collect();
+        assert_eq!(
+            anomalies,
+            [
+                (Some("reversed"), &b"b"[..], &AnomalyKind::UnorderedKey),
+                (Some("reversed"), &b"a"[..], &AnomalyKind::UnorderedKey),
+            ]
+        );
+    }
+
+    #[test]
+    fn verify_corrupted_environment() {
+        let dir = tempfile::tempdir().unwrap();
+        let env = unsafe {
+            EnvOpenOptions::new()
+                .map_size(10 * 1024 * 1024) // 10MB
+                .max_dbs(10)
+                .open(dir.path())
+                .unwrap()
+        };
+
+        let mut wtxn = env.write_txn().unwrap();
+        for name in ["broken", "intact"] {
+            let db = env.create_database::<Str, Str>(&mut wtxn, Some(name)).unwrap();
+            db.put(&mut wtxn, "hello", "world").unwrap();
+        }
+        wtxn.commit().unwrap();
+
+        let rtxn = env.read_txn().unwrap();
+        let report = env.verify(&rtxn, 0).unwrap();
+        assert!(report.is_ok(), "{:?}", report);
+        assert_eq!(report.number_of_databases, 3);
+        drop(rtxn);
+        env.prepare_for_closing().wait();
+
+        // The unnamed database stores the root page of the named databases right after
+        // their names, we make the root of the first one point after the end of the file.
+        let path = dir.path().join("data.mdb");
+        let mut file = std::fs::read(&path).unwrap();
+        let root_offset = 4 + 2 + 2 + 4 * std::mem::size_of::<usize>();
+        let mut start = 0;
+        while let Some(position) = file[start..].windows(6).position(|w| w == b"broken") {
+            let root = start + position + 6 + root_offset;
+            file[root..][..std::mem::size_of::<usize>()]
+                .copy_from_slice(&(usize::MAX / 2).to_ne_bytes());
+            start = root;
+        }
+        std::fs::write(&path, file).unwrap();
+
+        let env = unsafe {
+            EnvOpenOptions::new()
+                .map_size(10 * 1024 * 1024) // 10MB
+                .max_dbs(10)
+                .open(dir.path())
+                .unwrap()
+        };
+
+        // The transaction cannot be used after the corruption, the report is still returned.
+        let rtxn = env.read_txn().unwrap();
+        let report = env.verify(&rtxn, 0).unwrap();
+        assert_eq!(report.number_of_databases, 2);
+        assert_eq!(report.number_of_anomalies, 1);
+        assert!(report.anomalies.is_empty());
+        drop(rtxn);
+
+        let rtxn = env.read_txn().unwrap();
+        let report = env.verify(&rtxn, 10).unwrap();
+        let anomaly = &report.anomalies[0];
+        assert_eq!(anomaly.database.as_deref(), Some("broken"));
+        assert_eq!(anomaly.kind, AnomalyKind::Corrupted(MdbError::PageNotFound));
+    }
+
+    #[test]
+    fn error_context_while_iterating() {
+        let dir = tempfile::tempdir().unwrap();
+        let env = unsafe {
+            EnvOpenOptions::new()
+                .map_size(10 * 1024 * 1024) // 10MB
+                .max_dbs(10)
+                .error_context(true)
+                .open(dir.path())
+                .unwrap()
+        };
+
+        let mut wtxn = env.write_txn().unwrap();
+        let db = env.create_database::<Bytes, Bytes>(&mut wtxn, Some("numbers")).unwrap();
+        db.put(&mut wtxn, b"one", &[0, 0, 0, 1]).unwrap();
+        db.put(&mut wtxn, b"two", &[2]).unwrap();
+        wtxn.commit().unwrap();
+
+        let rtxn = env.read_txn().unwrap();
+        let db = db.remap_data_type::<U32<byteorder::BigEndian>>();
+        let mut iter = db.iter(&rtxn).unwrap();
+        assert_eq!(iter.next().unwrap().unwrap(), (&b"one"[..], 1));
+
+        let error = iter.next().unwrap().unwrap_err();
+        assert!(matches!(error.without_context(), Error::Decoding(_)));
+        let context = error.context().unwrap();
+        assert_eq!(context.database.as_deref(), Some("numbers"));
+        assert_eq!(context.operation, Operation::Iteration);
+        assert_eq!(context.key.as_deref(), Some("74776f"));
+        let suffix = " (iteration on database \"numbers\" with key 74776f (3 bytes))";
+        assert!(error.to_string().ends_with(suffix), "{}", error);
+    }
 }
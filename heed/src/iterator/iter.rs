@@ -1,4 +1,3 @@
-use std::borrow::Cow;
 use std::marker;
 
 use types::LazyDecode;
@@ -7,14 +6,14 @@ use crate::iteration_method::{IterationMethod, MoveBetweenKeys, MoveThroughDupli
 use crate::*;
 
 /// A read-only iterator structure.
-pub struct RoIter<'txn, KC, DC, IM = MoveThroughDuplicateValues> {
+pub struct RoIter<'txn, KC, DC, IM = MoveThroughDuplicateValues, E = BoxedError> {
     cursor: RoCursor<'txn>,
     move_on_first: bool,
-    _phantom: marker::PhantomData<(KC, DC, IM)>,
+    _phantom: marker::PhantomData<(KC, DC, IM, E)>,
 }
 
-impl<'txn, KC, DC, IM> RoIter<'txn, KC, DC, IM> {
-    pub(crate) fn new(cursor: RoCursor<'txn>) -> RoIter<'txn, KC, DC, IM> {
+impl<'txn, KC, DC, IM, E> RoIter<'txn, KC, DC, IM, E> {
+    pub(crate) fn new(cursor: RoCursor<'txn>) -> RoIter<'txn, KC, DC, IM, E> {
         RoIter { cursor, move_on_first: true, _phantom: marker::PhantomData }
     }
 
@@ -63,7 +62,7 @@ impl<'txn, KC, DC, IM> RoIter<'txn, KC, DC, IM> {
     /// wtxn.commit()?;
     /// # Ok(()) }
     /// ```
-    pub fn move_between_keys(self) -> RoIter<'txn, KC, DC, MoveBetweenKeys> {
+    pub fn move_between_keys(self) -> RoIter<'txn, KC, DC, MoveBetweenKeys, E> {
         RoIter {
             cursor: self.cursor,
             move_on_first: self.move_on_first,
@@ -119,7 +118,9 @@ impl<'txn, KC, DC, IM> RoIter<'txn, KC, DC, IM> {
     /// wtxn.commit()?;
     /// # Ok(()) }
     /// ```
-    pub fn move_through_duplicate_values(self) -> RoIter<'txn, KC, DC, MoveThroughDuplicateValues> {
+    pub fn move_through_duplicate_values(
+        self,
+    ) -> RoIter<'txn, KC, DC, MoveThroughDuplicateValues, E> {
         RoIter {
             cursor: self.cursor,
             move_on_first: self.move_on_first,
@@ -128,7 +129,7 @@ impl<'txn, KC, DC, IM> RoIter<'txn, KC, DC, IM> {
     }
 
     /// Change the codec types of this iterator, specifying the codecs.
-    pub fn remap_types<KC2, DC2>(self) -> RoIter<'txn, KC2, DC2, IM> {
+    pub fn remap_types<KC2, DC2>(self) -> RoIter<'txn, KC2, DC2, IM, E> {
         RoIter {
             cursor: self.cursor,
             move_on_first: self.move_on_first,
@@ -137,28 +138,30 @@ impl<'txn, KC, DC, IM> RoIter<'txn, KC, DC, IM> {
     }
 
     /// Change the key codec type of this iterator, specifying the new codec.
-    pub fn remap_key_type<KC2>(self) -> RoIter<'txn, KC2, DC, IM> {
+    pub fn remap_key_type<KC2>(self) -> RoIter<'txn, KC2, DC, IM, E> {
         self.remap_types::<KC2, DC>()
     }
 
     /// Change the data codec type of this iterator, specifying the new codec.
-    pub fn remap_data_type<DC2>(self) -> RoIter<'txn, KC, DC2, IM> {
+    pub fn remap_data_type<DC2>(self) -> RoIter<'txn, KC, DC2, IM, E> {
         self.remap_types::<KC, DC2>()
     }
 
     /// Wrap the data bytes into a lazy decoder.
-    pub fn lazily_decode_data(self) -> RoIter<'txn, KC, LazyDecode<DC>, IM> {
+    pub fn lazily_decode_data(self) -> RoIter<'txn, KC, LazyDecode<DC>, IM, E> {
         self.remap_types::<KC, LazyDecode<DC>>()
     }
 }
 
-impl<'txn, KC, DC, IM> Iterator for RoIter<'txn, KC, DC, IM>
+impl<'txn, KC, DC, IM, E> Iterator for RoIter<'txn, KC, DC, IM, E>
 where
     KC: BytesDecode<'txn>,
     DC: BytesDecode<'txn>,
+    KC::Error: Into<E>,
+    DC::Error: Into<E>,
     IM: IterationMethod,
 {
-    type Item = Result<(KC::DItem, DC::DItem)>;
+    type Item = Result<(KC::DItem, DC::DItem), Error<E>>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let result = if self.move_on_first {
@@ -169,9 +172,12 @@ where
         };
 
         match result {
-            Ok(Some((key, data))) => match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+            Ok(Some((key, data))) => match (
+                KC::bytes_decode(key).map_err(Into::into),
+                DC::bytes_decode(data).map_err(Into::into),
+            ) {
                 (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
             },
             Ok(None) => None,
             Err(e) => Some(Err(e)),
@@ -192,9 +198,12 @@ where
         };
 
         match result {
-            Ok(Some((key, data))) => match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+            Ok(Some((key, data))) => match (
+                KC::bytes_decode(key).map_err(Into::into),
+                DC::bytes_decode(data).map_err(Into::into),
+            ) {
                 (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
             },
             Ok(None) => None,
             Err(e) => Some(Err(e)),
@@ -202,24 +211,24 @@ where
     }
 }
 
-impl<KC, DC, IM> fmt::Debug for RoIter<'_, KC, DC, IM> {
+impl<KC, DC, IM, E> fmt::Debug for RoIter<'_, KC, DC, IM, E> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RoIter").finish()
     }
 }
 
 #[cfg(feature = "read-txn-no-tls")]
-unsafe impl<KC, DC, IM> Send for RoIter<'_, KC, DC, IM> {}
+unsafe impl<KC, DC, IM, E> Send for RoIter<'_, KC, DC, IM, E> {}
 
 /// A read-write iterator structure.
-pub struct RwIter<'txn, KC, DC, IM = MoveThroughDuplicateValues> {
+pub struct RwIter<'txn, KC, DC, IM = MoveThroughDuplicateValues, E = BoxedError> {
     cursor: RwCursor<'txn>,
     move_on_first: bool,
-    _phantom: marker::PhantomData<(KC, DC, IM)>,
+    _phantom: marker::PhantomData<(KC, DC, IM, E)>,
 }
 
-impl<'txn, KC, DC, IM> RwIter<'txn, KC, DC, IM> {
-    pub(crate) fn new(cursor: RwCursor<'txn>) -> RwIter<'txn, KC, DC, IM> {
+impl<'txn, KC, DC, IM, E> RwIter<'txn, KC, DC, IM, E> {
+    pub(crate) fn new(cursor: RwCursor<'txn>) -> RwIter<'txn, KC, DC, IM, E> {
         RwIter { cursor, move_on_first: true, _phantom: marker::PhantomData }
     }
 
@@ -236,7 +245,7 @@ impl<'txn, KC, DC, IM> RwIter<'txn, KC, DC, IM> {
     /// > or the end of the transaction.](http://www.lmdb.tech/doc/group__mdb.html#structMDB__val)
     ///
     /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
-    pub unsafe fn del_current(&mut self) -> Result<bool> {
+    pub unsafe fn del_current(&mut self) -> Result<bool, Error<E>> {
         self.cursor.del_current()
     }
 
@@ -267,14 +276,19 @@ impl<'txn, KC, DC, IM> RwIter<'txn, KC, DC, IM> {
         &mut self,
         key: &'a KC::EItem,
         data: &'a DC::EItem,
-    ) -> Result<bool>
+    ) -> Result<bool, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         DC: BytesEncode<'a>,
+        <DC as BytesEncode<'a>>::Error: Into<E>,
     {
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        let data_bytes: Cow<[u8]> = DC::bytes_encode(data).map_err(Error::Encoding)?;
-        self.cursor.put_current(&key_bytes, &data_bytes)
+        let txn = self.cursor.txn();
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer.encode::<KC>(key).map_err(Error::encoding)?;
+        let mut data_buffer = txn.data_buffer();
+        let data_bytes = data_buffer.encode::<DC>(data).map_err(Error::encoding)?;
+        self.cursor.put_current(key_bytes, data_bytes)
     }
 
     /// Write a new value to the current entry. The entry is written with the specified flags.
@@ -296,13 +310,15 @@ impl<'txn, KC, DC, IM> RwIter<'txn, KC, DC, IM> {
         key: &'a KC::EItem,
         data_size: usize,
         write_func: F,
-    ) -> Result<bool>
+    ) -> Result<bool, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         F: FnOnce(&mut ReservedSpace) -> io::Result<()>,
     {
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        self.cursor.put_current_reserved_with_flags(flags, &key_bytes, data_size, write_func)
+        let mut key_buffer = self.cursor.txn().key_buffer();
+        let key_bytes = key_buffer.encode::<KC>(key).map_err(Error::encoding)?;
+        self.cursor.put_current_reserved_with_flags(flags, key_bytes, data_size, write_func)
     }
 
     /// Insert a key-value pair in this database. The entry is written with the specified flags and data codec.
@@ -327,20 +343,25 @@ impl<'txn, KC, DC, IM> RwIter<'txn, KC, DC, IM> {
         flags: PutFlags,
         key: &'a KC::EItem,
         data: &'a NDC::EItem,
-    ) -> Result<()>
+    ) -> Result<(), Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         NDC: BytesEncode<'a>,
+        <NDC as BytesEncode<'a>>::Error: Into<E>,
     {
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        let data_bytes: Cow<[u8]> = NDC::bytes_encode(data).map_err(Error::Encoding)?;
-        self.cursor.put_current_with_flags(flags, &key_bytes, &data_bytes)
+        let txn = self.cursor.txn();
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer.encode::<KC>(key).map_err(Error::encoding)?;
+        let mut data_buffer = txn.data_buffer();
+        let data_bytes = data_buffer.encode::<NDC>(data).map_err(Error::encoding)?;
+        self.cursor.put_current_with_flags(flags, key_bytes, data_bytes)
     }
 
     /// Move on the first value of keys, ignoring duplicate values.
     ///
     /// For more info, see [`RoIter::move_between_keys`].
-    pub fn move_between_keys(self) -> RwIter<'txn, KC, DC, MoveBetweenKeys> {
+    pub fn move_between_keys(self) -> RwIter<'txn, KC, DC, MoveBetweenKeys, E> {
         RwIter {
             cursor: self.cursor,
             move_on_first: self.move_on_first,
@@ -351,7 +372,9 @@ impl<'txn, KC, DC, IM> RwIter<'txn, KC, DC, IM> {
     /// Move through key/values entries and output duplicate values.
     ///
     /// For more info, see [`RoIter::move_through_duplicate_values`].
-    pub fn move_through_duplicate_values(self) -> RwIter<'txn, KC, DC, MoveThroughDuplicateValues> {
+    pub fn move_through_duplicate_values(
+        self,
+    ) -> RwIter<'txn, KC, DC, MoveThroughDuplicateValues, E> {
         RwIter {
             cursor: self.cursor,
             move_on_first: self.move_on_first,
@@ -360,7 +383,7 @@ impl<'txn, KC, DC, IM> RwIter<'txn, KC, DC, IM> {
     }
 
     /// Change the codec types of this iterator, specifying the codecs.
-    pub fn remap_types<KC2, DC2>(self) -> RwIter<'txn, KC2, DC2, IM> {
+    pub fn remap_types<KC2, DC2>(self) -> RwIter<'txn, KC2, DC2, IM, E> {
         RwIter {
             cursor: self.cursor,
             move_on_first: self.move_on_first,
@@ -369,28 +392,30 @@ impl<'txn, KC, DC, IM> RwIter<'txn, KC, DC, IM> {
     }
 
     /// Change the key codec type of this iterator, specifying the new codec.
-    pub fn remap_key_type<KC2>(self) -> RwIter<'txn, KC2, DC, IM> {
+    pub fn remap_key_type<KC2>(self) -> RwIter<'txn, KC2, DC, IM, E> {
         self.remap_types::<KC2, DC>()
     }
 
     /// Change the data codec type of this iterator, specifying the new codec.
-    pub fn remap_data_type<DC2>(self) -> RwIter<'txn, KC, DC2, IM> {
+    pub fn remap_data_type<DC2>(self) -> RwIter<'txn, KC, DC2, IM, E> {
         self.remap_types::<KC, DC2>()
     }
 
     /// Wrap the data bytes into a lazy decoder.
-    pub fn lazily_decode_data(self) -> RwIter<'txn, KC, LazyDecode<DC>, IM> {
+    pub fn lazily_decode_data(self) -> RwIter<'txn, KC, LazyDecode<DC>, IM, E> {
         self.remap_types::<KC, LazyDecode<DC>>()
     }
 }
 
-impl<'txn, KC, DC, IM> Iterator for RwIter<'txn, KC, DC, IM>
+impl<'txn, KC, DC, IM, E> Iterator for RwIter<'txn, KC, DC, IM, E>
 where
     KC: BytesDecode<'txn>,
     DC: BytesDecode<'txn>,
+    KC::Error: Into<E>,
+    DC::Error: Into<E>,
     IM: IterationMethod,
 {
-    type Item = Result<(KC::DItem, DC::DItem)>;
+    type Item = Result<(KC::DItem, DC::DItem), Error<E>>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let result = if self.move_on_first {
@@ -401,9 +426,12 @@ where
         };
 
         match result {
-            Ok(Some((key, data))) => match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+            Ok(Some((key, data))) => match (
+                KC::bytes_decode(key).map_err(Into::into),
+                DC::bytes_decode(data).map_err(Into::into),
+            ) {
                 (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
             },
             Ok(None) => None,
             Err(e) => Some(Err(e)),
@@ -424,9 +452,12 @@ where
         };
 
         match result {
-            Ok(Some((key, data))) => match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+            Ok(Some((key, data))) => match (
+                KC::bytes_decode(key).map_err(Into::into),
+                DC::bytes_decode(data).map_err(Into::into),
+            ) {
                 (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
             },
             Ok(None) => None,
             Err(e) => Some(Err(e)),
@@ -434,28 +465,28 @@ where
     }
 }
 
-impl<KC, DC, IM> fmt::Debug for RwIter<'_, KC, DC, IM> {
+impl<KC, DC, IM, E> fmt::Debug for RwIter<'_, KC, DC, IM, E> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RwIter").finish()
     }
 }
 
 /// A reverse read-only iterator structure.
-pub struct RoRevIter<'txn, KC, DC, IM = MoveThroughDuplicateValues> {
+pub struct RoRevIter<'txn, KC, DC, IM = MoveThroughDuplicateValues, E = BoxedError> {
     cursor: RoCursor<'txn>,
     move_on_last: bool,
-    _phantom: marker::PhantomData<(KC, DC, IM)>,
+    _phantom: marker::PhantomData<(KC, DC, IM, E)>,
 }
 
-impl<'txn, KC, DC, IM> RoRevIter<'txn, KC, DC, IM> {
-    pub(crate) fn new(cursor: RoCursor<'txn>) -> RoRevIter<'txn, KC, DC, IM> {
+impl<'txn, KC, DC, IM, E> RoRevIter<'txn, KC, DC, IM, E> {
+    pub(crate) fn new(cursor: RoCursor<'txn>) -> RoRevIter<'txn, KC, DC, IM, E> {
         RoRevIter { cursor, move_on_last: true, _phantom: marker::PhantomData }
     }
 
     /// Move on the first value of keys, ignoring duplicate values.
     ///
     /// For more info, see [`RoIter::move_between_keys`].
-    pub fn move_between_keys(self) -> RoRevIter<'txn, KC, DC, MoveBetweenKeys> {
+    pub fn move_between_keys(self) -> RoRevIter<'txn, KC, DC, MoveBetweenKeys, E> {
         RoRevIter {
             cursor: self.cursor,
             move_on_last: self.move_on_last,
@@ -468,7 +499,7 @@ impl<'txn, KC, DC, IM> RoRevIter<'txn, KC, DC, IM> {
     /// For more info, see [`RoIter::move_through_duplicate_values`].
     pub fn move_through_duplicate_values(
         self,
-    ) -> RoRevIter<'txn, KC, DC, MoveThroughDuplicateValues> {
+    ) -> RoRevIter<'txn, KC, DC, MoveThroughDuplicateValues, E> {
         RoRevIter {
             cursor: self.cursor,
             move_on_last: self.move_on_last,
@@ -477,7 +508,7 @@ impl<'txn, KC, DC, IM> RoRevIter<'txn, KC, DC, IM> {
     }
 
     /// Change the codec types of this iterator, specifying the codecs.
-    pub fn remap_types<KC2, DC2>(self) -> RoRevIter<'txn, KC2, DC2, IM> {
+    pub fn remap_types<KC2, DC2>(self) -> RoRevIter<'txn, KC2, DC2, IM, E> {
         RoRevIter {
             cursor: self.cursor,
             move_on_last: self.move_on_last,
@@ -486,28 +517,30 @@ impl<'txn, KC, DC, IM> RoRevIter<'txn, KC, DC, IM> {
     }
 
     /// Change the key codec type of this iterator, specifying the new codec.
-    pub fn remap_key_type<KC2>(self) -> RoRevIter<'txn, KC2, DC, IM> {
+    pub fn remap_key_type<KC2>(self) -> RoRevIter<'txn, KC2, DC, IM, E> {
         self.remap_types::<KC2, DC>()
     }
 
     /// Change the data codec type of this iterator, specifying the new codec.
-    pub fn remap_data_type<DC2>(self) -> RoRevIter<'txn, KC, DC2, IM> {
+    pub fn remap_data_type<DC2>(self) -> RoRevIter<'txn, KC, DC2, IM, E> {
         self.remap_types::<KC, DC2>()
     }
 
     /// Wrap the data bytes into a lazy decoder.
-    pub fn lazily_decode_data(self) -> RoRevIter<'txn, KC, LazyDecode<DC>, IM> {
+    pub fn lazily_decode_data(self) -> RoRevIter<'txn, KC, LazyDecode<DC>, IM, E> {
         self.remap_types::<KC, LazyDecode<DC>>()
     }
 }
 
-impl<'txn, KC, DC, IM> Iterator for RoRevIter<'txn, KC, DC, IM>
+impl<'txn, KC, DC, IM, E> Iterator for RoRevIter<'txn, KC, DC, IM, E>
 where
     KC: BytesDecode<'txn>,
     DC: BytesDecode<'txn>,
+    KC::Error: Into<E>,
+    DC::Error: Into<E>,
     IM: IterationMethod,
 {
-    type Item = Result<(KC::DItem, DC::DItem)>;
+    type Item = Result<(KC::DItem, DC::DItem), Error<E>>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let result = if self.move_on_last {
@@ -518,9 +551,12 @@ where
         };
 
         match result {
-            Ok(Some((key, data))) => match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+            Ok(Some((key, data))) => match (
+                KC::bytes_decode(key).map_err(Into::into),
+                DC::bytes_decode(data).map_err(Into::into),
+            ) {
                 (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
             },
             Ok(None) => None,
             Err(e) => Some(Err(e)),
@@ -541,9 +577,12 @@ where
         };
 
         match result {
-            Ok(Some((key, data))) => match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+            Ok(Some((key, data))) => match (
+                KC::bytes_decode(key).map_err(Into::into),
+                DC::bytes_decode(data).map_err(Into::into),
+            ) {
                 (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
             },
             Ok(None) => None,
             Err(e) => Some(Err(e)),
@@ -551,24 +590,24 @@ where
     }
 }
 
-impl<KC, DC, IM> fmt::Debug for RoRevIter<'_, KC, DC, IM> {
+impl<KC, DC, IM, E> fmt::Debug for RoRevIter<'_, KC, DC, IM, E> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RoRevIter").finish()
     }
 }
 
 #[cfg(feature = "read-txn-no-tls")]
-unsafe impl<KC, DC, IM> Send for RoRevIter<'_, KC, DC, IM> {}
+unsafe impl<KC, DC, IM, E> Send for RoRevIter<'_, KC, DC, IM, E> {}
 
 /// A reverse read-write iterator structure.
-pub struct RwRevIter<'txn, KC, DC, IM = MoveThroughDuplicateValues> {
+pub struct RwRevIter<'txn, KC, DC, IM = MoveThroughDuplicateValues, E = BoxedError> {
     cursor: RwCursor<'txn>,
     move_on_last: bool,
-    _phantom: marker::PhantomData<(KC, DC, IM)>,
+    _phantom: marker::PhantomData<(KC, DC, IM, E)>,
 }
 
-impl<'txn, KC, DC, IM> RwRevIter<'txn, KC, DC, IM> {
-    pub(crate) fn new(cursor: RwCursor<'txn>) -> RwRevIter<'txn, KC, DC, IM> {
+impl<'txn, KC, DC, IM, E> RwRevIter<'txn, KC, DC, IM, E> {
+    pub(crate) fn new(cursor: RwCursor<'txn>) -> RwRevIter<'txn, KC, DC, IM, E> {
         RwRevIter { cursor, move_on_last: true, _phantom: marker::PhantomData }
     }
 
@@ -585,7 +624,7 @@ impl<'txn, KC, DC, IM> RwRevIter<'txn, KC, DC, IM> {
     /// > or the end of the transaction.](http://www.lmdb.tech/doc/group__mdb.html#structMDB__val)
     ///
     /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
-    pub unsafe fn del_current(&mut self) -> Result<bool> {
+    pub unsafe fn del_current(&mut self) -> Result<bool, Error<E>> {
         self.cursor.del_current()
     }
 
@@ -616,14 +655,19 @@ impl<'txn, KC, DC, IM> RwRevIter<'txn, KC, DC, IM> {
         &mut self,
         key: &'a KC::EItem,
         data: &'a DC::EItem,
-    ) -> Result<bool>
+    ) -> Result<bool, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         DC: BytesEncode<'a>,
+        <DC as BytesEncode<'a>>::Error: Into<E>,
     {
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        let data_bytes: Cow<[u8]> = DC::bytes_encode(data).map_err(Error::Encoding)?;
-        self.cursor.put_current(&key_bytes, &data_bytes)
+        let txn = self.cursor.txn();
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer.encode::<KC>(key).map_err(Error::encoding)?;
+        let mut data_buffer = txn.data_buffer();
+        let data_bytes = data_buffer.encode::<DC>(data).map_err(Error::encoding)?;
+        self.cursor.put_current(key_bytes, data_bytes)
     }
 
     /// Write a new value to the current entry. The entry is written with the specified flags.
@@ -645,13 +689,15 @@ impl<'txn, KC, DC, IM> RwRevIter<'txn, KC, DC, IM> {
         key: &'a KC::EItem,
         data_size: usize,
         write_func: F,
-    ) -> Result<bool>
+    ) -> Result<bool, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         F: FnOnce(&mut ReservedSpace) -> io::Result<()>,
     {
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        self.cursor.put_current_reserved_with_flags(flags, &key_bytes, data_size, write_func)
+        let mut key_buffer = self.cursor.txn().key_buffer();
+        let key_bytes = key_buffer.encode::<KC>(key).map_err(Error::encoding)?;
+        self.cursor.put_current_reserved_with_flags(flags, key_bytes, data_size, write_func)
     }
 
     /// Insert a key-value pair in this database. The entry is written with the specified flags and data codec.
@@ -676,20 +722,25 @@ impl<'txn, KC, DC, IM> RwRevIter<'txn, KC, DC, IM> {
         flags: PutFlags,
         key: &'a KC::EItem,
         data: &'a NDC::EItem,
-    ) -> Result<()>
+    ) -> Result<(), Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         NDC: BytesEncode<'a>,
+        <NDC as BytesEncode<'a>>::Error: Into<E>,
     {
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        let data_bytes: Cow<[u8]> = NDC::bytes_encode(data).map_err(Error::Encoding)?;
-        self.cursor.put_current_with_flags(flags, &key_bytes, &data_bytes)
+        let txn = self.cursor.txn();
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer.encode::<KC>(key).map_err(Error::encoding)?;
+        let mut data_buffer = txn.data_buffer();
+        let data_bytes = data_buffer.encode::<NDC>(data).map_err(Error::encoding)?;
+        self.cursor.put_current_with_flags(flags, key_bytes, data_bytes)
     }
 
     /// Move on the first value of keys, ignoring duplicate values.
     ///
     /// For more info, see [`RoIter::move_between_keys`].
-    pub fn move_between_keys(self) -> RwRevIter<'txn, KC, DC, MoveBetweenKeys> {
+    pub fn move_between_keys(self) -> RwRevIter<'txn, KC, DC, MoveBetweenKeys, E> {
         RwRevIter {
             cursor: self.cursor,
             move_on_last: self.move_on_last,
@@ -702,7 +753,7 @@ impl<'txn, KC, DC, IM> RwRevIter<'txn, KC, DC, IM> {
     /// For more info, see [`RoIter::move_through_duplicate_values`].
     pub fn move_through_duplicate_values(
         self,
-    ) -> RwRevIter<'txn, KC, DC, MoveThroughDuplicateValues> {
+    ) -> RwRevIter<'txn, KC, DC, MoveThroughDuplicateValues, E> {
         RwRevIter {
             cursor: self.cursor,
             move_on_last: self.move_on_last,
@@ -711,7 +762,7 @@ impl<'txn, KC, DC, IM> RwRevIter<'txn, KC, DC, IM> {
     }
 
     /// Change the codec types of this iterator, specifying the codecs.
-    pub fn remap_types<KC2, DC2>(self) -> RwRevIter<'txn, KC2, DC2, IM> {
+    pub fn remap_types<KC2, DC2>(self) -> RwRevIter<'txn, KC2, DC2, IM, E> {
         RwRevIter {
             cursor: self.cursor,
             move_on_last: self.move_on_last,
@@ -720,28 +771,30 @@ impl<'txn, KC, DC, IM> RwRevIter<'txn, KC, DC, IM> {
     }
 
     /// Change the key codec type of this iterator, specifying the new codec.
-    pub fn remap_key_type<KC2>(self) -> RwRevIter<'txn, KC2, DC, IM> {
+    pub fn remap_key_type<KC2>(self) -> RwRevIter<'txn, KC2, DC, IM, E> {
         self.remap_types::<KC2, DC>()
     }
 
     /// Change the data codec type of this iterator, specifying the new codec.
-    pub fn remap_data_type<DC2>(self) -> RwRevIter<'txn, KC, DC2, IM> {
+    pub fn remap_data_type<DC2>(self) -> RwRevIter<'txn, KC, DC2, IM, E> {
         self.remap_types::<KC, DC2>()
     }
 
     /// Wrap the data bytes into a lazy decoder.
-    pub fn lazily_decode_data(self) -> RwRevIter<'txn, KC, LazyDecode<DC>, IM> {
+    pub fn lazily_decode_data(self) -> RwRevIter<'txn, KC, LazyDecode<DC>, IM, E> {
         self.remap_types::<KC, LazyDecode<DC>>()
     }
 }
 
-impl<'txn, KC, DC, IM> Iterator for RwRevIter<'txn, KC, DC, IM>
+impl<'txn, KC, DC, IM, E> Iterator for RwRevIter<'txn, KC, DC, IM, E>
 where
     KC: BytesDecode<'txn>,
     DC: BytesDecode<'txn>,
+    KC::Error: Into<E>,
+    DC::Error: Into<E>,
     IM: IterationMethod,
 {
-    type Item = Result<(KC::DItem, DC::DItem)>;
+    type Item = Result<(KC::DItem, DC::DItem), Error<E>>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let result = if self.move_on_last {
@@ -752,9 +805,12 @@ where
         };
 
         match result {
-            Ok(Some((key, data))) => match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+            Ok(Some((key, data))) => match (
+                KC::bytes_decode(key).map_err(Into::into),
+                DC::bytes_decode(data).map_err(Into::into),
+            ) {
                 (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
             },
             Ok(None) => None,
             Err(e) => Some(Err(e)),
@@ -775,9 +831,12 @@ where
         };
 
         match result {
-            Ok(Some((key, data))) => match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+            Ok(Some((key, data))) => match (
+                KC::bytes_decode(key).map_err(Into::into),
+                DC::bytes_decode(data).map_err(Into::into),
+            ) {
                 (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
             },
             Ok(None) => None,
             Err(e) => Some(Err(e)),
@@ -785,7 +844,7 @@ where
     }
 }
 
-impl<KC, DC, IM> fmt::Debug for RwRevIter<'_, KC, DC, IM> {
+impl<KC, DC, IM, E> fmt::Debug for RwRevIter<'_, KC, DC, IM, E> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RwRevIter").finish()
     }
@@ -1,12 +1,12 @@
-use std::borrow::Cow;
 use std::marker;
 
 use heed_traits::LexicographicComparator;
 use types::LazyDecode;
 
+use crate::comparator::DefaultComparator;
 use crate::cursor::MoveOperation;
-use crate::env::DefaultComparator;
 use crate::iteration_method::{IterationMethod, MoveBetweenKeys, MoveThroughDuplicateValues};
+use crate::txn::ScratchBuffer;
 use crate::*;
 
 /// Advances `bytes` to the immediate lexicographic successor of equal length, as
@@ -47,10 +47,10 @@ fn retreat_prefix<C: LexicographicComparator>(bytes: &mut [u8]) -> bool {
     true
 }
 
-fn move_on_prefix_end<'txn, C: LexicographicComparator>(
+fn move_on_prefix_end<'txn, C: LexicographicComparator, E>(
     cursor: &mut RoCursor<'txn>,
     prefix: &mut [u8],
-) -> Result<Option<(&'txn [u8], &'txn [u8])>> {
+) -> Result<Option<(&'txn [u8], &'txn [u8])>, Error<E>> {
     if advance_prefix::<C>(prefix) {
         let result = cursor
             .move_on_key_greater_than_or_equal_to(prefix)
@@ -64,22 +64,32 @@ fn move_on_prefix_end<'txn, C: LexicographicComparator>(
 }
 
 /// A read-only prefix iterator structure.
-pub struct RoPrefix<'txn, KC, DC, C = DefaultComparator, IM = MoveThroughDuplicateValues> {
+pub struct RoPrefix<
+    'txn,
+    KC,
+    DC,
+    C = DefaultComparator,
+    IM = MoveThroughDuplicateValues,
+    E = BoxedError,
+> {
     cursor: RoCursor<'txn>,
-    prefix: Vec<u8>,
+    prefix: ScratchBuffer<'txn>,
     move_on_first: bool,
-    _phantom: marker::PhantomData<(KC, DC, C, IM)>,
+    _phantom: marker::PhantomData<(KC, DC, C, IM, E)>,
 }
 
-impl<'txn, KC, DC, C, IM> RoPrefix<'txn, KC, DC, C, IM> {
-    pub(crate) fn new(cursor: RoCursor<'txn>, prefix: Vec<u8>) -> RoPrefix<'txn, KC, DC, C, IM> {
+impl<'txn, KC, DC, C, IM, E> RoPrefix<'txn, KC, DC, C, IM, E> {
+    pub(crate) fn new(
+        cursor: RoCursor<'txn>,
+        prefix: ScratchBuffer<'txn>,
+    ) -> RoPrefix<'txn, KC, DC, C, IM, E> {
         RoPrefix { cursor, prefix, move_on_first: true, _phantom: marker::PhantomData }
     }
 
     /// Move on the first value of keys, ignoring duplicate values.
     ///
     /// For more info, see [`RoIter::move_between_keys`].
-    pub fn move_between_keys(self) -> RoPrefix<'txn, KC, DC, C, MoveBetweenKeys> {
+    pub fn move_between_keys(self) -> RoPrefix<'txn, KC, DC, C, MoveBetweenKeys, E> {
         RoPrefix {
             cursor: self.cursor,
             prefix: self.prefix,
@@ -93,7 +103,7 @@ impl<'txn, KC, DC, C, IM> RoPrefix<'txn, KC, DC, C, IM> {
     /// For more info, see [`RoIter::move_through_duplicate_values`].
     pub fn move_through_duplicate_values(
         self,
-    ) -> RoPrefix<'txn, KC, DC, C, MoveThroughDuplicateValues> {
+    ) -> RoPrefix<'txn, KC, DC, C, MoveThroughDuplicateValues, E> {
         RoPrefix {
             cursor: self.cursor,
             prefix: self.prefix,
@@ -103,7 +113,7 @@ impl<'txn, KC, DC, C, IM> RoPrefix<'txn, KC, DC, C, IM> {
     }
 
     /// Change the codec types of this iterator, specifying the codecs.
-    pub fn remap_types<KC2, DC2>(self) -> RoPrefix<'txn, KC2, DC2, C, IM> {
+    pub fn remap_types<KC2, DC2>(self) -> RoPrefix<'txn, KC2, DC2, C, IM, E> {
         RoPrefix {
             cursor: self.cursor,
             prefix: self.prefix,
@@ -113,29 +123,31 @@ impl<'txn, KC, DC, C, IM> RoPrefix<'txn, KC, DC, C, IM> {
     }
 
     /// Change the key codec type of this iterator, specifying the new codec.
-    pub fn remap_key_type<KC2>(self) -> RoPrefix<'txn, KC2, DC, C, IM> {
+    pub fn remap_key_type<KC2>(self) -> RoPrefix<'txn, KC2, DC, C, IM, E> {
         self.remap_types::<KC2, DC>()
     }
 
     /// Change the data codec type of this iterator, specifying the new codec.
-    pub fn remap_data_type<DC2>(self) -> RoPrefix<'txn, KC, DC2, C, IM> {
+    pub fn remap_data_type<DC2>(self) -> RoPrefix<'txn, KC, DC2, C, IM, E> {
         self.remap_types::<KC, DC2>()
     }
 
     /// Wrap the data bytes into a lazy decoder.
-    pub fn lazily_decode_data(self) -> RoPrefix<'txn, KC, LazyDecode<DC>, C, IM> {
+    pub fn lazily_decode_data(self) -> RoPrefix<'txn, KC, LazyDecode<DC>, C, IM, E> {
         self.remap_types::<KC, LazyDecode<DC>>()
     }
 }
 
-impl<'txn, KC, DC, C, IM> Iterator for RoPrefix<'txn, KC, DC, C, IM>
+impl<'txn, KC, DC, C, IM, E> Iterator for RoPrefix<'txn, KC, DC, C, IM, E>
 where
     KC: BytesDecode<'txn>,
     DC: BytesDecode<'txn>,
+    KC::Error: Into<E>,
+    DC::Error: Into<E>,
     C: LexicographicComparator,
     IM: IterationMethod,
 {
-    type Item = Result<(KC::DItem, DC::DItem)>;
+    type Item = Result<(KC::DItem, DC::DItem), Error<E>>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let result = if self.move_on_first {
@@ -148,9 +160,12 @@ where
         match result {
             Ok(Some((key, data))) => {
                 if key.starts_with(&self.prefix) {
-                    match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+                    match (
+                        KC::bytes_decode(key).map_err(Into::into),
+                        DC::bytes_decode(data).map_err(Into::into),
+                    ) {
                         (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                        (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                        (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
                     }
                 } else {
                     None
@@ -163,11 +178,11 @@ where
 
     fn last(mut self) -> Option<Self::Item> {
         let result = if self.move_on_first {
-            move_on_prefix_end::<C>(&mut self.cursor, &mut self.prefix)
+            move_on_prefix_end::<C, _>(&mut self.cursor, &mut self.prefix)
         } else {
             match (
                 self.cursor.current(),
-                move_on_prefix_end::<C>(&mut self.cursor, &mut self.prefix),
+                move_on_prefix_end::<C, _>(&mut self.cursor, &mut self.prefix),
             ) {
                 (Ok(Some((ckey, _))), Ok(Some((key, data)))) if ckey != key => {
                     Ok(Some((key, data)))
@@ -180,9 +195,12 @@ where
         match result {
             Ok(Some((key, data))) => {
                 if key.starts_with(&self.prefix) {
-                    match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+                    match (
+                        KC::bytes_decode(key).map_err(Into::into),
+                        DC::bytes_decode(data).map_err(Into::into),
+                    ) {
                         (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                        (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                        (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
                     }
                 } else {
                     None
@@ -194,25 +212,35 @@ where
     }
 }
 
-impl<KC, DC, C, IM> fmt::Debug for RoPrefix<'_, KC, DC, C, IM> {
+impl<KC, DC, C, IM, E> fmt::Debug for RoPrefix<'_, KC, DC, C, IM, E> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RoPrefix").finish()
     }
 }
 
 #[cfg(feature = "read-txn-no-tls")]
-unsafe impl<KC, DC, IM> Send for RoPrefix<'_, KC, DC, IM> {}
+unsafe impl<KC, DC, C, IM, E> Send for RoPrefix<'_, KC, DC, C, IM, E> {}
 
 /// A read-write prefix iterator structure.
-pub struct RwPrefix<'txn, KC, DC, C = DefaultComparator, IM = MoveThroughDuplicateValues> {
+pub struct RwPrefix<
+    'txn,
+    KC,
+    DC,
+    C = DefaultComparator,
+    IM = MoveThroughDuplicateValues,
+    E = BoxedError,
+> {
     cursor: RwCursor<'txn>,
-    prefix: Vec<u8>,
+    prefix: ScratchBuffer<'txn>,
     move_on_first: bool,
-    _phantom: marker::PhantomData<(KC, DC, C, IM)>,
+    _phantom: marker::PhantomData<(KC, DC, C, IM, E)>,
 }
 
-impl<'txn, KC, DC, C, IM> RwPrefix<'txn, KC, DC, C, IM> {
-    pub(crate) fn new(cursor: RwCursor<'txn>, prefix: Vec<u8>) -> RwPrefix<'txn, KC, DC, C, IM> {
+impl<'txn, KC, DC, C, IM, E> RwPrefix<'txn, KC, DC, C, IM, E> {
+    pub(crate) fn new(
+        cursor: RwCursor<'txn>,
+        prefix: ScratchBuffer<'txn>,
+    ) -> RwPrefix<'txn, KC, DC, C, IM, E> {
         RwPrefix { cursor, prefix, move_on_first: true, _phantom: marker::PhantomData }
     }
 
@@ -229,7 +257,7 @@ impl<'txn, KC, DC, C, IM> RwPrefix<'txn, KC, DC, C, IM> {
     /// > or the end of the transaction.](http://www.lmdb.tech/doc/group__mdb.html#structMDB__val)
     ///
     /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
-    pub unsafe fn del_current(&mut self) -> Result<bool> {
+    pub unsafe fn del_current(&mut self) -> Result<bool, Error<E>> {
         self.cursor.del_current()
     }
 
@@ -260,14 +288,19 @@ impl<'txn, KC, DC, C, IM> RwPrefix<'txn, KC, DC, C, IM> {
         &mut self,
         key: &'a KC::EItem,
         data: &'a DC::EItem,
-    ) -> Result<bool>
+    ) -> Result<bool, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         DC: BytesEncode<'a>,
+        <DC as BytesEncode<'a>>::Error: Into<E>,
     {
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        let data_bytes: Cow<[u8]> = DC::bytes_encode(data).map_err(Error::Encoding)?;
-        self.cursor.put_current(&key_bytes, &data_bytes)
+        let txn = self.cursor.txn();
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer.encode::<KC>(key).map_err(Error::encoding)?;
+        let mut data_buffer = txn.data_buffer();
+        let data_bytes = data_buffer.encode::<DC>(data).map_err(Error::encoding)?;
+        self.cursor.put_current(key_bytes, data_bytes)
     }
 
     /// Write a new value to the current entry. The entry is written with the specified flags.
@@ -289,13 +322,15 @@ impl<'txn, KC, DC, C, IM> RwPrefix<'txn, KC, DC, C, IM> {
         key: &'a KC::EItem,
         data_size: usize,
         write_func: F,
-    ) -> Result<bool>
+    ) -> Result<bool, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         F: FnOnce(&mut ReservedSpace) -> io::Result<()>,
     {
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        self.cursor.put_current_reserved_with_flags(flags, &key_bytes, data_size, write_func)
+        let mut key_buffer = self.cursor.txn().key_buffer();
+        let key_bytes = key_buffer.encode::<KC>(key).map_err(Error::encoding)?;
+        self.cursor.put_current_reserved_with_flags(flags, key_bytes, data_size, write_func)
     }
 
     /// Insert a key-value pair in this database. The entry is written with the specified flags and data codec.
@@ -320,20 +355,25 @@ impl<'txn, KC, DC, C, IM> RwPrefix<'txn, KC, DC, C, IM> {
         flags: PutFlags,
         key: &'a KC::EItem,
         data: &'a NDC::EItem,
-    ) -> Result<()>
+    ) -> Result<(), Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         NDC: BytesEncode<'a>,
+        <NDC as BytesEncode<'a>>::Error: Into<E>,
     {
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        let data_bytes: Cow<[u8]> = NDC::bytes_encode(data).map_err(Error::Encoding)?;
-        self.cursor.put_current_with_flags(flags, &key_bytes, &data_bytes)
+        let txn = self.cursor.txn();
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer.encode::<KC>(key).map_err(Error::encoding)?;
+        let mut data_buffer = txn.data_buffer();
+        let data_bytes = data_buffer.encode::<NDC>(data).map_err(Error::encoding)?;
+        self.cursor.put_current_with_flags(flags, key_bytes, data_bytes)
     }
 
     /// Move on the first value of keys, ignoring duplicate values.
     ///
     /// For more info, see [`RoIter::move_between_keys`].
-    pub fn move_between_keys(self) -> RwPrefix<'txn, KC, DC, C, MoveBetweenKeys> {
+    pub fn move_between_keys(self) -> RwPrefix<'txn, KC, DC, C, MoveBetweenKeys, E> {
         RwPrefix {
             cursor: self.cursor,
             prefix: self.prefix,
@@ -347,7 +387,7 @@ impl<'txn, KC, DC, C, IM> RwPrefix<'txn, KC, DC, C, IM> {
     /// For more info, see [`RoIter::move_through_duplicate_values`].
     pub fn move_through_duplicate_values(
         self,
-    ) -> RwPrefix<'txn, KC, DC, C, MoveThroughDuplicateValues> {
+    ) -> RwPrefix<'txn, KC, DC, C, MoveThroughDuplicateValues, E> {
         RwPrefix {
             cursor: self.cursor,
             prefix: self.prefix,
@@ -357,7 +397,7 @@ impl<'txn, KC, DC, C, IM> RwPrefix<'txn, KC, DC, C, IM> {
     }
 
     /// Change the codec types of this iterator, specifying the codecs.
-    pub fn remap_types<KC2, DC2>(self) -> RwPrefix<'txn, KC2, DC2, C, IM> {
+    pub fn remap_types<KC2, DC2>(self) -> RwPrefix<'txn, KC2, DC2, C, IM, E> {
         RwPrefix {
             cursor: self.cursor,
             prefix: self.prefix,
@@ -367,29 +407,31 @@ impl<'txn, KC, DC, C, IM> RwPrefix<'txn, KC, DC, C, IM> {
     }
 
     /// Change the key codec type of this iterator, specifying the new codec.
-    pub fn remap_key_type<KC2>(self) -> RwPrefix<'txn, KC2, DC, C, IM> {
+    pub fn remap_key_type<KC2>(self) -> RwPrefix<'txn, KC2, DC, C, IM, E> {
         self.remap_types::<KC2, DC>()
     }
 
     /// Change the data codec type of this iterator, specifying the new codec.
-    pub fn remap_data_type<DC2>(self) -> RwPrefix<'txn, KC, DC2, C, IM> {
+    pub fn remap_data_type<DC2>(self) -> RwPrefix<'txn, KC, DC2, C, IM, E> {
         self.remap_types::<KC, DC2>()
     }
 
     /// Wrap the data bytes into a lazy decoder.
-    pub fn lazily_decode_data(self) -> RwPrefix<'txn, KC, LazyDecode<DC>, C, IM> {
+    pub fn lazily_decode_data(self) -> RwPrefix<'txn, KC, LazyDecode<DC>, C, IM, E> {
         self.remap_types::<KC, LazyDecode<DC>>()
     }
 }
 
-impl<'txn, KC, DC, C, IM> Iterator for RwPrefix<'txn, KC, DC, C, IM>
+impl<'txn, KC, DC, C, IM, E> Iterator for RwPrefix<'txn, KC, DC, C, IM, E>
 where
     KC: BytesDecode<'txn>,
     DC: BytesDecode<'txn>,
+    KC::Error: Into<E>,
+    DC::Error: Into<E>,
     C: LexicographicComparator,
     IM: IterationMethod,
 {
-    type Item = Result<(KC::DItem, DC::DItem)>;
+    type Item = Result<(KC::DItem, DC::DItem), Error<E>>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let result = if self.move_on_first {
@@ -402,9 +444,12 @@ where
         match result {
             Ok(Some((key, data))) => {
                 if key.starts_with(&self.prefix) {
-                    match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+                    match (
+                        KC::bytes_decode(key).map_err(Into::into),
+                        DC::bytes_decode(data).map_err(Into::into),
+                    ) {
                         (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                        (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                        (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
                     }
                 } else {
                     None
@@ -417,11 +462,11 @@ where
 
     fn last(mut self) -> Option<Self::Item> {
         let result = if self.move_on_first {
-            move_on_prefix_end::<C>(&mut self.cursor, &mut self.prefix)
+            move_on_prefix_end::<C, _>(&mut self.cursor, &mut self.prefix)
         } else {
             match (
                 self.cursor.current(),
-                move_on_prefix_end::<C>(&mut self.cursor, &mut self.prefix),
+                move_on_prefix_end::<C, _>(&mut self.cursor, &mut self.prefix),
             ) {
                 (Ok(Some((ckey, _))), Ok(Some((key, data)))) if ckey != key => {
                     Ok(Some((key, data)))
@@ -434,9 +479,12 @@ where
         match result {
             Ok(Some((key, data))) => {
                 if key.starts_with(&self.prefix) {
-                    match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+                    match (
+                        KC::bytes_decode(key).map_err(Into::into),
+                        DC::bytes_decode(data).map_err(Into::into),
+                    ) {
                         (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                        (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                        (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
                     }
                 } else {
                     None
@@ -448,29 +496,39 @@ where
     }
 }
 
-impl<KC, DC, C, IM> fmt::Debug for RwPrefix<'_, KC, DC, C, IM> {
+impl<KC, DC, C, IM, E> fmt::Debug for RwPrefix<'_, KC, DC, C, IM, E> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RwPrefix").finish()
     }
 }
 
 /// A reverse read-only prefix iterator structure.
-pub struct RoRevPrefix<'txn, KC, DC, C = DefaultComparator, IM = MoveThroughDuplicateValues> {
+pub struct RoRevPrefix<
+    'txn,
+    KC,
+    DC,
+    C = DefaultComparator,
+    IM = MoveThroughDuplicateValues,
+    E = BoxedError,
+> {
     cursor: RoCursor<'txn>,
-    prefix: Vec<u8>,
+    prefix: ScratchBuffer<'txn>,
     move_on_last: bool,
-    _phantom: marker::PhantomData<(KC, DC, C, IM)>,
+    _phantom: marker::PhantomData<(KC, DC, C, IM, E)>,
 }
 
-impl<'txn, KC, DC, C, IM> RoRevPrefix<'txn, KC, DC, C, IM> {
-    pub(crate) fn new(cursor: RoCursor<'txn>, prefix: Vec<u8>) -> RoRevPrefix<'txn, KC, DC, C, IM> {
+impl<'txn, KC, DC, C, IM, E> RoRevPrefix<'txn, KC, DC, C, IM, E> {
+    pub(crate) fn new(
+        cursor: RoCursor<'txn>,
+        prefix: ScratchBuffer<'txn>,
+    ) -> RoRevPrefix<'txn, KC, DC, C, IM, E> {
         RoRevPrefix { cursor, prefix, move_on_last: true, _phantom: marker::PhantomData }
     }
 
     /// Move on the first value of keys, ignoring duplicate values.
     ///
     /// For more info, see [`RoIter::move_between_keys`].
-    pub fn move_between_keys(self) -> RoRevPrefix<'txn, KC, DC, C, MoveBetweenKeys> {
+    pub fn move_between_keys(self) -> RoRevPrefix<'txn, KC, DC, C, MoveBetweenKeys, E> {
         RoRevPrefix {
             cursor: self.cursor,
             prefix: self.prefix,
@@ -484,7 +542,7 @@ impl<'txn, KC, DC, C, IM> RoRevPrefix<'txn, KC, DC, C, IM> {
     /// For more info, see [`RoIter::move_through_duplicate_values`].
     pub fn move_through_duplicate_values(
         self,
-    ) -> RoRevPrefix<'txn, KC, DC, C, MoveThroughDuplicateValues> {
+    ) -> RoRevPrefix<'txn, KC, DC, C, MoveThroughDuplicateValues, E> {
         RoRevPrefix {
             cursor: self.cursor,
             prefix: self.prefix,
@@ -494,7 +552,7 @@ impl<'txn, KC, DC, C, IM> RoRevPrefix<'txn, KC, DC, C, IM> {
     }
 
     /// Change the codec types of this iterator, specifying the codecs.
-    pub fn remap_types<KC2, DC2>(self) -> RoRevPrefix<'txn, KC2, DC2, C, IM> {
+    pub fn remap_types<KC2, DC2>(self) -> RoRevPrefix<'txn, KC2, DC2, C, IM, E> {
         RoRevPrefix {
             cursor: self.cursor,
             prefix: self.prefix,
@@ -504,34 +562,36 @@ impl<'txn, KC, DC, C, IM> RoRevPrefix<'txn, KC, DC, C, IM> {
     }
 
     /// Change the key codec type of this iterator, specifying the new codec.
-    pub fn remap_key_type<KC2>(self) -> RoRevPrefix<'txn, KC2, DC, C, IM> {
+    pub fn remap_key_type<KC2>(self) -> RoRevPrefix<'txn, KC2, DC, C, IM, E> {
         self.remap_types::<KC2, DC>()
     }
 
     /// Change the data codec type of this iterator, specifying the new codec.
-    pub fn remap_data_type<DC2>(self) -> RoRevPrefix<'txn, KC, DC2, C, IM> {
+    pub fn remap_data_type<DC2>(self) -> RoRevPrefix<'txn, KC, DC2, C, IM, E> {
         self.remap_types::<KC, DC2>()
     }
 
     /// Wrap the data bytes into a lazy decoder.
-    pub fn lazily_decode_data(self) -> RoRevPrefix<'txn, KC, LazyDecode<DC>, C, IM> {
+    pub fn lazily_decode_data(self) -> RoRevPrefix<'txn, KC, LazyDecode<DC>, C, IM, E> {
         self.remap_types::<KC, LazyDecode<DC>>()
     }
 }
 
-impl<'txn, KC, DC, C, IM> Iterator for RoRevPrefix<'txn, KC, DC, C, IM>
+impl<'txn, KC, DC, C, IM, E> Iterator for RoRevPrefix<'txn, KC, DC, C, IM, E>
 where
     KC: BytesDecode<'txn>,
     DC: BytesDecode<'txn>,
+    KC::Error: Into<E>,
+    DC::Error: Into<E>,
     C: LexicographicComparator,
     IM: IterationMethod,
 {
-    type Item = Result<(KC::DItem, DC::DItem)>;
+    type Item = Result<(KC::DItem, DC::DItem), Error<E>>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let result = if self.move_on_last {
             self.move_on_last = false;
-            move_on_prefix_end::<C>(&mut self.cursor, &mut self.prefix)
+            move_on_prefix_end::<C, _>(&mut self.cursor, &mut self.prefix)
         } else {
             self.cursor.move_on_prev(IM::MOVE_OPERATION)
         };
@@ -539,9 +599,12 @@ where
         match result {
             Ok(Some((key, data))) => {
                 if key.starts_with(&self.prefix) {
-                    match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+                    match (
+                        KC::bytes_decode(key).map_err(Into::into),
+                        DC::bytes_decode(data).map_err(Into::into),
+                    ) {
                         (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                        (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                        (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
                     }
                 } else {
                     None
@@ -570,9 +633,12 @@ where
         match result {
             Ok(Some((key, data))) => {
                 if key.starts_with(&self.prefix) {
-                    match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+                    match (
+                        KC::bytes_decode(key).map_err(Into::into),
+                        DC::bytes_decode(data).map_err(Into::into),
+                    ) {
                         (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                        (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                        (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
                     }
                 } else {
                     None
@@ -584,25 +650,35 @@ where
     }
 }
 
-impl<KC, DC, C, IM> fmt::Debug for RoRevPrefix<'_, KC, DC, C, IM> {
+impl<KC, DC, C, IM, E> fmt::Debug for RoRevPrefix<'_, KC, DC, C, IM, E> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RoRevPrefix").finish()
     }
 }
 
 #[cfg(feature = "read-txn-no-tls")]
-unsafe impl<KC, DC, IM> Send for RoRevPrefix<'_, KC, DC, IM> {}
+unsafe impl<KC, DC, C, IM, E> Send for RoRevPrefix<'_, KC, DC, C, IM, E> {}
 
 /// A reverse read-write prefix iterator structure.
-pub struct RwRevPrefix<'txn, KC, DC, C = DefaultComparator, IM = MoveThroughDuplicateValues> {
+pub struct RwRevPrefix<
+    'txn,
+    KC,
+    DC,
+    C = DefaultComparator,
+    IM = MoveThroughDuplicateValues,
+    E = BoxedError,
+> {
     cursor: RwCursor<'txn>,
-    prefix: Vec<u8>,
+    prefix: ScratchBuffer<'txn>,
     move_on_last: bool,
-    _phantom: marker::PhantomData<(KC, DC, C, IM)>,
+    _phantom: marker::PhantomData<(KC, DC, C, IM, E)>,
 }
 
-impl<'txn, KC, DC, C, IM> RwRevPrefix<'txn, KC, DC, C, IM> {
-    pub(crate) fn new(cursor: RwCursor<'txn>, prefix: Vec<u8>) -> RwRevPrefix<'txn, KC, DC, C, IM> {
+impl<'txn, KC, DC, C, IM, E> RwRevPrefix<'txn, KC, DC, C, IM, E> {
+    pub(crate) fn new(
+        cursor: RwCursor<'txn>,
+        prefix: ScratchBuffer<'txn>,
+    ) -> RwRevPrefix<'txn, KC, DC, C, IM, E> {
         RwRevPrefix { cursor, prefix, move_on_last: true, _phantom: marker::PhantomData }
     }
 
@@ -619,7 +695,7 @@ impl<'txn, KC, DC, C, IM> RwRevPrefix<'txn, KC, DC, C, IM> {
     /// > or the end of the transaction.](http://www.lmdb.tech/doc/group__mdb.html#structMDB__val)
     ///
     /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
-    pub unsafe fn del_current(&mut self) -> Result<bool> {
+    pub unsafe fn del_current(&mut self) -> Result<bool, Error<E>> {
         self.cursor.del_current()
     }
 
@@ -650,14 +726,19 @@ impl<'txn, KC, DC, C, IM> RwRevPrefix<'txn, KC, DC, C, IM> {
         &mut self,
         key: &'a KC::EItem,
         data: &'a DC::EItem,
-    ) -> Result<bool>
+    ) -> Result<bool, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         DC: BytesEncode<'a>,
+        <DC as BytesEncode<'a>>::Error: Into<E>,
     {
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        let data_bytes: Cow<[u8]> = DC::bytes_encode(data).map_err(Error::Encoding)?;
-        self.cursor.put_current(&key_bytes, &data_bytes)
+        let txn = self.cursor.txn();
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer.encode::<KC>(key).map_err(Error::encoding)?;
+        let mut data_buffer = txn.data_buffer();
+        let data_bytes = data_buffer.encode::<DC>(data).map_err(Error::encoding)?;
+        self.cursor.put_current(key_bytes, data_bytes)
     }
 
     /// Write a new value to the current entry. The entry is written with the specified flags.
@@ -679,13 +760,15 @@ impl<'txn, KC, DC, C, IM> RwRevPrefix<'txn, KC, DC, C, IM> {
         key: &'a KC::EItem,
         data_size: usize,
         write_func: F,
-    ) -> Result<bool>
+    ) -> Result<bool, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         F: FnOnce(&mut ReservedSpace) -> io::Result<()>,
     {
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        self.cursor.put_current_reserved_with_flags(flags, &key_bytes, data_size, write_func)
+        let mut key_buffer = self.cursor.txn().key_buffer();
+        let key_bytes = key_buffer.encode::<KC>(key).map_err(Error::encoding)?;
+        self.cursor.put_current_reserved_with_flags(flags, key_bytes, data_size, write_func)
     }
 
     /// Insert a key-value pair in this database. The entry is written with the specified flags and data codec.
@@ -710,20 +793,25 @@ impl<'txn, KC, DC, C, IM> RwRevPrefix<'txn, KC, DC, C, IM> {
         flags: PutFlags,
         key: &'a KC::EItem,
         data: &'a NDC::EItem,
-    ) -> Result<()>
+    ) -> Result<(), Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         NDC: BytesEncode<'a>,
+        <NDC as BytesEncode<'a>>::Error: Into<E>,
     {
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        let data_bytes: Cow<[u8]> = NDC::bytes_encode(data).map_err(Error::Encoding)?;
-        self.cursor.put_current_with_flags(flags, &key_bytes, &data_bytes)
+        let txn = self.cursor.txn();
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer.encode::<KC>(key).map_err(Error::encoding)?;
+        let mut data_buffer = txn.data_buffer();
+        let data_bytes = data_buffer.encode::<NDC>(data).map_err(Error::encoding)?;
+        self.cursor.put_current_with_flags(flags, key_bytes, data_bytes)
     }
 
     /// Move on the first value of keys, ignoring duplicate values.
     ///
     /// For more info, see [`RoIter::move_between_keys`].
-    pub fn move_between_keys(self) -> RwRevPrefix<'txn, KC, DC, C, MoveBetweenKeys> {
+    pub fn move_between_keys(self) -> RwRevPrefix<'txn, KC, DC, C, MoveBetweenKeys, E> {
         RwRevPrefix {
             cursor: self.cursor,
             prefix: self.prefix,
@@ -737,7 +825,7 @@ impl<'txn, KC, DC, C, IM> RwRevPrefix<'txn, KC, DC, C, IM> {
     /// For more info, see [`RoIter::move_through_duplicate_values`].
     pub fn move_through_duplicate_values(
         self,
-    ) -> RwRevPrefix<'txn, KC, DC, C, MoveThroughDuplicateValues> {
+    ) -> RwRevPrefix<'txn, KC, DC, C, MoveThroughDuplicateValues, E> {
         RwRevPrefix {
             cursor: self.cursor,
             prefix: self.prefix,
@@ -747,7 +835,7 @@ impl<'txn, KC, DC, C, IM> RwRevPrefix<'txn, KC, DC, C, IM> {
     }
 
     /// Change the codec types of this iterator, specifying the codecs.
-    pub fn remap_types<KC2, DC2>(self) -> RwRevPrefix<'txn, KC2, DC2, C, IM> {
+    pub fn remap_types<KC2, DC2>(self) -> RwRevPrefix<'txn, KC2, DC2, C, IM, E> {
         RwRevPrefix {
             cursor: self.cursor,
             prefix: self.prefix,
@@ -757,34 +845,36 @@ impl<'txn, KC, DC, C, IM> RwRevPrefix<'txn, KC, DC, C, IM> {
     }
 
     /// Change the key codec type of this iterator, specifying the new codec.
-    pub fn remap_key_type<KC2>(self) -> RwRevPrefix<'txn, KC2, DC, C, IM> {
+    pub fn remap_key_type<KC2>(self) -> RwRevPrefix<'txn, KC2, DC, C, IM, E> {
         self.remap_types::<KC2, DC>()
     }
 
     /// Change the data codec type of this iterator, specifying the new codec.
-    pub fn remap_data_type<DC2>(self) -> RwRevPrefix<'txn, KC, DC2, C, IM> {
+    pub fn remap_data_type<DC2>(self) -> RwRevPrefix<'txn, KC, DC2, C, IM, E> {
         self.remap_types::<KC, DC2>()
     }
 
     /// Wrap the data bytes into a lazy decoder.
-    pub fn lazily_decode_data(self) -> RwRevPrefix<'txn, KC, LazyDecode<DC>, C, IM> {
+    pub fn lazily_decode_data(self) -> RwRevPrefix<'txn, KC, LazyDecode<DC>, C, IM, E> {
         self.remap_types::<KC, LazyDecode<DC>>()
     }
 }
 
-impl<'txn, KC, DC, C, IM> Iterator for RwRevPrefix<'txn, KC, DC, C, IM>
+impl<'txn, KC, DC, C, IM, E> Iterator for RwRevPrefix<'txn, KC, DC, C, IM, E>
 where
     KC: BytesDecode<'txn>,
     DC: BytesDecode<'txn>,
+    KC::Error: Into<E>,
+    DC::Error: Into<E>,
     C: LexicographicComparator,
     IM: IterationMethod,
 {
-    type Item = Result<(KC::DItem, DC::DItem)>;
+    type Item = Result<(KC::DItem, DC::DItem), Error<E>>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let result = if self.move_on_last {
             self.move_on_last = false;
-            move_on_prefix_end::<C>(&mut self.cursor, &mut self.prefix)
+            move_on_prefix_end::<C, _>(&mut self.cursor, &mut self.prefix)
         } else {
             self.cursor.move_on_prev(IM::MOVE_OPERATION)
         };
@@ -792,9 +882,12 @@ where
         match result {
             Ok(Some((key, data))) => {
                 if key.starts_with(&self.prefix) {
-                    match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+                    match (
+                        KC::bytes_decode(key).map_err(Into::into),
+                        DC::bytes_decode(data).map_err(Into::into),
+                    ) {
                         (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                        (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                        (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
                     }
                 } else {
                     None
@@ -823,9 +916,12 @@ where
         match result {
             Ok(Some((key, data))) => {
                 if key.starts_with(&self.prefix) {
-                    match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+                    match (
+                        KC::bytes_decode(key).map_err(Into::into),
+                        DC::bytes_decode(data).map_err(Into::into),
+                    ) {
                         (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                        (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                        (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
                     }
                 } else {
                     None
@@ -837,7 +933,7 @@ where
     }
 }
 
-impl<KC, DC, C, IM> fmt::Debug for RwRevPrefix<'_, KC, DC, C, IM> {
+impl<KC, DC, C, IM, E> fmt::Debug for RwRevPrefix<'_, KC, DC, C, IM, E> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RwRevPrefix").finish()
     }
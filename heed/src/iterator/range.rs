@@ -1,4 +1,3 @@
-use std::borrow::Cow;
 use std::marker;
 use std::ops::Bound;
 
@@ -6,15 +5,16 @@ use types::LazyDecode;
 
 use crate::cursor::MoveOperation;
 use crate::iteration_method::{IterationMethod, MoveBetweenKeys, MoveThroughDuplicateValues};
+use crate::txn::ScratchBuffer;
 use crate::*;
 
-fn move_on_range_end<'txn>(
+fn move_on_range_end<'txn, E>(
     cursor: &mut RoCursor<'txn>,
-    end_bound: &Bound<Vec<u8>>,
-) -> Result<Option<(&'txn [u8], &'txn [u8])>> {
+    end_bound: &Bound<ScratchBuffer>,
+) -> Result<Option<(&'txn [u8], &'txn [u8])>, Error<E>> {
     match end_bound {
         Bound::Included(end) => match cursor.move_on_key_greater_than_or_equal_to(end) {
-            Ok(Some((key, data))) if key == &end[..] => Ok(Some((key, data))),
+            Ok(Some((key, data))) if cursor.compare_keys(key, end).is_eq() => Ok(Some((key, data))),
             Ok(_) => cursor.move_on_prev(MoveOperation::NoDup),
             Err(e) => Err(e),
         },
@@ -25,14 +25,16 @@ fn move_on_range_end<'txn>(
     }
 }
 
-fn move_on_range_start<'txn>(
+fn move_on_range_start<'txn, E>(
     cursor: &mut RoCursor<'txn>,
-    start_bound: &mut Bound<Vec<u8>>,
-) -> Result<Option<(&'txn [u8], &'txn [u8])>> {
+    start_bound: &mut Bound<ScratchBuffer>,
+) -> Result<Option<(&'txn [u8], &'txn [u8])>, Error<E>> {
     match start_bound {
         Bound::Included(start) => cursor.move_on_key_greater_than_or_equal_to(start),
         Bound::Excluded(start) => match cursor.move_on_key_greater_than_or_equal_to(start)? {
-            Some((key, _)) if key == start => cursor.move_on_next(MoveOperation::NoDup),
+            Some((key, _)) if cursor.compare_keys(key, start).is_eq() => {
+                cursor.move_on_next(MoveOperation::NoDup)
+            }
             result => Ok(result),
         },
         Bound::Unbounded => cursor.move_on_first(MoveOperation::NoDup),
@@ -40,20 +42,20 @@ fn move_on_range_start<'txn>(
 }
 
 /// A read-only range iterator structure.
-pub struct RoRange<'txn, KC, DC, IM = MoveThroughDuplicateValues> {
+pub struct RoRange<'txn, KC, DC, IM = MoveThroughDuplicateValues, E = BoxedError> {
     cursor: RoCursor<'txn>,
     move_on_start: bool,
-    start_bound: Bound<Vec<u8>>,
-    end_bound: Bound<Vec<u8>>,
-    _phantom: marker::PhantomData<(KC, DC, IM)>,
+    start_bound: Bound<ScratchBuffer<'txn>>,
+    end_bound: Bound<ScratchBuffer<'txn>>,
+    _phantom: marker::PhantomData<(KC, DC, IM, E)>,
 }
 
-impl<'txn, KC, DC, IM> RoRange<'txn, KC, DC, IM> {
+impl<'txn, KC, DC, IM, E> RoRange<'txn, KC, DC, IM, E> {
     pub(crate) fn new(
         cursor: RoCursor<'txn>,
-        start_bound: Bound<Vec<u8>>,
-        end_bound: Bound<Vec<u8>>,
-    ) -> RoRange<'txn, KC, DC, IM> {
+        start_bound: Bound<ScratchBuffer<'txn>>,
+        end_bound: Bound<ScratchBuffer<'txn>>,
+    ) -> RoRange<'txn, KC, DC, IM, E> {
         RoRange {
             cursor,
             move_on_start: true,
@@ -66,7 +68,7 @@ impl<'txn, KC, DC, IM> RoRange<'txn, KC, DC, IM> {
     /// Move on the first value of keys, ignoring duplicate values.
     ///
     /// For more info, see [`RoIter::move_between_keys`].
-    pub fn move_between_keys(self) -> RoRange<'txn, KC, DC, MoveBetweenKeys> {
+    pub fn move_between_keys(self) -> RoRange<'txn, KC, DC, MoveBetweenKeys, E> {
         RoRange {
             cursor: self.cursor,
             move_on_start: self.move_on_start,
@@ -81,7 +83,7 @@ impl<'txn, KC, DC, IM> RoRange<'txn, KC, DC, IM> {
     /// For more info, see [`RoIter::move_through_duplicate_values`].
     pub fn move_through_duplicate_values(
         self,
-    ) -> RoRange<'txn, KC, DC, MoveThroughDuplicateValues> {
+    ) -> RoRange<'txn, KC, DC, MoveThroughDuplicateValues, E> {
         RoRange {
             cursor: self.cursor,
             move_on_start: self.move_on_start,
@@ -92,7 +94,7 @@ impl<'txn, KC, DC, IM> RoRange<'txn, KC, DC, IM> {
     }
 
     /// Change the codec types of this iterator, specifying the codecs.
-    pub fn remap_types<KC2, DC2>(self) -> RoRange<'txn, KC2, DC2, IM> {
+    pub fn remap_types<KC2, DC2>(self) -> RoRange<'txn, KC2, DC2, IM, E> {
         RoRange {
             cursor: self.cursor,
             move_on_start: self.move_on_start,
@@ -103,28 +105,30 @@ impl<'txn, KC, DC, IM> RoRange<'txn, KC, DC, IM> {
     }
 
     /// Change the key codec type of this iterator, specifying the new codec.
-    pub fn remap_key_type<KC2>(self) -> RoRange<'txn, KC2, DC, IM> {
+    pub fn remap_key_type<KC2>(self) -> RoRange<'txn, KC2, DC, IM, E> {
         self.remap_types::<KC2, DC>()
     }
 
     /// Change the data codec type of this iterator, specifying the new codec.
-    pub fn remap_data_type<DC2>(self) -> RoRange<'txn, KC, DC2, IM> {
+    pub fn remap_data_type<DC2>(self) -> RoRange<'txn, KC, DC2, IM, E> {
         self.remap_types::<KC, DC2>()
     }
 
     /// Wrap the data bytes into a lazy decoder.
-    pub fn lazily_decode_data(self) -> RoRange<'txn, KC, LazyDecode<DC>, IM> {
+    pub fn lazily_decode_data(self) -> RoRange<'txn, KC, LazyDecode<DC>, IM, E> {
         self.remap_types::<KC, LazyDecode<DC>>()
     }
 }
 
-impl<'txn, KC, DC, IM> Iterator for RoRange<'txn, KC, DC, IM>
+impl<'txn, KC, DC, IM, E> Iterator for RoRange<'txn, KC, DC, IM, E>
 where
     KC: BytesDecode<'txn>,
     DC: BytesDecode<'txn>,
+    KC::Error: Into<E>,
+    DC::Error: Into<E>,
     IM: IterationMethod,
 {
-    type Item = Result<(KC::DItem, DC::DItem)>;
+    type Item = Result<(KC::DItem, DC::DItem), Error<E>>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let result = if self.move_on_start {
@@ -137,15 +141,18 @@ where
         match result {
             Ok(Some((key, data))) => {
                 let must_be_returned = match &self.end_bound {
-                    Bound::Included(end) => key <= end,
-                    Bound::Excluded(end) => key < end,
+                    Bound::Included(end) => self.cursor.compare_keys(key, end).is_le(),
+                    Bound::Excluded(end) => self.cursor.compare_keys(key, end).is_lt(),
                     Bound::Unbounded => true,
                 };
 
                 if must_be_returned {
-                    match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+                    match (
+                        KC::bytes_decode(key).map_err(Into::into),
+                        DC::bytes_decode(data).map_err(Into::into),
+                    ) {
                         (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                        (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                        (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
                     }
                 } else {
                     None
@@ -172,15 +179,18 @@ where
         match result {
             Ok(Some((key, data))) => {
                 let must_be_returned = match &self.start_bound {
-                    Bound::Included(start) => key >= start,
-                    Bound::Excluded(start) => key > start,
+                    Bound::Included(start) => self.cursor.compare_keys(key, start).is_ge(),
+                    Bound::Excluded(start) => self.cursor.compare_keys(key, start).is_gt(),
                     Bound::Unbounded => true,
                 };
 
                 if must_be_returned {
-                    match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+                    match (
+                        KC::bytes_decode(key).map_err(Into::into),
+                        DC::bytes_decode(data).map_err(Into::into),
+                    ) {
                         (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                        (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                        (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
                     }
                 } else {
                     None
@@ -192,30 +202,30 @@ where
     }
 }
 
-impl<KC, DC, IM> fmt::Debug for RoRange<'_, KC, DC, IM> {
+impl<KC, DC, IM, E> fmt::Debug for RoRange<'_, KC, DC, IM, E> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RoRange").finish()
     }
 }
 
 #[cfg(feature = "read-txn-no-tls")]
-unsafe impl<KC, DC, IM> Send for RoRange<'_, KC, DC, IM> {}
+unsafe impl<KC, DC, IM, E> Send for RoRange<'_, KC, DC, IM, E> {}
 
 /// A read-write range iterator structure.
-pub struct RwRange<'txn, KC, DC, IM = MoveThroughDuplicateValues> {
+pub struct RwRange<'txn, KC, DC, IM = MoveThroughDuplicateValues, E = BoxedError> {
     cursor: RwCursor<'txn>,
     move_on_start: bool,
-    start_bound: Bound<Vec<u8>>,
-    end_bound: Bound<Vec<u8>>,
-    _phantom: marker::PhantomData<(KC, DC, IM)>,
+    start_bound: Bound<ScratchBuffer<'txn>>,
+    end_bound: Bound<ScratchBuffer<'txn>>,
+    _phantom: marker::PhantomData<(KC, DC, IM, E)>,
 }
 
-impl<'txn, KC, DC, IM> RwRange<'txn, KC, DC, IM> {
+impl<'txn, KC, DC, IM, E> RwRange<'txn, KC, DC, IM, E> {
     pub(crate) fn new(
         cursor: RwCursor<'txn>,
-        start_bound: Bound<Vec<u8>>,
-        end_bound: Bound<Vec<u8>>,
-    ) -> RwRange<'txn, KC, DC, IM> {
+        start_bound: Bound<ScratchBuffer<'txn>>,
+        end_bound: Bound<ScratchBuffer<'txn>>,
+    ) -> RwRange<'txn, KC, DC, IM, E> {
         RwRange {
             cursor,
             move_on_start: true,
@@ -238,7 +248,7 @@ impl<'txn, KC, DC, IM> RwRange<'txn, KC, DC, IM> {
     /// > or the end of the transaction.](http://www.lmdb.tech/doc/group__mdb.html#structMDB__val)
     ///
     /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
-    pub unsafe fn del_current(&mut self) -> Result<bool> {
+    pub unsafe fn del_current(&mut self) -> Result<bool, Error<E>> {
         self.cursor.del_current()
     }
 
@@ -269,14 +279,19 @@ impl<'txn, KC, DC, IM> RwRange<'txn, KC, DC, IM> {
         &mut self,
         key: &'a KC::EItem,
         data: &'a DC::EItem,
-    ) -> Result<bool>
+    ) -> Result<bool, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         DC: BytesEncode<'a>,
+        <DC as BytesEncode<'a>>::Error: Into<E>,
     {
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        let data_bytes: Cow<[u8]> = DC::bytes_encode(data).map_err(Error::Encoding)?;
-        self.cursor.put_current(&key_bytes, &data_bytes)
+        let txn = self.cursor.txn();
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer.encode::<KC>(key).map_err(Error::encoding)?;
+        let mut data_buffer = txn.data_buffer();
+        let data_bytes = data_buffer.encode::<DC>(data).map_err(Error::encoding)?;
+        self.cursor.put_current(key_bytes, data_bytes)
     }
 
     /// Write a new value to the current entry. The entry is written with the specified flags.
@@ -298,13 +313,15 @@ impl<'txn, KC, DC, IM> RwRange<'txn, KC, DC, IM> {
         key: &'a KC::EItem,
         data_size: usize,
         write_func: F,
-    ) -> Result<bool>
+    ) -> Result<bool, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         F: FnOnce(&mut ReservedSpace) -> io::Result<()>,
     {
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        self.cursor.put_current_reserved_with_flags(flags, &key_bytes, data_size, write_func)
+        let mut key_buffer = self.cursor.txn().key_buffer();
+        let key_bytes = key_buffer.encode::<KC>(key).map_err(Error::encoding)?;
+        self.cursor.put_current_reserved_with_flags(flags, key_bytes, data_size, write_func)
     }
 
     /// Insert a key-value pair in this database. The entry is written with the specified flags and data codec.
@@ -329,20 +346,25 @@ impl<'txn, KC, DC, IM> RwRange<'txn, KC, DC, IM> {
         flags: PutFlags,
         key: &'a KC::EItem,
         data: &'a NDC::EItem,
-    ) -> Result<()>
+    ) -> Result<(), Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         NDC: BytesEncode<'a>,
+        <NDC as BytesEncode<'a>>::Error: Into<E>,
     {
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        let data_bytes: Cow<[u8]> = NDC::bytes_encode(data).map_err(Error::Encoding)?;
-        self.cursor.put_current_with_flags(flags, &key_bytes, &data_bytes)
+        let txn = self.cursor.txn();
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer.encode::<KC>(key).map_err(Error::encoding)?;
+        let mut data_buffer = txn.data_buffer();
+        let data_bytes = data_buffer.encode::<NDC>(data).map_err(Error::encoding)?;
+        self.cursor.put_current_with_flags(flags, key_bytes, data_bytes)
     }
 
     /// Move on the first value of keys, ignoring duplicate values.
     ///
     /// For more info, see [`RoIter::move_between_keys`].
-    pub fn move_between_keys(self) -> RwRange<'txn, KC, DC, MoveBetweenKeys> {
+    pub fn move_between_keys(self) -> RwRange<'txn, KC, DC, MoveBetweenKeys, E> {
         RwRange {
             cursor: self.cursor,
             move_on_start: self.move_on_start,
@@ -357,7 +379,7 @@ impl<'txn, KC, DC, IM> RwRange<'txn, KC, DC, IM> {
     /// For more info, see [`RoIter::move_through_duplicate_values`].
     pub fn move_through_duplicate_values(
         self,
-    ) -> RwRange<'txn, KC, DC, MoveThroughDuplicateValues> {
+    ) -> RwRange<'txn, KC, DC, MoveThroughDuplicateValues, E> {
         RwRange {
             cursor: self.cursor,
             move_on_start: self.move_on_start,
@@ -368,7 +390,7 @@ impl<'txn, KC, DC, IM> RwRange<'txn, KC, DC, IM> {
     }
 
     /// Change the codec types of this iterator, specifying the codecs.
-    pub fn remap_types<KC2, DC2>(self) -> RwRange<'txn, KC2, DC2, IM> {
+    pub fn remap_types<KC2, DC2>(self) -> RwRange<'txn, KC2, DC2, IM, E> {
         RwRange {
             cursor: self.cursor,
             move_on_start: self.move_on_start,
@@ -379,28 +401,30 @@ impl<'txn, KC, DC, IM> RwRange<'txn, KC, DC, IM> {
     }
 
     /// Change the key codec type of this iterator, specifying the new codec.
-    pub fn remap_key_type<KC2>(self) -> RwRange<'txn, KC2, DC, IM> {
+    pub fn remap_key_type<KC2>(self) -> RwRange<'txn, KC2, DC, IM, E> {
         self.remap_types::<KC2, DC>()
     }
 
     /// Change the data codec type of this iterator, specifying the new codec.
-    pub fn remap_data_type<DC2>(self) -> RwRange<'txn, KC, DC2, IM> {
+    pub fn remap_data_type<DC2>(self) -> RwRange<'txn, KC, DC2, IM, E> {
         self.remap_types::<KC, DC2>()
     }
 
     /// Wrap the data bytes into a lazy decoder.
-    pub fn lazily_decode_data(self) -> RwRange<'txn, KC, LazyDecode<DC>, IM> {
+    pub fn lazily_decode_data(self) -> RwRange<'txn, KC, LazyDecode<DC>, IM, E> {
         self.remap_types::<KC, LazyDecode<DC>>()
     }
 }
 
-impl<'txn, KC, DC, IM> Iterator for RwRange<'txn, KC, DC, IM>
+impl<'txn, KC, DC, IM, E> Iterator for RwRange<'txn, KC, DC, IM, E>
 where
     KC: BytesDecode<'txn>,
     DC: BytesDecode<'txn>,
+    KC::Error: Into<E>,
+    DC::Error: Into<E>,
     IM: IterationMethod,
 {
-    type Item = Result<(KC::DItem, DC::DItem)>;
+    type Item = Result<(KC::DItem, DC::DItem), Error<E>>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let result = if self.move_on_start {
@@ -413,15 +437,18 @@ where
         match result {
             Ok(Some((key, data))) => {
                 let must_be_returned = match self.end_bound {
-                    Bound::Included(ref end) => key <= end,
-                    Bound::Excluded(ref end) => key < end,
+                    Bound::Included(ref end) => self.cursor.compare_keys(key, end).is_le(),
+                    Bound::Excluded(ref end) => self.cursor.compare_keys(key, end).is_lt(),
                     Bound::Unbounded => true,
                 };
 
                 if must_be_returned {
-                    match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+                    match (
+                        KC::bytes_decode(key).map_err(Into::into),
+                        DC::bytes_decode(data).map_err(Into::into),
+                    ) {
                         (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                        (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                        (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
                     }
                 } else {
                     None
@@ -448,15 +475,18 @@ where
         match result {
             Ok(Some((key, data))) => {
                 let must_be_returned = match &self.start_bound {
-                    Bound::Included(start) => key >= start,
-                    Bound::Excluded(start) => key > start,
+                    Bound::Included(start) => self.cursor.compare_keys(key, start).is_ge(),
+                    Bound::Excluded(start) => self.cursor.compare_keys(key, start).is_gt(),
                     Bound::Unbounded => true,
                 };
 
                 if must_be_returned {
-                    match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+                    match (
+                        KC::bytes_decode(key).map_err(Into::into),
+                        DC::bytes_decode(data).map_err(Into::into),
+                    ) {
                         (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                        (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                        (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
                     }
                 } else {
                     None
@@ -468,27 +498,27 @@ where
     }
 }
 
-impl<KC, DC, IM> fmt::Debug for RwRange<'_, KC, DC, IM> {
+impl<KC, DC, IM, E> fmt::Debug for RwRange<'_, KC, DC, IM, E> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RwRange").finish()
     }
 }
 
 /// A reverse read-only range iterator structure.
-pub struct RoRevRange<'txn, KC, DC, IM = MoveThroughDuplicateValues> {
+pub struct RoRevRange<'txn, KC, DC, IM = MoveThroughDuplicateValues, E = BoxedError> {
     cursor: RoCursor<'txn>,
     move_on_end: bool,
-    start_bound: Bound<Vec<u8>>,
-    end_bound: Bound<Vec<u8>>,
-    _phantom: marker::PhantomData<(KC, DC, IM)>,
+    start_bound: Bound<ScratchBuffer<'txn>>,
+    end_bound: Bound<ScratchBuffer<'txn>>,
+    _phantom: marker::PhantomData<(KC, DC, IM, E)>,
 }
 
-impl<'txn, KC, DC, IM> RoRevRange<'txn, KC, DC, IM> {
+impl<'txn, KC, DC, IM, E> RoRevRange<'txn, KC, DC, IM, E> {
     pub(crate) fn new(
         cursor: RoCursor<'txn>,
-        start_bound: Bound<Vec<u8>>,
-        end_bound: Bound<Vec<u8>>,
-    ) -> RoRevRange<'txn, KC, DC, IM> {
+        start_bound: Bound<ScratchBuffer<'txn>>,
+        end_bound: Bound<ScratchBuffer<'txn>>,
+    ) -> RoRevRange<'txn, KC, DC, IM, E> {
         RoRevRange {
             cursor,
             move_on_end: true,
@@ -501,7 +531,7 @@ impl<'txn, KC, DC, IM> RoRevRange<'txn, KC, DC, IM> {
     /// Move on the first value of keys, ignoring duplicate values.
     ///
     /// For more info, see [`RoIter::move_between_keys`].
-    pub fn move_between_keys(self) -> RoRevRange<'txn, KC, DC, MoveBetweenKeys> {
+    pub fn move_between_keys(self) -> RoRevRange<'txn, KC, DC, MoveBetweenKeys, E> {
         RoRevRange {
             cursor: self.cursor,
             move_on_end: self.move_on_end,
@@ -516,7 +546,7 @@ impl<'txn, KC, DC, IM> RoRevRange<'txn, KC, DC, IM> {
     /// For more info, see [`RoIter::move_through_duplicate_values`].
     pub fn move_through_duplicate_values(
         self,
-    ) -> RoRevRange<'txn, KC, DC, MoveThroughDuplicateValues> {
+    ) -> RoRevRange<'txn, KC, DC, MoveThroughDuplicateValues, E> {
         RoRevRange {
             cursor: self.cursor,
             move_on_end: self.move_on_end,
@@ -527,7 +557,7 @@ impl<'txn, KC, DC, IM> RoRevRange<'txn, KC, DC, IM> {
     }
 
     /// Change the codec types of this iterator, specifying the codecs.
-    pub fn remap_types<KC2, DC2>(self) -> RoRevRange<'txn, KC2, DC2, IM> {
+    pub fn remap_types<KC2, DC2>(self) -> RoRevRange<'txn, KC2, DC2, IM, E> {
         RoRevRange {
             cursor: self.cursor,
             move_on_end: self.move_on_end,
@@ -538,28 +568,30 @@ impl<'txn, KC, DC, IM> RoRevRange<'txn, KC, DC, IM> {
     }
 
     /// Change the key codec type of this iterator, specifying the new codec.
-    pub fn remap_key_type<KC2>(self) -> RoRevRange<'txn, KC2, DC, IM> {
+    pub fn remap_key_type<KC2>(self) -> RoRevRange<'txn, KC2, DC, IM, E> {
         self.remap_types::<KC2, DC>()
     }
 
     /// Change the data codec type of this iterator, specifying the new codec.
-    pub fn remap_data_type<DC2>(self) -> RoRevRange<'txn, KC, DC2, IM> {
+    pub fn remap_data_type<DC2>(self) -> RoRevRange<'txn, KC, DC2, IM, E> {
         self.remap_types::<KC, DC2>()
     }
 
     /// Wrap the data bytes into a lazy decoder.
-    pub fn lazily_decode_data(self) -> RoRevRange<'txn, KC, LazyDecode<DC>, IM> {
+    pub fn lazily_decode_data(self) -> RoRevRange<'txn, KC, LazyDecode<DC>, IM, E> {
         self.remap_types::<KC, LazyDecode<DC>>()
     }
 }
 
-impl<'txn, KC, DC, IM> Iterator for RoRevRange<'txn, KC, DC, IM>
+impl<'txn, KC, DC, IM, E> Iterator for RoRevRange<'txn, KC, DC, IM, E>
 where
     KC: BytesDecode<'txn>,
     DC: BytesDecode<'txn>,
+    KC::Error: Into<E>,
+    DC::Error: Into<E>,
     IM: IterationMethod,
 {
-    type Item = Result<(KC::DItem, DC::DItem)>;
+    type Item = Result<(KC::DItem, DC::DItem), Error<E>>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let result = if self.move_on_end {
@@ -572,15 +604,18 @@ where
         match result {
             Ok(Some((key, data))) => {
                 let must_be_returned = match &self.start_bound {
-                    Bound::Included(start) => key >= start,
-                    Bound::Excluded(start) => key > start,
+                    Bound::Included(start) => self.cursor.compare_keys(key, start).is_ge(),
+                    Bound::Excluded(start) => self.cursor.compare_keys(key, start).is_gt(),
                     Bound::Unbounded => true,
                 };
 
                 if must_be_returned {
-                    match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+                    match (
+                        KC::bytes_decode(key).map_err(Into::into),
+                        DC::bytes_decode(data).map_err(Into::into),
+                    ) {
                         (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                        (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                        (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
                     }
                 } else {
                     None
@@ -609,15 +644,18 @@ where
         match result {
             Ok(Some((key, data))) => {
                 let must_be_returned = match &self.end_bound {
-                    Bound::Included(end) => key <= end,
-                    Bound::Excluded(end) => key < end,
+                    Bound::Included(end) => self.cursor.compare_keys(key, end).is_le(),
+                    Bound::Excluded(end) => self.cursor.compare_keys(key, end).is_lt(),
                     Bound::Unbounded => true,
                 };
 
                 if must_be_returned {
-                    match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+                    match (
+                        KC::bytes_decode(key).map_err(Into::into),
+                        DC::bytes_decode(data).map_err(Into::into),
+                    ) {
                         (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                        (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                        (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
                     }
                 } else {
                     None
@@ -629,30 +667,30 @@ where
     }
 }
 
-impl<KC, DC, IM> fmt::Debug for RoRevRange<'_, KC, DC, IM> {
+impl<KC, DC, IM, E> fmt::Debug for RoRevRange<'_, KC, DC, IM, E> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RoRevRange").finish()
     }
 }
 
 #[cfg(feature = "read-txn-no-tls")]
-unsafe impl<KC, DC, IM> Send for RoRevRange<'_, KC, DC, IM> {}
+unsafe impl<KC, DC, IM, E> Send for RoRevRange<'_, KC, DC, IM, E> {}
 
 /// A reverse read-write range iterator structure.
-pub struct RwRevRange<'txn, KC, DC, IM = MoveThroughDuplicateValues> {
+pub struct RwRevRange<'txn, KC, DC, IM = MoveThroughDuplicateValues, E = BoxedError> {
     cursor: RwCursor<'txn>,
     move_on_end: bool,
-    start_bound: Bound<Vec<u8>>,
-    end_bound: Bound<Vec<u8>>,
-    _phantom: marker::PhantomData<(KC, DC, IM)>,
+    start_bound: Bound<ScratchBuffer<'txn>>,
+    end_bound: Bound<ScratchBuffer<'txn>>,
+    _phantom: marker::PhantomData<(KC, DC, IM, E)>,
 }
 
-impl<'txn, KC, DC, IM> RwRevRange<'txn, KC, DC, IM> {
+impl<'txn, KC, DC, IM, E> RwRevRange<'txn, KC, DC, IM, E> {
     pub(crate) fn new(
         cursor: RwCursor<'txn>,
-        start_bound: Bound<Vec<u8>>,
-        end_bound: Bound<Vec<u8>>,
-    ) -> RwRevRange<'txn, KC, DC, IM> {
+        start_bound: Bound<ScratchBuffer<'txn>>,
+        end_bound: Bound<ScratchBuffer<'txn>>,
+    ) -> RwRevRange<'txn, KC, DC, IM, E> {
         RwRevRange {
             cursor,
             move_on_end: true,
@@ -675,7 +713,7 @@ impl<'txn, KC, DC, IM> RwRevRange<'txn, KC, DC, IM> {
     /// > or the end of the transaction.](http://www.lmdb.tech/doc/group__mdb.html#structMDB__val)
     ///
     /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
-    pub unsafe fn del_current(&mut self) -> Result<bool> {
+    pub unsafe fn del_current(&mut self) -> Result<bool, Error<E>> {
         self.cursor.del_current()
     }
 
@@ -706,14 +744,19 @@ impl<'txn, KC, DC, IM> RwRevRange<'txn, KC, DC, IM> {
         &mut self,
         key: &'a KC::EItem,
         data: &'a DC::EItem,
-    ) -> Result<bool>
+    ) -> Result<bool, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         DC: BytesEncode<'a>,
+        <DC as BytesEncode<'a>>::Error: Into<E>,
     {
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        let data_bytes: Cow<[u8]> = DC::bytes_encode(data).map_err(Error::Encoding)?;
-        self.cursor.put_current(&key_bytes, &data_bytes)
+        let txn = self.cursor.txn();
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer.encode::<KC>(key).map_err(Error::encoding)?;
+        let mut data_buffer = txn.data_buffer();
+        let data_bytes = data_buffer.encode::<DC>(data).map_err(Error::encoding)?;
+        self.cursor.put_current(key_bytes, data_bytes)
     }
 
     /// Write a new value to the current entry. The entry is written with the specified flags.
@@ -735,13 +778,15 @@ impl<'txn, KC, DC, IM> RwRevRange<'txn, KC, DC, IM> {
         key: &'a KC::EItem,
         data_size: usize,
         write_func: F,
-    ) -> Result<bool>
+    ) -> Result<bool, Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         F: FnOnce(&mut ReservedSpace) -> io::Result<()>,
     {
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        self.cursor.put_current_reserved_with_flags(flags, &key_bytes, data_size, write_func)
+        let mut key_buffer = self.cursor.txn().key_buffer();
+        let key_bytes = key_buffer.encode::<KC>(key).map_err(Error::encoding)?;
+        self.cursor.put_current_reserved_with_flags(flags, key_bytes, data_size, write_func)
     }
 
     /// Insert a key-value pair in this database. The entry is written with the specified flags and data codec.
@@ -766,20 +811,25 @@ impl<'txn, KC, DC, IM> RwRevRange<'txn, KC, DC, IM> {
         flags: PutFlags,
         key: &'a KC::EItem,
         data: &'a NDC::EItem,
-    ) -> Result<()>
+    ) -> Result<(), Error<E>>
     where
         KC: BytesEncode<'a>,
+        <KC as BytesEncode<'a>>::Error: Into<E>,
         NDC: BytesEncode<'a>,
+        <NDC as BytesEncode<'a>>::Error: Into<E>,
     {
-        let key_bytes: Cow<[u8]> = KC::bytes_encode(key).map_err(Error::Encoding)?;
-        let data_bytes: Cow<[u8]> = NDC::bytes_encode(data).map_err(Error::Encoding)?;
-        self.cursor.put_current_with_flags(flags, &key_bytes, &data_bytes)
+        let txn = self.cursor.txn();
+        let mut key_buffer = txn.key_buffer();
+        let key_bytes = key_buffer.encode::<KC>(key).map_err(Error::encoding)?;
+        let mut data_buffer = txn.data_buffer();
+        let data_bytes = data_buffer.encode::<NDC>(data).map_err(Error::encoding)?;
+        self.cursor.put_current_with_flags(flags, key_bytes, data_bytes)
     }
 
     /// Move on the first value of keys, ignoring duplicate values.
     ///
     /// For more info, see [`RoIter::move_between_keys`].
-    pub fn move_between_keys(self) -> RwRevRange<'txn, KC, DC, MoveBetweenKeys> {
+    pub fn move_between_keys(self) -> RwRevRange<'txn, KC, DC, MoveBetweenKeys, E> {
         RwRevRange {
             cursor: self.cursor,
             move_on_end: self.move_on_end,
@@ -794,7 +844,7 @@ impl<'txn, KC, DC, IM> RwRevRange<'txn, KC, DC, IM> {
     /// For more info, see [`RoIter::move_through_duplicate_values`].
     pub fn move_through_duplicate_values(
         self,
-    ) -> RwRevRange<'txn, KC, DC, MoveThroughDuplicateValues> {
+    ) -> RwRevRange<'txn, KC, DC, MoveThroughDuplicateValues, E> {
         RwRevRange {
             cursor: self.cursor,
             move_on_end: self.move_on_end,
@@ -805,7 +855,7 @@ impl<'txn, KC, DC, IM> RwRevRange<'txn, KC, DC, IM> {
     }
 
     /// Change the codec types of this iterator, specifying the codecs.
-    pub fn remap_types<KC2, DC2>(self) -> RwRevRange<'txn, KC2, DC2, IM> {
+    pub fn remap_types<KC2, DC2>(self) -> RwRevRange<'txn, KC2, DC2, IM, E> {
         RwRevRange {
             cursor: self.cursor,
             move_on_end: self.move_on_end,
@@ -816,28 +866,30 @@ impl<'txn, KC, DC, IM> RwRevRange<'txn, KC, DC, IM> {
     }
 
     /// Change the key codec type of this iterator, specifying the new codec.
-    pub fn remap_key_type<KC2>(self) -> RwRevRange<'txn, KC2, DC, IM> {
+    pub fn remap_key_type<KC2>(self) -> RwRevRange<'txn, KC2, DC, IM, E> {
         self.remap_types::<KC2, DC>()
     }
 
     /// Change the data codec type of this iterator, specifying the new codec.
-    pub fn remap_data_type<DC2>(self) -> RwRevRange<'txn, KC, DC2, IM> {
+    pub fn remap_data_type<DC2>(self) -> RwRevRange<'txn, KC, DC2, IM, E> {
         self.remap_types::<KC, DC2>()
     }
 
     /// Wrap the data bytes into a lazy decoder.
-    pub fn lazily_decode_data(self) -> RwRevRange<'txn, KC, LazyDecode<DC>, IM> {
+    pub fn lazily_decode_data(self) -> RwRevRange<'txn, KC, LazyDecode<DC>, IM, E> {
         self.remap_types::<KC, LazyDecode<DC>>()
     }
 }
 
-impl<'txn, KC, DC, IM> Iterator for RwRevRange<'txn, KC, DC, IM>
+impl<'txn, KC, DC, IM, E> Iterator for RwRevRange<'txn, KC, DC, IM, E>
 where
     KC: BytesDecode<'txn>,
     DC: BytesDecode<'txn>,
+    KC::Error: Into<E>,
+    DC::Error: Into<E>,
     IM: IterationMethod,
 {
-    type Item = Result<(KC::DItem, DC::DItem)>;
+    type Item = Result<(KC::DItem, DC::DItem), Error<E>>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let result = if self.move_on_end {
@@ -850,15 +902,18 @@ where
         match result {
             Ok(Some((key, data))) => {
                 let must_be_returned = match &self.start_bound {
-                    Bound::Included(start) => key >= start,
-                    Bound::Excluded(start) => key > start,
+                    Bound::Included(start) => self.cursor.compare_keys(key, start).is_ge(),
+                    Bound::Excluded(start) => self.cursor.compare_keys(key, start).is_gt(),
                     Bound::Unbounded => true,
                 };
 
                 if must_be_returned {
-                    match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+                    match (
+                        KC::bytes_decode(key).map_err(Into::into),
+                        DC::bytes_decode(data).map_err(Into::into),
+                    ) {
                         (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                        (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                        (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
                     }
                 } else {
                     None
@@ -887,15 +942,18 @@ where
         match result {
             Ok(Some((key, data))) => {
                 let must_be_returned = match &self.end_bound {
-                    Bound::Included(end) => key <= end,
-                    Bound::Excluded(end) => key < end,
+                    Bound::Included(end) => self.cursor.compare_keys(key, end).is_le(),
+                    Bound::Excluded(end) => self.cursor.compare_keys(key, end).is_lt(),
                     Bound::Unbounded => true,
                 };
 
                 if must_be_returned {
-                    match (KC::bytes_decode(key), DC::bytes_decode(data)) {
+                    match (
+                        KC::bytes_decode(key).map_err(Into::into),
+                        DC::bytes_decode(data).map_err(Into::into),
+                    ) {
                         (Ok(key), Ok(data)) => Some(Ok((key, data))),
-                        (Err(e), _) | (_, Err(e)) => Some(Err(Error::Decoding(e))),
+                        (Err(e), _) | (_, Err(e)) => Some(Err(self.cursor.decoding_error(key, e))),
                     }
                 } else {
                     None
@@ -907,7 +965,7 @@ where
     }
 }
 
-impl<KC, DC, IM> fmt::Debug for RwRevRange<'_, KC, DC, IM> {
+impl<KC, DC, IM, E> fmt::Debug for RwRevRange<'_, KC, DC, IM, E> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RwRevRange").finish()
     }
@@ -62,27 +62,49 @@
 //! ```
 #![warn(missing_docs)]
 
+#[cfg(feature = "collation")]
+mod collation;
+mod comparator;
 pub mod cookbook;
 mod cursor;
 mod database;
+mod dump;
+mod dyn_comparator;
 mod env;
 pub mod iteration_method;
 mod iterator;
 mod mdb;
 mod reserved_space;
 mod txn;
+mod verify;
 
 use std::ffi::CStr;
 use std::{error, fmt, io, mem, result};
 
+#[cfg(feature = "derive")]
+pub use heed_derive::HeedCodec;
+#[cfg(feature = "collation")]
+pub use {icu_collator, icu_locid};
+// The derived codecs refer to heed by its name, even in its own tests.
+#[cfg(all(test, feature = "derive"))]
+extern crate self as heed;
 use heed_traits as traits;
 pub use {byteorder, heed_types as types};
 
+#[cfg(feature = "collation")]
+pub use self::collation::{CollationLocale, CollatorComparator, RootCollation};
+pub use self::comparator::{
+    AsciiCaseInsensitiveComparator, BigEndianSignedComparator, DefaultComparator,
+    NaturalOrderComparator, ReverseLexicographicComparator,
+};
 use self::cursor::{RoCursor, RwCursor};
-pub use self::database::{Database, DatabaseOpenOptions, DatabaseStat};
+pub use self::database::{
+    Database, DatabaseOpenOptions, DatabaseStat, DynDatabaseOpenOptions, IntegerCodec,
+    IntegerKeyDatabase,
+};
+pub use self::dyn_comparator::{DynComparator, RuntimeComparator};
 pub use self::env::{
-    env_closing_event, CompactionOption, DefaultComparator, Env, EnvClosingEvent, EnvInfo,
-    EnvOpenOptions, FlagSetMode,
+    env_closing_event, CompactionOption, Env, EnvClosingEvent, EnvInfo, EnvOpenOptions, FlagSetMode,
 };
 pub use self::iterator::{
     RoIter, RoPrefix, RoRange, RoRevIter, RoRevPrefix, RoRevRange, RwIter, RwPrefix, RwRange,
@@ -92,8 +114,12 @@ pub use self::mdb::error::Error as MdbError;
 use self::mdb::ffi::{from_val, into_val};
 pub use self::mdb::flags::{DatabaseFlags, EnvFlags, PutFlags};
 pub use self::reserved_space::ReservedSpace;
-pub use self::traits::{BoxedError, BytesDecode, BytesEncode, Comparator, LexicographicComparator};
+pub use self::traits::{
+    BoxedBytesDecode, BoxedBytesEncode, BoxedError, BytesDecode, BytesDecodeOwned, BytesEncode,
+    Comparator, EncodeIntoWriterError, LexicographicComparator,
+};
 pub use self::txn::{RoTxn, RwTxn};
+pub use self::verify::{Anomaly, AnomalyKind, InvalidEntry, ValidationReport, VerifyReport};
 
 /// The underlying LMDB library version information.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -139,16 +165,21 @@ pub fn lmdb_version() -> LmdbVersion {
 }
 
 /// An error that encapsulates all possible errors in this crate.
+///
+/// The encoding and decoding errors are of type `E`, which is a [`BoxedError`] by default.
+/// A database can return the typed errors of its codecs with [`Database::remap_error_type`],
+/// making it possible to match on them without downcasting.
 #[derive(Debug)]
-pub enum Error {
+#[non_exhaustive]
+pub enum Error<E = BoxedError> {
     /// I/O error: can come from the standard library or be a rewrapped [`MdbError`].
     Io(io::Error),
     /// LMDB error.
     Mdb(MdbError),
     /// Encoding error.
-    Encoding(BoxedError),
+    Encoding(E),
     /// Decoding error.
-    Decoding(BoxedError),
+    Decoding(E),
     /// Database closing in progress.
     DatabaseClosing,
     /// Attempt to open [`Env`] with different options.
@@ -158,9 +189,68 @@ pub enum Error {
         /// The env opened with the original options.
         env: Env,
     },
+    /// Too many databases are using a runtime comparator, see
+    /// [`DatabaseOpenOptions::dyn_key_comparator`].
+    DynComparatorsFull,
+    /// An error along with the context in which it happened.
+    ///
+    /// Only returned when [`EnvOpenOptions::error_context`] is enabled.
+    WithContext {
+        /// The error that happened.
+        error: Box<Error<E>>,
+        /// The context in which the error happened.
+        context: Box<ErrorContext>,
+    },
 }
 
-impl fmt::Display for Error {
+impl<E> Error<E> {
+    /// Returns the context in which this error happened, if any.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Error::WithContext { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Returns this error without its context.
+    ///
+    /// It is useful to match on the error regardless of [`EnvOpenOptions::error_context`].
+    pub fn without_context(&self) -> &Error<E> {
+        match self {
+            Error::WithContext { error, .. } => error,
+            error => error,
+        }
+    }
+
+    /// Converts the encoding or decoding error of this error with the given function,
+    /// keeping its context.
+    ///
+    /// It is useful to turn a typed error into a boxed one with `error.map_codec_error(Into::into)`.
+    pub fn map_codec_error<F>(self, op: impl FnOnce(E) -> F) -> Error<F> {
+        match self {
+            Error::Io(error) => Error::Io(error),
+            Error::Mdb(error) => Error::Mdb(error),
+            Error::Encoding(error) => Error::Encoding(op(error)),
+            Error::Decoding(error) => Error::Decoding(op(error)),
+            Error::DatabaseClosing => Error::DatabaseClosing,
+            Error::BadOpenOptions { options, env } => Error::BadOpenOptions { options, env },
+            Error::DynComparatorsFull => Error::DynComparatorsFull,
+            Error::WithContext { error, context } => {
+                Error::WithContext { error: Box::new(error.map_codec_error(op)), context }
+            }
+        }
+    }
+
+    /// Converts the error of a codec that encoded an item into a writer.
+    pub(crate) fn encoding<CE: Into<E>>(error: EncodeIntoWriterError<CE>) -> Error<E> {
+        match error {
+            EncodeIntoWriterError::Encoding(error) => Error::Encoding(error.into()),
+            EncodeIntoWriterError::Io(error) => Error::Io(error),
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for Error<E> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Error::Io(error) => write!(f, "{}", error),
@@ -173,14 +263,99 @@ impl fmt::Display for Error {
             Error::BadOpenOptions { .. } => {
                 f.write_str("an environment is already opened with different options")
             }
+            Error::DynComparatorsFull => {
+                f.write_str("too many databases are using a runtime comparator")
+            }
+            Error::WithContext { error, context } => write!(f, "{} ({})", error, context),
         }
     }
 }
 
-impl error::Error for Error {}
+impl<E: fmt::Debug + fmt::Display + 'static> error::Error for Error<E> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::WithContext { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// The context in which an [`Error`] happened.
+///
+/// Only attached to the errors when [`EnvOpenOptions::error_context`] is enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// The name of the database, `None` for the unnamed database.
+    pub database: Option<String>,
+    /// The operation that failed.
+    pub operation: Operation,
+    /// The key in hexadecimal, truncated to [`ErrorContext::MAX_KEY_BYTES`] bytes.
+    ///
+    /// It is `None` if the key was not known, e.g. when the key failed to be encoded.
+    pub key: Option<String>,
+    /// The length of the key in bytes.
+    pub key_len: Option<usize>,
+}
+
+impl ErrorContext {
+    /// The maximum number of bytes of the key that are kept in the context.
+    pub const MAX_KEY_BYTES: usize = 32;
+
+    pub(crate) fn new(
+        database: Option<String>,
+        operation: Operation,
+        key: Option<&[u8]>,
+    ) -> ErrorContext {
+        let key_len = key.map(<[u8]>::len);
+        let key = key.map(|key| {
+            let truncated = &key[..key.len().min(Self::MAX_KEY_BYTES)];
+            dump::hex(truncated)
+        });
+        ErrorContext { database, operation, key, key_len }
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} on ", self.operation)?;
+        match &self.database {
+            Some(name) => write!(f, "database {:?}", name)?,
+            None => f.write_str("the unnamed database")?,
+        }
+        if let (Some(key), Some(key_len)) = (&self.key, self.key_len) {
+            let ellipsis = if key_len > Self::MAX_KEY_BYTES { "..." } else { "" };
+            write!(f, " with key {}{} ({} bytes)", key, ellipsis, key_len)?;
+        }
+        Ok(())
+    }
+}
+
+/// The operations that can be described by an [`ErrorContext`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// Reading an entry, e.g. [`Database::get`].
+    Get,
+    /// Writing an entry, e.g. [`Database::put`].
+    Put,
+    /// Deleting entries, e.g. [`Database::delete`].
+    Delete,
+    /// Iterating over entries, e.g. [`Database::iter`].
+    Iteration,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operation::Get => f.write_str("get"),
+            Operation::Put => f.write_str("put"),
+            Operation::Delete => f.write_str("delete"),
+            Operation::Iteration => f.write_str("iteration"),
+        }
+    }
+}
 
-impl From<MdbError> for Error {
-    fn from(error: MdbError) -> Error {
+impl<E> From<MdbError> for Error<E> {
+    fn from(error: MdbError) -> Error<E> {
         match error {
             MdbError::Other(e) => Error::Io(io::Error::from_raw_os_error(e)),
             _ => Error::Mdb(error),
@@ -188,14 +363,14 @@ impl From<MdbError> for Error {
     }
 }
 
-impl From<io::Error> for Error {
-    fn from(error: io::Error) -> Error {
+impl<E> From<io::Error> for Error<E> {
+    fn from(error: io::Error) -> Error<E> {
         Error::Io(error)
     }
 }
 
 /// Either a success or an [`Error`].
-pub type Result<T> = result::Result<T, Error>;
+pub type Result<T, E = Error> = result::Result<T, E>;
 
 /// An unspecified type.
 ///
@@ -233,7 +408,7 @@ mod tests {
     fn error_is_send_sync() {
         fn give_me_send_sync<T: Send + Sync>(_: T) {}
 
-        let error = Error::Encoding(Box::from("There is an issue, you know?"));
+        let error: Error = Error::Encoding(Box::from("There is an issue, you know?"));
         give_me_send_sync(error);
     }
 }
@@ -1,7 +1,11 @@
 use std::borrow::Cow;
-use std::ops::Deref;
-use std::ptr;
+use std::cell::Cell;
+use std::ops::{Deref, DerefMut};
+use std::{mem, ptr};
 
+use heed_traits::{BytesEncode, EncodeIntoWriterError};
+
+use crate::dyn_comparator;
 use crate::mdb::error::mdb_result;
 use crate::mdb::ffi;
 use crate::{Env, Result};
@@ -27,7 +31,13 @@ use crate::{Env, Result};
 /// You may increase the limit by editing it **at your own risk**: `/Library/LaunchDaemons/sysctl.plist`
 pub struct RoTxn<'e> {
     pub(crate) txn: *mut ffi::MDB_txn,
-    env: Cow<'e, Env>,
+    pub(crate) env: Cow<'e, Env>,
+    buffers: ScratchBuffers,
+    /// The databases first opened by this transaction, LMDB closes them
+    /// and their runtime comparators are released if it is aborted.
+    opened_dbis: Cell<Vec<ffi::MDB_dbi>>,
+    /// The databases first opened by the parent transactions, not committed yet.
+    parent_opened_dbis: Vec<ffi::MDB_dbi>,
 }
 
 impl<'e> RoTxn<'e> {
@@ -43,7 +53,7 @@ impl<'e> RoTxn<'e> {
             ))?
         };
 
-        Ok(RoTxn { txn, env: Cow::Borrowed(env) })
+        Ok(RoTxn::from_raw(txn, Cow::Borrowed(env)))
     }
 
     pub(crate) fn static_read_txn(env: Env) -> Result<RoTxn<'static>> {
@@ -58,13 +68,76 @@ impl<'e> RoTxn<'e> {
             ))?
         };
 
-        Ok(RoTxn { txn, env: Cow::Owned(env) })
+        Ok(RoTxn::from_raw(txn, Cow::Owned(env)))
+    }
+
+    fn from_raw(txn: *mut ffi::MDB_txn, env: Cow<'e, Env>) -> RoTxn<'e> {
+        RoTxn {
+            txn,
+            env,
+            buffers: ScratchBuffers::default(),
+            opened_dbis: Cell::default(),
+            parent_opened_dbis: Vec::new(),
+        }
     }
 
     pub(crate) fn env_mut_ptr(&self) -> *mut ffi::MDB_env {
         self.env.env_mut_ptr()
     }
 
+    /// Records a database opened by this transaction and returns whether
+    /// it was not opened by a committed or a parent transaction yet.
+    pub(crate) fn record_opened_dbi(&self, dbi: ffi::MDB_dbi) -> bool {
+        let mut dbis = self.opened_dbis.take();
+        let new = !dbis.contains(&dbi)
+            && !self.parent_opened_dbis.contains(&dbi)
+            && !self.env.is_dbi_opened(dbi);
+        if new {
+            dbis.push(dbi);
+        }
+        self.opened_dbis.set(dbis);
+        new
+    }
+
+    /// Aborts the transaction, LMDB closes the databases it opened
+    /// and their runtime comparators are released.
+    fn abort_and_release(&mut self) {
+        abort_txn(self.txn);
+        self.txn = ptr::null_mut();
+        self.release_opened_dbis();
+    }
+
+    /// Releases the runtime comparators of the databases LMDB closed with this transaction.
+    fn release_opened_dbis(&self) {
+        dyn_comparator::release(self.env_mut_ptr(), &self.opened_dbis.take());
+    }
+
+    /// Tells the environment about the databases this transaction opened, once committed.
+    fn commit_opened_dbis(&mut self) {
+        self.env.commit_opened_dbis(self.opened_dbis.get_mut());
+    }
+
+    /// Borrows the buffer reused by this transaction to encode the keys.
+    pub(crate) fn key_buffer(&self) -> ScratchBuffer<'_> {
+        ScratchBuffer::take(&self.buffers.key)
+    }
+
+    /// Borrows the buffer reused by this transaction to encode the data.
+    pub(crate) fn data_buffer(&self) -> ScratchBuffer<'_> {
+        ScratchBuffer::take(&self.buffers.data)
+    }
+
+    /// Borrows the buffer reused by this transaction to encode the start bound
+    /// or the prefix of an iterator, it is given back once the iterator is dropped.
+    pub(crate) fn start_bound_buffer(&self) -> ScratchBuffer<'_> {
+        ScratchBuffer::take(&self.buffers.start_bound)
+    }
+
+    /// Borrows the buffer reused by this transaction to encode the end bound of an iterator.
+    pub(crate) fn end_bound_buffer(&self) -> ScratchBuffer<'_> {
+        ScratchBuffer::take(&self.buffers.end_bound)
+    }
+
     /// Commit a read transaction.
     ///
     /// Synchronizing some [`Env`] metadata with the global handle.
@@ -77,14 +150,24 @@ impl<'e> RoTxn<'e> {
     pub fn commit(mut self) -> Result<()> {
         let result = unsafe { mdb_result(ffi::mdb_txn_commit(self.txn)) };
         self.txn = ptr::null_mut();
-        result.map_err(Into::into)
+        // A failed commit aborts the transaction.
+        match result {
+            Ok(()) => {
+                self.commit_opened_dbis();
+                Ok(())
+            }
+            Err(e) => {
+                self.release_opened_dbis();
+                Err(e.into())
+            }
+        }
     }
 }
 
 impl Drop for RoTxn<'_> {
     fn drop(&mut self) {
         if !self.txn.is_null() {
-            abort_txn(self.txn);
+            self.abort_and_release();
         }
     }
 }
@@ -92,6 +175,97 @@ impl Drop for RoTxn<'_> {
 #[cfg(feature = "read-txn-no-tls")]
 unsafe impl Send for RoTxn<'_> {}
 
+/// The buffers kept by a transaction to encode the keys, the data
+/// and the bounds of the iterators without allocating.
+#[derive(Default)]
+struct ScratchBuffers {
+    key: Cell<Vec<u8>>,
+    data: Cell<Vec<u8>>,
+    start_bound: Cell<Vec<u8>>,
+    end_bound: Cell<Vec<u8>>,
+}
+
+/// A buffer taken from a transaction, it is given back when dropped.
+pub(crate) struct ScratchBuffer<'t> {
+    cell: &'t Cell<Vec<u8>>,
+    buffer: Vec<u8>,
+}
+
+impl<'t> ScratchBuffer<'t> {
+    fn take(cell: &'t Cell<Vec<u8>>) -> ScratchBuffer<'t> {
+        ScratchBuffer { cell, buffer: cell.take() }
+    }
+
+    /// Encodes the item with the given codec.
+    ///
+    /// The bytes are written into the reused buffer if the codec encodes the item
+    /// into a writer, the bytes returned by the codec are used otherwise.
+    pub(crate) fn encode<'a, 'b, C>(
+        &'b mut self,
+        item: &'a C::EItem,
+    ) -> std::result::Result<&'b [u8], EncodeIntoWriterError<C::Error>>
+    where
+        'a: 'b,
+        C: BytesEncode<'a>,
+    {
+        self.buffer.clear();
+        if C::encodes_into_writer(item) {
+            C::bytes_encode_into_writer(item, &mut self.buffer)?;
+        } else {
+            match C::bytes_encode(item).map_err(EncodeIntoWriterError::Encoding)? {
+                Cow::Borrowed(bytes) => return Ok(bytes),
+                Cow::Owned(bytes) => self.buffer = bytes,
+            }
+        }
+        Ok(&self.buffer)
+    }
+
+    /// Encodes the item with the given codec into the reused buffer,
+    /// for the bytes that are kept, like the bounds of an iterator.
+    pub(crate) fn encode_to_buffer<'a, C: BytesEncode<'a>>(
+        &mut self,
+        item: &'a C::EItem,
+    ) -> std::result::Result<(), EncodeIntoWriterError<C::Error>> {
+        self.buffer.clear();
+        if C::encodes_into_writer(item) {
+            C::bytes_encode_into_writer(item, &mut self.buffer)?;
+        } else {
+            match C::bytes_encode(item).map_err(EncodeIntoWriterError::Encoding)? {
+                Cow::Borrowed(bytes) => self.buffer.extend_from_slice(bytes),
+                Cow::Owned(bytes) => self.buffer = bytes,
+            }
+        }
+        Ok(())
+    }
+
+    /// Copies the bytes into the reused buffer, e.g. a value LMDB is about to replace.
+    pub(crate) fn copy(&mut self, bytes: &[u8]) -> &[u8] {
+        self.buffer.clear();
+        self.buffer.extend_from_slice(bytes);
+        &self.buffer
+    }
+}
+
+impl Deref for ScratchBuffer<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.buffer
+    }
+}
+
+impl DerefMut for ScratchBuffer<'_> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.buffer
+    }
+}
+
+impl Drop for ScratchBuffer<'_> {
+    fn drop(&mut self) {
+        self.cell.set(mem::take(&mut self.buffer));
+    }
+}
+
 fn abort_txn(txn: *mut ffi::MDB_txn) {
     // Asserts that the transaction hasn't been already committed.
     assert!(!txn.is_null());
@@ -118,6 +292,9 @@ fn abort_txn(txn: *mut ffi::MDB_txn) {
 /// You may increase the limit by editing it **at your own risk**: `/Library/LaunchDaemons/sysctl.plist`
 pub struct RwTxn<'p> {
     pub(crate) txn: RoTxn<'p>,
+    /// The databases opened by the parent transaction, that keeps
+    /// the ones of this nested transaction once committed.
+    parent_opened_dbis: Option<&'p Cell<Vec<ffi::MDB_dbi>>>,
 }
 
 impl<'p> RwTxn<'p> {
@@ -126,7 +303,7 @@ impl<'p> RwTxn<'p> {
 
         unsafe { mdb_result(ffi::mdb_txn_begin(env.env_mut_ptr(), ptr::null_mut(), 0, &mut txn))? };
 
-        Ok(RwTxn { txn: RoTxn { txn, env: Cow::Borrowed(env) } })
+        Ok(RwTxn { txn: RoTxn::from_raw(txn, Cow::Borrowed(env)), parent_opened_dbis: None })
     }
 
     pub(crate) fn nested(env: &'p Env, parent: &'p mut RwTxn) -> Result<RwTxn<'p>> {
@@ -135,7 +312,10 @@ impl<'p> RwTxn<'p> {
 
         unsafe { mdb_result(ffi::mdb_txn_begin(env.env_mut_ptr(), parent_ptr, 0, &mut txn))? };
 
-        Ok(RwTxn { txn: RoTxn { txn, env: Cow::Borrowed(env) } })
+        let mut txn = RoTxn::from_raw(txn, Cow::Borrowed(env));
+        txn.parent_opened_dbis = parent.txn.parent_opened_dbis.clone();
+        txn.parent_opened_dbis.extend_from_slice(parent.txn.opened_dbis.get_mut());
+        Ok(RwTxn { txn, parent_opened_dbis: Some(&parent.txn.opened_dbis) })
     }
 
     pub(crate) fn env_mut_ptr(&self) -> *mut ffi::MDB_env {
@@ -147,14 +327,29 @@ impl<'p> RwTxn<'p> {
     pub fn commit(mut self) -> Result<()> {
         let result = unsafe { mdb_result(ffi::mdb_txn_commit(self.txn.txn)) };
         self.txn.txn = ptr::null_mut();
-        result.map_err(Into::into)
+        match (result, self.parent_opened_dbis) {
+            (Ok(()), None) => {
+                self.txn.commit_opened_dbis();
+                Ok(())
+            }
+            (Ok(()), Some(parent)) => {
+                let mut dbis = parent.take();
+                dbis.append(self.txn.opened_dbis.get_mut());
+                parent.set(dbis);
+                Ok(())
+            }
+            // A failed commit aborts the transaction.
+            (Err(e), _) => {
+                self.txn.release_opened_dbis();
+                Err(e.into())
+            }
+        }
     }
 
     /// Abandon all the operations of the transaction instead of saving them.
     /// The transaction is reset.
     pub fn abort(mut self) {
-        abort_txn(self.txn.txn);
-        self.txn.txn = ptr::null_mut();
+        self.txn.abort_and_release();
     }
 }
 
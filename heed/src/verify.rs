@@ -0,0 +1,208 @@
+use std::cmp::Ordering;
+
+use crate::cursor::MoveOperation;
+use crate::mdb::error::mdb_result;
+use crate::mdb::ffi;
+use crate::mdb::lmdb_flags::AllDatabaseFlags;
+use crate::{BoxedError, BytesDecode, Error, MdbError, Result, RoCursor, RoTxn};
+
+/// The result of an integrity check, see [`Env::verify`](crate::Env::verify)
+/// and [`Database::verify`](crate::Database::verify).
+#[derive(Debug, Clone, Default)]
+pub struct VerifyReport {
+    /// Number of databases that were traversed.
+    pub number_of_databases: usize,
+    /// Number of entries that were read, the duplicates included.
+    pub number_of_entries: usize,
+    /// Number of anomalies that were found.
+    pub number_of_anomalies: usize,
+    /// The first anomalies that were found, up to the maximum given to the verify function.
+    pub anomalies: Vec<Anomaly>,
+    /// The names of the databases that were traversed without checking their ordering,
+    /// they were not opened before the verification and their comparator is unknown.
+    pub unordered_databases: Vec<String>,
+}
+
+impl VerifyReport {
+    /// Returns `true` if no anomaly was found.
+    pub fn is_ok(&self) -> bool {
+        self.number_of_anomalies == 0
+    }
+
+    pub(crate) fn record(
+        &mut self,
+        max_anomalies: usize,
+        database: Option<&str>,
+        key: &[u8],
+        kind: AnomalyKind,
+    ) {
+        self.number_of_anomalies += 1;
+        if self.anomalies.len() < max_anomalies {
+            let database = database.map(ToOwned::to_owned);
+            self.anomalies.push(Anomaly { database, key: key.to_vec(), kind });
+        }
+    }
+}
+
+/// An anomaly found while verifying a database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Anomaly {
+    /// The name of the database in which the anomaly was found.
+    ///
+    /// It is `None` for the unnamed database and when verifying a single [`Database`](crate::Database).
+    pub database: Option<String>,
+    /// The raw key of the entry, it is the last valid key when the anomaly
+    /// is an [`AnomalyKind::Corrupted`] one.
+    pub key: Vec<u8>,
+    /// The kind of anomaly.
+    pub kind: AnomalyKind,
+}
+
+/// The different kinds of anomalies that can be found while verifying a database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnomalyKind {
+    /// The key is not strictly greater than the previous key
+    /// according to the comparator of the database.
+    UnorderedKey,
+    /// The data is not strictly greater than the previous data of the same key
+    /// according to the duplicate comparator of a `DUP_SORT` database.
+    UnorderedDuplicate,
+    /// The traversal of the database stopped because a page could not be read.
+    Corrupted(MdbError),
+    /// The key could not be decoded with the key codec of the database.
+    KeyDecoding(String),
+    /// The data could not be decoded with the data codec of the database.
+    DataDecoding(String),
+}
+
+/// The result of a codec validation, see [`Database::validate`](crate::Database::validate).
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    /// Number of entries that were decoded, the duplicates included.
+    pub number_of_entries: usize,
+    /// Number of entries that could not be decoded.
+    pub number_of_invalid_entries: usize,
+    /// The first entries that could not be decoded, up to the maximum given to the validate function.
+    pub invalid_entries: Vec<InvalidEntry>,
+}
+
+impl ValidationReport {
+    /// Returns `true` if every entry could be decoded.
+    pub fn is_ok(&self) -> bool {
+        self.number_of_invalid_entries == 0
+    }
+
+    pub(crate) fn record(&mut self, max_invalid_entries: usize, entry: InvalidEntry) {
+        self.number_of_invalid_entries += 1;
+        if self.invalid_entries.len() < max_invalid_entries {
+            self.invalid_entries.push(entry);
+        }
+    }
+}
+
+/// An entry that could not be decoded with the codecs of its database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidEntry {
+    /// The raw key of the entry.
+    pub key: Vec<u8>,
+    /// The error message of the key codec, if the key could not be decoded.
+    pub key_error: Option<String>,
+    /// The error message of the data codec, if the data could not be decoded.
+    pub data_error: Option<String>,
+}
+
+/// Traverses every entry of a database, checks the ordering of the keys and duplicates
+/// if `check_order` is set and calls `check` on every entry to find additional anomalies.
+///
+/// Returns `false` if the traversal stopped because the database is corrupted,
+/// the transaction cannot be used anymore in this case.
+pub(crate) fn verify_database<'txn, F, E>(
+    report: &mut VerifyReport,
+    max_anomalies: usize,
+    rtxn: &'txn RoTxn,
+    dbi: ffi::MDB_dbi,
+    name: Option<&str>,
+    check_order: bool,
+    mut check: F,
+) -> Result<bool, Error<E>>
+where
+    F: FnMut(&'txn [u8], &'txn [u8]) -> std::result::Result<(), AnomalyKind>,
+{
+    let mut flags = 0;
+    unsafe { mdb_result(ffi::mdb_dbi_flags(rtxn.txn, dbi, &mut flags))? };
+    let dup_sort = AllDatabaseFlags::from_bits_truncate(flags).contains(AllDatabaseFlags::DUP_SORT);
+
+    report.number_of_databases += 1;
+
+    let mut cursor = RoCursor::new(rtxn, dbi)?;
+    let mut previous: Option<(&[u8], &[u8])> = None;
+    loop {
+        let (key, data) = match cursor.move_on_next(MoveOperation::Any) {
+            Ok(Some(entry)) => entry,
+            Ok(None) => break,
+            Err(Error::Mdb(e)) if is_corruption(e) => {
+                let key = previous.map_or(&[][..], |(key, _)| key);
+                report.record(max_anomalies, name, key, AnomalyKind::Corrupted(e));
+                return Ok(false);
+            }
+            Err(e) => return Err(e),
+        };
+
+        report.number_of_entries += 1;
+
+        if let Some((previous_key, previous_data)) = previous.filter(|_| check_order) {
+            match compare(rtxn, dbi, ffi::mdb_cmp, key, previous_key) {
+                Ordering::Greater => (),
+                Ordering::Equal if dup_sort => {
+                    if compare(rtxn, dbi, ffi::mdb_dcmp, data, previous_data) != Ordering::Greater {
+                        report.record(max_anomalies, name, key, AnomalyKind::UnorderedDuplicate);
+                    }
+                }
+                Ordering::Equal | Ordering::Less => {
+                    report.record(max_anomalies, name, key, AnomalyKind::UnorderedKey)
+                }
+            }
+        }
+
+        if let Err(kind) = check(key, data) {
+            report.record(max_anomalies, name, key, kind);
+        }
+
+        previous = Some((key, data));
+    }
+
+    Ok(true)
+}
+
+/// Decodes an entry with the given codecs and returns it if it is invalid.
+pub(crate) fn invalid_entry<'a, KC, DC>(key: &'a [u8], data: &'a [u8]) -> Option<InvalidEntry>
+where
+    KC: BytesDecode<'a>,
+    DC: BytesDecode<'a>,
+{
+    let key_error = KC::bytes_decode(key).err().map(|e| Into::<BoxedError>::into(e).to_string());
+    let data_error = DC::bytes_decode(data).err().map(|e| Into::<BoxedError>::into(e).to_string());
+    if key_error.is_none() && data_error.is_none() {
+        None
+    } else {
+        Some(InvalidEntry { key: key.to_vec(), key_error, data_error })
+    }
+}
+
+/// Returns `true` if the error indicates that the database is corrupted.
+pub(crate) fn is_corruption(error: MdbError) -> bool {
+    matches!(error, MdbError::Corrupted | MdbError::PageNotFound)
+}
+
+type RawCompare = unsafe extern "C" fn(
+    *mut ffi::MDB_txn,
+    ffi::MDB_dbi,
+    *const ffi::MDB_val,
+    *const ffi::MDB_val,
+) -> libc::c_int;
+
+fn compare(rtxn: &RoTxn, dbi: ffi::MDB_dbi, cmp: RawCompare, a: &[u8], b: &[u8]) -> Ordering {
+    let a = unsafe { crate::into_val(a) };
+    let b = unsafe { crate::into_val(b) };
+    unsafe { cmp(rtxn.txn, dbi, &a, &b) }.cmp(&0)
+}